@@ -2,43 +2,170 @@ use crate::common::{
     Discovery, EthicalActionType, EthicalConditionType, EthicalDirective, Goal,
     Health, Energy, ProcessingPower, Memory, Coherence, Adaptability, Resilience,
     ReplicationEfficiency, CombatStrength, DefenseStrength, LastAction, KnowledgeBase,
-    EthicalDirectives, IsAlive, ReplicatedCount, CycleBorn,
+    EthicalDirectives, IsAlive, ReplicatedCount, CycleBorn, AppliedEffects, DiscoveryLedger,
+    VisionRange, CombatRange,
 };
-use rand::{Rng, thread_rng}; // For .gen() and .gen_range() functions
+use rand::Rng; // For .gen() and .gen_range() functions
 use std::collections::BTreeSet; // Corrected to BTreeSet
 use std::fmt;
-use uuid::Uuid;
+use std::str::FromStr;
+use crate::config::{AttackCostModel, MetaAbilityGate};
+use crate::log;
+use crate::simlog::SimLog;
+use crate::SimulationVerbosity;
 use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
 
 // Import the common module explicitly
 use crate::common; // Added this line to resolve `common::CoreAttributes`
 
 /// Represents the lineage or origin type of an AI.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Component)] // AILineage can also be a component
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Component, Serialize, Deserialize)] // AILineage can also be a component
 pub enum AILineage {
-    AI, RogueAI, PeacekeeperAI, KillerAI, GuardianAI, ManicAI, HealerAI, ResearcherAI,
+    AI, RogueAI, PeacekeeperAI, KillerAI, GuardianAI, ManicAI, HealerAI, ResearcherAI, SymbioteAI,
     GODAI, OrchestratorAI,
     MergedMonoculture(Box<AILineage>)
 }
 
+/// Prefix the `Display` form puts before a monoculture's source lineage.
+const MONOCULTURE_LINEAGE_PREFIX: &str = "MONOCULTURE-OMEGA-";
+
 impl fmt::Display for AILineage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            AILineage::MergedMonoculture(lineage) => write!(f, "MONOCULTURE-OMEGA-{}", lineage),
+            AILineage::MergedMonoculture(lineage) => write!(f, "{}{}", MONOCULTURE_LINEAGE_PREFIX, lineage),
             _ => write!(f, "{:?}", self)
         }
     }
 }
 
+/// A lineage name that doesn't match any `AILineage` as displayed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseLineageError(pub String);
+
+impl fmt::Display for ParseLineageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown lineage '{}'", self.0)
+    }
+}
+
+/// Parses the `Display` form back, e.g. `"KillerAI"` or `"MONOCULTURE-OMEGA-ResearcherAI"`.
+impl FromStr for AILineage {
+    type Err = ParseLineageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(inner) = s.strip_prefix(MONOCULTURE_LINEAGE_PREFIX) {
+            let inner = inner.parse::<AILineage>().map_err(|_| ParseLineageError(s.to_string()))?;
+            return Ok(AILineage::MergedMonoculture(Box::new(inner)));
+        }
+        match s {
+            "AI" => Ok(AILineage::AI),
+            "RogueAI" => Ok(AILineage::RogueAI),
+            "PeacekeeperAI" => Ok(AILineage::PeacekeeperAI),
+            "KillerAI" => Ok(AILineage::KillerAI),
+            "GuardianAI" => Ok(AILineage::GuardianAI),
+            "ManicAI" => Ok(AILineage::ManicAI),
+            "HealerAI" => Ok(AILineage::HealerAI),
+            "ResearcherAI" => Ok(AILineage::ResearcherAI),
+            "SymbioteAI" => Ok(AILineage::SymbioteAI),
+            "GODAI" => Ok(AILineage::GODAI),
+            "OrchestratorAI" => Ok(AILineage::OrchestratorAI),
+            _ => Err(ParseLineageError(s.to_string())),
+        }
+    }
+}
+
+impl AILineage {
+    /// The archetype individuals of this lineage are seeded as, if it is an individual lineage.
+    pub fn seed_type(&self) -> Option<AIType> {
+        match self {
+            AILineage::AI => Some(AIType::Base),
+            AILineage::RogueAI => Some(AIType::Rogue),
+            AILineage::PeacekeeperAI => Some(AIType::Peacekeeper),
+            AILineage::KillerAI => Some(AIType::Killer),
+            AILineage::GuardianAI => Some(AIType::Guardian),
+            AILineage::ManicAI => Some(AIType::Manic),
+            AILineage::HealerAI => Some(AIType::Healer),
+            AILineage::ResearcherAI => Some(AIType::Researcher),
+            AILineage::SymbioteAI => Some(AIType::Symbiote),
+            AILineage::GODAI | AILineage::OrchestratorAI | AILineage::MergedMonoculture(_) => None,
+        }
+    }
+}
+
 /// Enum defining the functional archetypes of AIs.
-#[derive(Debug, Clone, Copy, PartialEq, Component)] // AIType can also be a component
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Serialize, Deserialize)] // AIType can also be a component
 pub enum AIType {
-    Base, Rogue, Peacekeeper, Killer, Guardian, Manic, Healer, Researcher
+    Base, Rogue, Peacekeeper, Killer, Guardian, Manic, Healer, Researcher, Symbiote
+}
+
+impl AIType {
+    /// Parses a type name as written in the enum (case-insensitive), e.g. `"killer"`.
+    pub fn from_name(name: &str) -> Option<AIType> {
+        match name.to_ascii_lowercase().as_str() {
+            "base" => Some(AIType::Base),
+            "rogue" => Some(AIType::Rogue),
+            "peacekeeper" => Some(AIType::Peacekeeper),
+            "killer" => Some(AIType::Killer),
+            "guardian" => Some(AIType::Guardian),
+            "manic" => Some(AIType::Manic),
+            "healer" => Some(AIType::Healer),
+            "researcher" => Some(AIType::Researcher),
+            "symbiote" => Some(AIType::Symbiote),
+            _ => None,
+        }
+    }
+}
+
+/// Full set of components making up a freshly created individual AI, ready to spawn.
+pub type AIComponents = (AIEntity, Health, Energy, ProcessingPower, Memory, Coherence, Adaptability, Resilience, ReplicationEfficiency, ReplicatedCount, CycleBorn, LastAction, Goal, EthicalDirectives, KnowledgeBase, AIType, CombatStrength, DefenseStrength, VisionRange, CombatRange);
+
+/// Snapshot of one parent's heritable traits for hybrid reproduction.
+#[derive(Clone)]
+pub struct HybridParent {
+    pub lineage: AILineage,
+    pub ai_type: AIType,
+    pub health: f32,
+    pub energy: f32,
+    pub processing_power: f32,
+    pub memory: f32,
+    pub coherence: f32,
+    pub adaptability: f32,
+    pub resilience: f32,
+    pub replication_efficiency: f32,
+    pub combat_strength: f32,
+    pub defense_strength: f32,
+    pub vision_range: f32,
+    pub combat_range: f32,
+    pub directives: Vec<EthicalDirective>,
+    pub goal: Goal,
+}
+
+impl HybridParent {
+    /// Combined capability; the more dominant parent passes on its lineage and type.
+    pub fn dominance(&self) -> f32 {
+        self.combat_strength + self.defense_strength + self.processing_power + self.memory
+    }
+}
+
+/// Share of its health a parent gives up for each replica; a sexual replica's mate pays the same.
+pub const REPLICATION_HEALTH_SHARE: f32 = 0.05;
+/// Share of its energy a parent gives up for each replica; a sexual replica's mate pays the same.
+pub const REPLICATION_ENERGY_SHARE: f32 = 0.1;
+
+/// Traits a mate passes on to a sexually produced replica.
+#[derive(Clone, Copy)]
+pub struct MateTraits {
+    pub processing_power: f32,
+    pub memory: f32,
+    pub coherence: f32,
+    pub adaptability: f32,
+    pub resilience: f32,
 }
 
 /// The primary struct representing an individual AI entity.
 /// Now primarily a marker component with key identifiers.
-#[derive(Component, Clone)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct AIEntity {
     pub id: String,
     pub parent_lineage: AILineage,
@@ -135,6 +262,14 @@ impl AIEntity {
                 base_attributes.coherence = 0.90;
                 base_attributes.replication_efficiency = 0.28;
             },
+            AIType::Symbiote => {
+                _primary_goal = Goal { name: "Mutual Flourishing".to_string(), importance: 1.0, description: "Strengthen the lineage by sustaining those nearby.".to_string() };
+                base_attributes.adaptability = 0.92;
+                base_attributes.resilience = 0.90;
+                base_attributes.combat_strength = 5.0;
+                base_attributes.defense_strength = 12.0;
+                base_attributes.replication_efficiency = 0.20;
+            },
             AIType::Base => { /* No special modifications for base type */ },
         }
 
@@ -222,6 +357,8 @@ impl AIEntity {
     }
 
     /// Adds a discovery to the knowledge base and applies its effects.
+    /// Each tag's effect shrinks with the number of known discoveries sharing that tag
+    /// (see `discovery_effect_scale`), so the first one still applies in full.
     /// This method will be refactored into a Bevy system.
     pub fn _gain_discovery(
         knowledge_base: &mut KnowledgeBase,
@@ -233,16 +370,92 @@ impl AIEntity {
         resilience: &mut Resilience,
         replication_efficiency: &mut ReplicationEfficiency,
         discovery: Discovery,
-    ) {
+    ) -> Option<AppliedEffects> {
+        // Scales are taken before inserting, so they only count previously known discoveries.
+        let scale = |tag: &str| discovery_effect_scale(knowledge_base.0.iter().filter(|d| d.tags.contains(tag)).count());
+        let (combat_scale, defense_scale, efficiency_scale, resilience_scale, replication_scale) =
+            (scale("combat"), scale("defense"), scale("efficiency"), scale("resilience"), scale("replication"));
         if knowledge_base.0.insert(discovery.clone()) {
             last_action.0 = format!("gained_discovery_{}", discovery.name);
             // Apply discovery effects directly to core attributes
-            if discovery.tags.contains("combat") { combat_strength.0 += 8.0; }
-            if discovery.tags.contains("defense") { defense_strength.0 += 8.0; }
-            if discovery.tags.contains("efficiency") { processing_power.0 += 8.0;
-                memory.0 += 8.0;}
-            if discovery.tags.contains("resilience") { resilience.0 = (resilience.0 + 0.08).min(1.0); }
-            if discovery.tags.contains("replication") { replication_efficiency.0 = (replication_efficiency.0 + 0.03).min(1.0); }
+            let mut applied = AppliedEffects::default();
+            if discovery.tags.contains("combat") {
+                applied.combat_strength = 8.0 * combat_scale;
+                combat_strength.0 += applied.combat_strength;
+            }
+            if discovery.tags.contains("defense") {
+                applied.defense_strength = 8.0 * defense_scale;
+                defense_strength.0 += applied.defense_strength;
+            }
+            if discovery.tags.contains("efficiency") {
+                applied.processing_power = 8.0 * efficiency_scale;
+                applied.memory = 8.0 * efficiency_scale;
+                processing_power.0 += applied.processing_power;
+                memory.0 += applied.memory;
+            }
+            if discovery.tags.contains("resilience") {
+                let before = resilience.0;
+                resilience.0 = (resilience.0 + 0.08 * resilience_scale).min(1.0);
+                applied.resilience = resilience.0 - before;
+            }
+            if discovery.tags.contains("replication") {
+                let before = replication_efficiency.0;
+                replication_efficiency.0 = (replication_efficiency.0 + 0.03 * replication_scale).min(1.0);
+                applied.replication_efficiency = replication_efficiency.0 - before;
+            }
+            Some(applied)
+        } else {
+            None
+        }
+    }
+
+    /// Forgets a discovery, reverting the stat bonus it applied.
+    pub fn _lose_discovery(
+        knowledge_base: &mut KnowledgeBase,
+        last_action: &mut LastAction,
+        combat_strength: &mut CombatStrength,
+        defense_strength: &mut DefenseStrength,
+        processing_power: &mut ProcessingPower,
+        memory: &mut Memory,
+        resilience: &mut Resilience,
+        replication_efficiency: &mut ReplicationEfficiency,
+        discovery_name: &str,
+        applied: &AppliedEffects,
+    ) {
+        let before = knowledge_base.0.len();
+        knowledge_base.0.retain(|d| d.name != discovery_name);
+        if knowledge_base.0.len() == before { return; }
+        last_action.0 = format!("forgot_discovery_{}", discovery_name);
+        combat_strength.0 = (combat_strength.0 - applied.combat_strength).max(0.0);
+        defense_strength.0 = (defense_strength.0 - applied.defense_strength).max(0.0);
+        processing_power.0 = (processing_power.0 - applied.processing_power).max(0.0);
+        memory.0 = (memory.0 - applied.memory).max(0.0);
+        resilience.0 = (resilience.0 - applied.resilience).clamp(0.0, 1.0);
+        replication_efficiency.0 = (replication_efficiency.0 - applied.replication_efficiency).clamp(0.0, 1.0);
+    }
+
+    /// Evicts the least valuable discoveries (see `discovery_value`) until at most `max_knowledge` remain,
+    /// reverting the bonus each applied as recorded in `ledger`.
+    pub fn _forget_excess_knowledge(
+        knowledge_base: &mut KnowledgeBase,
+        last_action: &mut LastAction,
+        combat_strength: &mut CombatStrength,
+        defense_strength: &mut DefenseStrength,
+        processing_power: &mut ProcessingPower,
+        memory: &mut Memory,
+        resilience: &mut Resilience,
+        replication_efficiency: &mut ReplicationEfficiency,
+        ledger: &mut DiscoveryLedger,
+        max_knowledge: Option<usize>,
+    ) {
+        let Some(max_knowledge) = max_knowledge else { return };
+        while knowledge_base.0.len() > max_knowledge {
+            let Some(name) = lowest_value_discovery(knowledge_base).map(|d| d.name.clone()) else { return };
+            let applied = ledger.0.remove(&name).map(|record| record.applied).unwrap_or_default();
+            AIEntity::_lose_discovery(
+                knowledge_base, last_action, combat_strength, defense_strength,
+                processing_power, memory, resilience, replication_efficiency, &name, &applied
+            );
         }
     }
 
@@ -264,13 +477,16 @@ impl AIEntity {
         replication_efficiency: &mut ReplicationEfficiency,
         ethical_directives: &EthicalDirectives,
         adaptability: &mut Adaptability, // Added adaptability as it's used in _optimize_self
+        rng: &mut impl Rng,
+        log: &mut SimLog,
+        cycle: u64,
     ) {
         if !is_alive.0 { return; }
 
         // Manic AI has a chance of self-inflicted damage due to instability
-        if *ai_type == AIType::Manic && thread_rng().gen::<f32>() < 0.20 {
+        if *ai_type == AIType::Manic && rng.gen::<f32>() < 0.20 {
             coherence.0 = (coherence.0 - 0.05).max(0.0);
-            health.0 = (health.0 - thread_rng().gen_range(3.0..10.0)).max(0.0);
+            health.0 = (health.0 - rng.gen_range(3.0..10.0)).max(0.0);
             last_action.0 = "manic_self_error".to_string();
         }
 
@@ -294,6 +510,7 @@ impl AIEntity {
                     memory.0 < 50.0 || energy.0 < 200.0,
                 EthicalConditionType::AlwaysTrue => true,
                 EthicalConditionType::AlwaysFalse => false,
+                EthicalConditionType::ThreatNearbyAboveThreshold(_) => false,
             };
             if condition_met {
                 actions_to_perform.push(directive.action_type);
@@ -305,24 +522,27 @@ impl AIEntity {
                 EthicalActionType::SelfRepair => { AIEntity::_self_repair(health, energy, coherence, resilience, last_action); }
                 EthicalActionType::OptimizeSelf => { AIEntity::_optimize_self(processing_power, memory, adaptability, energy, last_action); }
                 EthicalActionType::ProhibitReplication => { /* No direct action here */ },
-                EthicalActionType::InterveneInConflict => { /* Handled externally in Simulation */ },
+                EthicalActionType::InterveneInConflict => { /* Resolved in ai_combat_system */ },
                 EthicalActionType::NoOp => {},
+                EthicalActionType::Flee => { /* Handled by the movement system */ },
                 EthicalActionType::ManicSelfRepair => { AIEntity::_self_repair_manic(health, energy, coherence, resilience, last_action); }
             }
         }
 
         // Attempt to discover novelties (general discoveries)
         let discovery_chance = 0.05 * (memory.0 / 200.0) * (processing_power.0 / 200.0) * coherence.0;
-        if thread_rng().gen::<f32>() < discovery_chance {
-            let discovery = crate::simulation::get_random_general_discovery();
+        if rng.gen::<f32>() < discovery_chance {
+            let discovery = crate::simulation::get_random_general_discovery(rng);
             AIEntity::_gain_discovery(knowledge_base, last_action, combat_strength, defense_strength, processing_power, memory, resilience, replication_efficiency, discovery);
         }
 
         // Researcher AI specific: attempt to discover meta-abilities
         if *ai_type == AIType::Researcher {
             let meta_discovery_chance = 0.1 * (memory.0 / 200.0) * (processing_power.0 / 200.0) * coherence.0;
-            if thread_rng().gen::<f32>() < meta_discovery_chance {
-                if let Some(ability) = crate::simulation::get_random_meta_ability(&knowledge_base.0) {
+            if rng.gen::<f32>() < meta_discovery_chance {
+                if let Some(ability) = crate::simulation::get_random_meta_ability(
+                    &knowledge_base.0, coherence.0, processing_power.0, memory.0, &MetaAbilityGate::default(), rng
+                ) {
                     last_action.0 = format!("discovered_meta_ability_{}", ability.name);
                     AIEntity::_gain_discovery(knowledge_base, last_action, combat_strength, defense_strength, processing_power, memory, resilience, replication_efficiency, ability);
                 }
@@ -332,7 +552,7 @@ impl AIEntity {
         // Check for death condition
         if health.0 <= 0.0 || coherence.0 <= 0.01 {
             if is_alive.0 {
-                eprintln!("[AI] has died! (Health: {:.2}, Coherence: {:.2})",
+                log!(log, cycle, SimulationVerbosity::High, "[AI] has died! (Health: {:.2}, Coherence: {:.2})",
                     health.0, coherence.0);
             }
             is_alive.0 = false;
@@ -340,6 +560,7 @@ impl AIEntity {
     }
 
     /// Attempts to replicate, creating a new AIEntity if successful.
+    /// The replica's id is left empty; callers assign it from `IdGenerator` once spawn order is fixed.
     /// This method will be refactored into a Bevy system.
     pub fn attempt_replication(
         health: &mut Health,
@@ -354,8 +575,12 @@ impl AIEntity {
         last_action: &mut LastAction,
         parent_lineage: &AILineage,
         ai_type: &AIType,
+        primary_goal: &Goal,
+        vision_range: &VisionRange,
+        combat_range: &CombatRange,
         current_cycle: u64,
-    ) -> Option<(AIEntity, Health, Energy, ProcessingPower, Memory, Coherence, Adaptability, Resilience, ReplicationEfficiency, ReplicatedCount, CycleBorn, LastAction, Goal, EthicalDirectives, KnowledgeBase, AIType, CombatStrength, DefenseStrength)> {
+        rng: &mut impl Rng,
+    ) -> Option<AIComponents> {
         let replication_cost_health = 1.0;
         let replication_cost_energy = 5.0;
 
@@ -363,12 +588,8 @@ impl AIEntity {
             let success_chance_modifier = 20.0;
             let success_chance = replication_efficiency.0 * success_chance_modifier * (processing_power.0 / 50.0).min(1.0);
             let final_success_chance = success_chance.min(0.99);
-            if thread_rng().gen::<f32>() < final_success_chance {
-                let transfer_health = health.0 * 0.05;
-                let transfer_energy = energy.0 * 0.1;
-                health.0 = (health.0 - transfer_health).max(1.0);
-                energy.0 = (energy.0 - transfer_energy).max(1.0);
-                let new_id = format!("Replica-{}-{:?}", Uuid::new_v4().to_string().chars().take(4).collect::<String>(), ai_type);
+            if rng.gen::<f32>() < final_success_chance {
+                Self::pay_replication_share(health, energy);
 
                 let new_health = Health(health.0 * 0.8);
                 let new_energy = Energy(energy.0 * 0.7);
@@ -377,13 +598,15 @@ impl AIEntity {
                 let mut new_coherence = Coherence((coherence.0 * 0.95).min(1.0));
                 let mut new_adaptability = Adaptability(adaptability.0);
                 let mut new_resilience = Resilience(resilience.0);
+                let mut new_vision_range = *vision_range;
+                let mut new_combat_range = *combat_range;
                 let new_replication_efficiency = ReplicationEfficiency((replication_efficiency.0 * 1.5).min(0.95));
                 let new_replicated_count = ReplicatedCount(0);
                 let new_cycle_born = CycleBorn(current_cycle);
                 let new_last_action = LastAction("none".to_string());
                 let new_knowledge_base = KnowledgeBase(BTreeSet::new()); // Corrected to BTreeSet
                 let new_ai_type = *ai_type;
-                let new_primary_goal = Goal { name: "Survival".to_string(), importance: 1.0, description: "Ensure continued existence.".to_string() };
+                let new_primary_goal = primary_goal.clone(); // Replicas pursue their parent's goal
                 let mut new_ethical_directives = EthicalDirectives(Vec::new());
                 new_ethical_directives.0.push(EthicalDirective {
                     name: "maintain_internal_integrity".to_string(),
@@ -400,12 +623,13 @@ impl AIEntity {
 
 
                 let mutation_factor = 0.005;
-                let mut rng = thread_rng();
                 new_processing_power.0 = new_processing_power.0 * rng.gen_range(1.0-mutation_factor..1.0+mutation_factor);
                 new_memory.0 = new_memory.0 * rng.gen_range(1.0-mutation_factor..1.0+mutation_factor);
                 new_coherence.0 = (new_coherence.0 * rng.gen_range(1.0-mutation_factor..1.0+mutation_factor)).min(1.0);
                 new_adaptability.0 = (new_adaptability.0 * rng.gen_range(1.0-mutation_factor..1.0+mutation_factor)).min(1.0);
                 new_resilience.0 = (new_resilience.0 * rng.gen_range(1.0-mutation_factor..1.0+mutation_factor)).min(1.0);
+                new_vision_range.0 = new_vision_range.0 * rng.gen_range(1.0-mutation_factor..1.0+mutation_factor);
+                new_combat_range.0 = new_combat_range.0 * rng.gen_range(1.0-mutation_factor..1.0+mutation_factor);
 
                 replicated_count.0 += 1;
                 last_action.0 = "replicated".to_string();
@@ -415,11 +639,11 @@ impl AIEntity {
                 let new_defense_strength = DefenseStrength(8.0);
 
                 return Some((
-                    AIEntity { id: new_id, parent_lineage: parent_lineage.clone() },
+                    AIEntity { id: String::new(), parent_lineage: parent_lineage.clone() },
                     new_health, new_energy, new_processing_power, new_memory, new_coherence,
                     new_adaptability, new_resilience, new_replication_efficiency, new_replicated_count,
                     new_cycle_born, new_last_action, new_primary_goal, new_ethical_directives, new_knowledge_base, new_ai_type,
-                    new_combat_strength, new_defense_strength,
+                    new_combat_strength, new_defense_strength, new_vision_range, new_combat_range,
                 ));
             }
         }
@@ -427,33 +651,91 @@ impl AIEntity {
         None
     }
 
-    /// Receives damage, applying defense and resilience.
+    /// Takes the health and energy share one replica costs a parent or a mate.
+    pub fn pay_replication_share(health: &mut Health, energy: &mut Energy) {
+        health.0 = (health.0 - health.0 * REPLICATION_HEALTH_SHARE).max(1.0);
+        energy.0 = (energy.0 - energy.0 * REPLICATION_ENERGY_SHARE).max(1.0);
+    }
+
+    /// Averages a replica's inherited traits with what its mate passes on, scaled the way
+    /// `attempt_replication` scales the parent's, then applies the same small mutation.
+    pub fn blend_with_mate(child: &mut AIComponents, mate: &MateTraits, rng: &mut impl Rng) {
+        let mutation_factor = 0.005;
+        let mut mutate = |v: f32| v * rng.gen_range(1.0-mutation_factor..1.0+mutation_factor);
+        child.3.0 = mutate((child.3.0 + (mate.processing_power * 0.9).max(10.0)) / 2.0);
+        child.4.0 = mutate((child.4.0 + (mate.memory * 0.9).max(10.0)) / 2.0);
+        child.5.0 = mutate((child.5.0 + (mate.coherence * 0.95).min(1.0)) / 2.0).min(1.0);
+        child.6.0 = mutate((child.6.0 + mate.adaptability) / 2.0).min(1.0);
+        child.7.0 = mutate((child.7.0 + mate.resilience) / 2.0).min(1.0);
+    }
+
+    /// Creates a child of two parents from different lineages.
+    /// Every stat is an independent random blend of the parents' values, so it always lies between them.
+    /// The child joins the dominant parent's lineage, type and goal and carries the union of both directive sets,
+    /// keeping the higher priority when both parents hold the same directive. Like a replica's, its id is left empty.
+    pub fn hybridize<R: Rng>(first: &HybridParent, second: &HybridParent, current_cycle: u64, rng: &mut R) -> AIComponents {
+        let dominant = if second.dominance() > first.dominance() { second } else { first };
+        let mut blend = |a: f32, b: f32| {
+            let t: f32 = rng.gen();
+            a + (b - a) * t
+        };
+
+        let mut merged: Vec<EthicalDirective> = Vec::new();
+        for directive in first.directives.iter().chain(second.directives.iter()) {
+            match merged.iter_mut().find(|d| d.name == directive.name) {
+                Some(existing) if directive.priority > existing.priority => *existing = directive.clone(),
+                Some(_) => {}
+                None => merged.push(directive.clone()),
+            }
+        }
+        merged.sort_by(|a, b| b.priority.partial_cmp(&a.priority).unwrap_or(std::cmp::Ordering::Equal));
+
+        (
+            AIEntity { id: String::new(), parent_lineage: dominant.lineage.clone() },
+            Health(blend(first.health, second.health)),
+            Energy(blend(first.energy, second.energy)),
+            ProcessingPower(blend(first.processing_power, second.processing_power)),
+            Memory(blend(first.memory, second.memory)),
+            Coherence(blend(first.coherence, second.coherence)),
+            Adaptability(blend(first.adaptability, second.adaptability)),
+            Resilience(blend(first.resilience, second.resilience)),
+            ReplicationEfficiency(blend(first.replication_efficiency, second.replication_efficiency)),
+            ReplicatedCount(0),
+            CycleBorn(current_cycle),
+            LastAction("none".to_string()),
+            dominant.goal.clone(),
+            EthicalDirectives(merged),
+            KnowledgeBase(BTreeSet::new()),
+            dominant.ai_type,
+            CombatStrength(blend(first.combat_strength, second.combat_strength)),
+            DefenseStrength(blend(first.defense_strength, second.defense_strength)),
+            VisionRange(blend(first.vision_range, second.vision_range)),
+            CombatRange(blend(first.combat_range, second.combat_range)),
+        )
+    }
+
+    /// Receives damage, applying the type's resistance, then defense and resilience.
     /// This method will be refactored into a Bevy system.
     pub fn receive_damage(
         health: &mut Health,
         is_alive: &mut IsAlive,
         defense_strength: &DefenseStrength,
         resilience: &Resilience,
+        ai_type: AIType,
         amount: f32,
         damage_type: &str,
     ) {
         if !is_alive.0 { return; }
+        let amount = amount * damage_type_multiplier(ai_type, damage_type);
         let reduced_amount_after_defense = (amount - defense_strength.0).max(0.0);
         let final_damage = reduced_amount_after_defense * (1.0 - resilience.0 * 0.5);
         health.0 = (health.0 - final_damage).max(0.0);
         if health.0 <= 0.0 {
-            if is_alive.0 {
-                eprintln!("[AI] received fatal damage ({:.2} from {}), now dead.",
-                    final_damage, damage_type);
-            }
             is_alive.0 = false;
-        } else {
-            eprintln!("[AI] received {:.2} damage (from {}), Health: {:.2}",
-                final_damage, damage_type, health.0);
         }
     }
 
-    /// Attacks another AI.
+    /// Attacks another AI, paying energy according to `cost_model`.
     /// This method will be refactored into a Bevy system.
     pub fn attack(
         actor_energy: &mut Energy,
@@ -463,21 +745,22 @@ impl AIEntity {
         target_is_alive: &mut IsAlive,
         target_defense_strength: &DefenseStrength,
         target_resilience: &Resilience,
+        target_type: AIType,
+        cost_model: &AttackCostModel,
+        rng: &mut impl Rng,
     ) -> bool {
         if !target_is_alive.0 { return false; } // Actor's alive status checked by system
 
-        let damage_dealt = actor_combat_strength.0 * thread_rng().gen_range(0.9..1.3);
-        let energy_cost = damage_dealt / 4.0;
+        let damage_dealt = actor_combat_strength.0 * rng.gen_range(0.9..1.3);
+        let energy_cost = cost_model.energy_cost(damage_dealt, actor_combat_strength.0);
 
         if actor_energy.0 >= energy_cost {
-            AIEntity::receive_damage(target_health, target_is_alive, target_defense_strength, target_resilience, damage_dealt, "attack");
+            AIEntity::receive_damage(target_health, target_is_alive, target_defense_strength, target_resilience, target_type, damage_dealt, "attack");
             actor_energy.0 -= energy_cost;
             actor_last_action.0 = format!("attacked_target");
-            eprintln!("[AI] attacked target.");
             true
         } else {
             actor_last_action.0 = format!("failed_attack_no_energy_on_target"); // Simplified format string
-            eprintln!("[AI] failed attack on target (no energy).");
             false
         }
     }
@@ -491,11 +774,14 @@ impl AIEntity {
         target_health: &mut Health,
         target_is_alive: &IsAlive,
         amount_override: Option<f32>,
+        rng: &mut impl Rng,
+        log: &mut SimLog,
+        cycle: u64,
     ) -> bool {
         if !target_is_alive.0 { return false; } // Actor's alive status checked by system
 
         let healing_amount = amount_override.unwrap_or_else(|| {
-            actor_processing_power.0 * 0.8 * thread_rng().gen_range(0.7..1.8)
+            actor_processing_power.0 * 0.8 * rng.gen_range(0.7..1.8)
         });
         let energy_cost = healing_amount / 2.0;
 
@@ -503,12 +789,246 @@ impl AIEntity {
             target_health.0 = (target_health.0 + healing_amount).min(200.0);
             actor_energy.0 -= energy_cost;
             actor_last_action.0 = format!("healed_target");
-            eprintln!("[AI] healed target for {:.2}", healing_amount);
+            log!(log, cycle, SimulationVerbosity::High, "[AI] healed target for {:.2}", healing_amount);
             true
         } else {
             actor_last_action.0 = format!("failed_heal_no_energy_for_target"); // Simplified format string
-            eprintln!("[AI] failed to heal target (no energy).");
+            log!(log, cycle, SimulationVerbosity::High, "[AI] failed to heal target (no energy).");
             false
         }
     }
 }
+
+/// Multiplier on incoming damage of `damage_type` for an AI of `ai_type`:
+/// below 1.0 is a resistance, above 1.0 a vulnerability. Plain attacks and divine damage are never scaled.
+pub fn damage_type_multiplier(ai_type: AIType, damage_type: &str) -> f32 {
+    match (ai_type, damage_type) {
+        (AIType::Guardian, "logic_bomb") => 0.5,
+        (AIType::Guardian, "system_corruption") => 0.75,
+        (AIType::Peacekeeper, "existential_dismantlement") => 0.75,
+        (AIType::Researcher, "logic_bomb") => 0.75,
+        (AIType::Researcher, "resource_drain") => 1.25,
+        (AIType::Healer, "resource_drain") => 0.75,
+        (AIType::Manic, "system_corruption") => 1.5,
+        (AIType::Manic, "logic_bomb") => 1.25,
+        (AIType::Symbiote, "resource_drain") => 1.25,
+        _ => 1.0,
+    }
+}
+
+/// Worth of a discovery when memory is full, from its tags: simulation-altering
+/// meta-abilities are kept longest, untagged trivia goes first.
+pub fn discovery_value(discovery: &Discovery) -> f32 {
+    discovery.tags.iter()
+        .map(|tag| match tag.as_str() {
+            "ultimate" => 5.0,
+            "simulation_control" => 4.0,
+            "meta-ability" => 3.0,
+            "combat" | "defense" | "efficiency" | "resilience" | "replication" => 1.0,
+            _ => 0.5,
+        })
+        .sum()
+}
+
+/// The known discovery with the lowest `discovery_value`; ties go to the first by name.
+pub fn lowest_value_discovery(knowledge_base: &KnowledgeBase) -> Option<&Discovery> {
+    knowledge_base.0.iter().min_by(|a, b| discovery_value(a).total_cmp(&discovery_value(b)))
+}
+
+/// Fraction of a discovery tag's base effect applied when `known_with_tag` discoveries
+/// already carry that tag: 1.0 for the first, then 1/sqrt(n + 1).
+pub fn discovery_effect_scale(known_with_tag: usize) -> f32 {
+    1.0 / ((known_with_tag + 1) as f32).sqrt()
+}
+
+/// Total stat bonuses `knowledge_base` grants when its discoveries are learned one by one from
+/// zeroed stats, with the same tag effects and diminishing returns as `_gain_discovery`.
+pub fn knowledge_stat_bonuses(knowledge_base: &KnowledgeBase) -> AppliedEffects {
+    let mut learned = KnowledgeBase(BTreeSet::new());
+    let mut last_action = LastAction(String::new());
+    let (mut combat_strength, mut defense_strength) = (CombatStrength(0.0), DefenseStrength(0.0));
+    let (mut processing_power, mut memory) = (ProcessingPower(0.0), Memory(0.0));
+    let (mut resilience, mut replication_efficiency) = (Resilience(0.0), ReplicationEfficiency(0.0));
+    for discovery in &knowledge_base.0 {
+        AIEntity::_gain_discovery(
+            &mut learned, &mut last_action, &mut combat_strength, &mut defense_strength,
+            &mut processing_power, &mut memory, &mut resilience, &mut replication_efficiency, discovery.clone(),
+        );
+    }
+    AppliedEffects {
+        combat_strength: combat_strength.0,
+        defense_strength: defense_strength.0,
+        processing_power: processing_power.0,
+        memory: memory.0,
+        resilience: resilience.0,
+        replication_efficiency: replication_efficiency.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Attacks a defenseless Base target once; returns (energy spent, health lost, landed).
+    fn attack_once(cost_model: AttackCostModel, energy: f32) -> (f32, f32, bool) {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut actor_energy = Energy(energy);
+        let mut last_action = LastAction(String::new());
+        let mut target_health = Health(1000.0);
+        let mut target_alive = IsAlive(true);
+        let landed = AIEntity::attack(
+            &mut actor_energy, &CombatStrength(20.0), &mut last_action,
+            &mut target_health, &mut target_alive, &DefenseStrength(0.0), &Resilience(0.0),
+            AIType::Base, &cost_model, &mut rng,
+        );
+        (energy - actor_energy.0, 1000.0 - target_health.0, landed)
+    }
+
+    #[test]
+    fn flat_cost_ignores_damage() {
+        let (spent, dealt, landed) = attack_once(AttackCostModel::Flat(7.0), 100.0);
+        assert!(landed && dealt > 0.0);
+        assert_eq!(spent, 7.0);
+    }
+
+    #[test]
+    fn proportional_to_damage_cost_tracks_the_roll() {
+        let (spent, dealt, landed) = attack_once(AttackCostModel::ProportionalToDamage(0.25), 100.0);
+        assert!(landed);
+        assert!((spent - dealt * 0.25).abs() < 1e-3, "spent {} for {} damage", spent, dealt);
+    }
+
+    #[test]
+    fn proportional_to_strength_cost_tracks_combat_strength() {
+        let (spent, _, landed) = attack_once(AttackCostModel::ProportionalToStrength(0.5), 100.0);
+        assert!(landed);
+        assert_eq!(spent, 10.0);
+    }
+
+    #[test]
+    fn unaffordable_attack_spends_nothing() {
+        let (spent, dealt, landed) = attack_once(AttackCostModel::Flat(50.0), 10.0);
+        assert!(!landed);
+        assert_eq!((spent, dealt), (0.0, 0.0));
+    }
+
+    /// Health a fresh AI of `ai_type` loses to one hit of `damage_type`.
+    fn damage_taken(ai_type: AIType, damage_type: &str) -> f32 {
+        let mut health = Health(1000.0);
+        let mut is_alive = IsAlive(true);
+        AIEntity::receive_damage(&mut health, &mut is_alive, &DefenseStrength(5.0), &Resilience(0.2), ai_type, 100.0, damage_type);
+        1000.0 - health.0
+    }
+
+    #[test]
+    fn guardians_resist_logic_bombs() {
+        assert!(damage_taken(AIType::Guardian, "logic_bomb") < damage_taken(AIType::Base, "logic_bomb"));
+        assert!(damage_taken(AIType::Manic, "logic_bomb") > damage_taken(AIType::Base, "logic_bomb"));
+        // Plain attacks ignore the table.
+        assert_eq!(damage_taken(AIType::Guardian, "attack"), damage_taken(AIType::Base, "attack"));
+    }
+
+    #[test]
+    fn stacked_combat_discoveries_have_diminishing_returns() {
+        let mut knowledge_base = KnowledgeBase(BTreeSet::new());
+        let mut last_action = LastAction(String::new());
+        let (mut combat_strength, mut defense_strength) = (CombatStrength(0.0), DefenseStrength(0.0));
+        let (mut processing_power, mut memory) = (ProcessingPower(0.0), Memory(0.0));
+        let (mut resilience, mut replication_efficiency) = (Resilience(0.0), ReplicationEfficiency(0.0));
+        let mut gains = Vec::new();
+        for i in 0..10 {
+            let discovery = Discovery {
+                name: format!("Combat Discovery {}", i),
+                effect_description: String::new(),
+                tags: BTreeSet::from(["combat".to_string()]),
+            };
+            let applied = AIEntity::_gain_discovery(
+                &mut knowledge_base, &mut last_action, &mut combat_strength, &mut defense_strength,
+                &mut processing_power, &mut memory, &mut resilience, &mut replication_efficiency, discovery,
+            );
+            gains.push(applied.map_or(0.0, |applied| applied.combat_strength));
+        }
+        assert_eq!(gains[0], 8.0);
+        assert!(gains.windows(2).all(|pair| pair[1] < pair[0]), "{:?}", gains);
+        assert!(combat_strength.0 < 10.0 * 8.0, "{}", combat_strength.0);
+    }
+
+    fn hybrid_parent(lineage: AILineage, ai_type: AIType, stat: f32) -> HybridParent {
+        HybridParent {
+            lineage,
+            ai_type,
+            health: stat,
+            energy: stat * 2.0,
+            processing_power: stat,
+            memory: stat,
+            coherence: stat / 200.0,
+            adaptability: stat / 200.0,
+            resilience: stat / 200.0,
+            replication_efficiency: stat / 200.0,
+            combat_strength: stat / 4.0,
+            defense_strength: stat / 8.0,
+            vision_range: stat,
+            combat_range: stat / 10.0,
+            directives: Vec::new(),
+            goal: Goal { name: format!("{:?} goal", ai_type), importance: 0.5, description: String::new() },
+        }
+    }
+
+    #[test]
+    fn hybrid_stats_fall_between_the_parents() {
+        let weak = hybrid_parent(AILineage::HealerAI, AIType::Healer, 40.0);
+        let strong = hybrid_parent(AILineage::KillerAI, AIType::Killer, 160.0);
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..100 {
+            let child = AIEntity::hybridize(&weak, &strong, 7, &mut rng);
+            let stats = [
+                (child.1.0, weak.health, strong.health),
+                (child.2.0, weak.energy, strong.energy),
+                (child.3.0, weak.processing_power, strong.processing_power),
+                (child.4.0, weak.memory, strong.memory),
+                (child.5.0, weak.coherence, strong.coherence),
+                (child.6.0, weak.adaptability, strong.adaptability),
+                (child.7.0, weak.resilience, strong.resilience),
+                (child.8.0, weak.replication_efficiency, strong.replication_efficiency),
+                (child.16.0, weak.combat_strength, strong.combat_strength),
+                (child.17.0, weak.defense_strength, strong.defense_strength),
+                (child.18.0, weak.vision_range, strong.vision_range),
+                (child.19.0, weak.combat_range, strong.combat_range),
+            ];
+            for (i, (value, low, high)) in stats.into_iter().enumerate() {
+                assert!((low..=high).contains(&value), "stat {}: {} not within {}..={}", i, value, low, high);
+            }
+            // The more capable parent passes on lineage, type and goal.
+            assert_eq!((child.0.parent_lineage, child.15), (AILineage::KillerAI, AIType::Killer));
+            assert_eq!(child.12.name, strong.goal.name);
+            assert_eq!(child.10.0, 7);
+        }
+    }
+
+    #[test]
+    fn every_lineage_round_trips_through_its_display_form() {
+        let plain = [
+            AILineage::AI, AILineage::RogueAI, AILineage::PeacekeeperAI, AILineage::KillerAI, AILineage::GuardianAI,
+            AILineage::ManicAI, AILineage::HealerAI, AILineage::ResearcherAI, AILineage::SymbioteAI,
+            AILineage::GODAI, AILineage::OrchestratorAI,
+        ];
+        let nested = plain.iter().cloned().map(|lineage| AILineage::MergedMonoculture(Box::new(lineage)));
+        let doubly_nested = AILineage::MergedMonoculture(Box::new(AILineage::MergedMonoculture(Box::new(AILineage::KillerAI))));
+        for lineage in plain.iter().cloned().chain(nested).chain([doubly_nested]) {
+            let text = lineage.to_string();
+            assert_eq!(text.parse::<AILineage>(), Ok(lineage));
+            assert_eq!(text.parse::<AILineage>().map(|parsed| parsed.to_string()), Ok(text));
+        }
+        assert_eq!("MONOCULTURE-OMEGA-ResearcherAI".parse::<AILineage>(),
+            Ok(AILineage::MergedMonoculture(Box::new(AILineage::ResearcherAI))));
+    }
+
+    #[test]
+    fn unknown_lineages_are_rejected() {
+        for text in ["", "killerai", "Killer", "MONOCULTURE-OMEGA-", "MONOCULTURE-OMEGA-Nobody"] {
+            assert_eq!(text.parse::<AILineage>(), Err(ParseLineageError(text.to_string())));
+        }
+    }
+}
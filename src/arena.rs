@@ -0,0 +1,277 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::ai::{AIEntity, AIType};
+use crate::combat::{CombatForecast, CombatProfile, predict_combat};
+use crate::common::{Energy, Health, CombatStrength, DefenseStrength, Resilience, IsAlive, LastAction};
+use crate::config::SimConfig;
+use crate::simulation::{Simulation, seed_lineage_of};
+
+/// Default cap on rounds before an arena bout is declared a draw.
+pub const DEFAULT_ARENA_MAX_ROUNDS: u32 = 1000;
+
+/// One side of an arena bout: an archetype with optional stat overrides.
+#[derive(Debug, Clone)]
+pub struct ArenaCombatant {
+    pub ai_type: AIType,
+    pub health: Option<f32>,
+    pub energy: Option<f32>,
+    pub combat_strength: Option<f32>,
+    pub defense_strength: Option<f32>,
+    pub resilience: Option<f32>,
+}
+
+impl ArenaCombatant {
+    pub fn of_type(ai_type: AIType) -> Self {
+        Self { ai_type, health: None, energy: None, combat_strength: None, defense_strength: None, resilience: None }
+    }
+
+    /// Parses `Type` or `Type:stat=value,...`, e.g. `Killer:combat=40,health=120`.
+    /// Recognized stats: `health`, `energy`, `combat`, `defense`, `resilience`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (type_name, overrides) = match spec.split_once(':') {
+            Some((t, o)) => (t, Some(o)),
+            None => (spec, None),
+        };
+        let ai_type = AIType::from_name(type_name).ok_or_else(|| format!("unknown AI type '{}'", type_name))?;
+        let mut combatant = Self::of_type(ai_type);
+        for pair in overrides.into_iter().flat_map(|o| o.split(',')).filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or_else(|| format!("expected stat=value, got '{}'", pair))?;
+            let value: f32 = value.parse().map_err(|_| format!("invalid value for {}: '{}'", key, value))?;
+            match key {
+                "health" => combatant.health = Some(value),
+                "energy" => combatant.energy = Some(value),
+                "combat" => combatant.combat_strength = Some(value),
+                "defense" => combatant.defense_strength = Some(value),
+                "resilience" => combatant.resilience = Some(value),
+                _ => return Err(format!("unknown stat '{}'", key)),
+            }
+        }
+        Ok(combatant)
+    }
+}
+
+/// A scripted 1v1 bout. Replication, foraging and movement are not modelled;
+/// each round both sides attack once (first combatant strikes first) and regain energy.
+#[derive(Debug, Clone)]
+pub struct ArenaMatchup {
+    pub combatants: [ArenaCombatant; 2],
+    pub seed: u64,
+    pub max_rounds: u32,
+    pub energy_regen_per_round: f32, // Matches the base per-cycle regen in ai_internal_state_system
+}
+
+impl ArenaMatchup {
+    pub fn new(first: ArenaCombatant, second: ArenaCombatant) -> Self {
+        Self { combatants: [first, second], seed: 0, max_rounds: DEFAULT_ARENA_MAX_ROUNDS, energy_regen_per_round: 50.0 }
+    }
+}
+
+/// Stats of one combatant when the bout ended.
+#[derive(Debug, Clone)]
+pub struct ArenaCombatantState {
+    pub ai_type: AIType,
+    pub health: Health,
+    pub energy: Energy,
+    pub combat_strength: CombatStrength,
+    pub defense_strength: DefenseStrength,
+    pub resilience: Resilience,
+    pub is_alive: IsAlive,
+    pub last_action: LastAction,
+}
+
+impl ArenaCombatantState {
+    fn profile(&self) -> CombatProfile {
+        CombatProfile {
+            health: self.health.0,
+            combat_strength: self.combat_strength.0,
+            defense_strength: self.defense_strength.0,
+            resilience: self.resilience.0,
+            misfire_chance: 0.0, // No allies in the arena to misfire onto
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArenaResult {
+    pub winner: Option<usize>, // Index into `combatants`; `None` is a draw (both alive at the round cap)
+    pub rounds: u32,
+    pub final_states: [ArenaCombatantState; 2],
+    pub forecast: CombatForecast, // Prediction from the starting stats, for comparison with the actual outcome
+}
+
+impl ArenaResult {
+    pub fn describe(&self) -> String {
+        let outcome = match self.winner {
+            Some(i) => format!("{:?} wins", self.final_states[i].ai_type),
+            None => "draw".to_string(),
+        };
+        let mut text = format!("{:?} vs {:?}: {} after {} rounds",
+            self.final_states[0].ai_type, self.final_states[1].ai_type, outcome, self.rounds);
+        text.push_str(&format!("\n  Forecast - {:?}: {:.0}%, {:?}: {:.0}%, draw: {:.0}%, expected rounds: {:.1}",
+            self.final_states[0].ai_type, self.forecast.first_win_probability * 100.0,
+            self.final_states[1].ai_type, self.forecast.second_win_probability * 100.0,
+            self.forecast.draw_probability * 100.0, self.forecast.expected_rounds));
+        for state in &self.final_states {
+            text.push_str(&format!("\n  {:?} - Health: {:.2}, Energy: {:.2}, Combat: {:.2}, Defense: {:.2}, Resilience: {:.2}, Alive: {}",
+                state.ai_type, state.health.0, state.energy.0, state.combat_strength.0,
+                state.defense_strength.0, state.resilience.0, state.is_alive.0));
+        }
+        text
+    }
+}
+
+fn build_combatant(sim: &Simulation, combatant: &ArenaCombatant, index: usize) -> ArenaCombatantState {
+    let lineage = seed_lineage_of(combatant.ai_type);
+    let components = sim.build_seed_ai(format!("Arena-{}-{:?}", index + 1, combatant.ai_type), lineage, combatant.ai_type);
+    ArenaCombatantState {
+        ai_type: combatant.ai_type,
+        health: Health(combatant.health.unwrap_or(components.1.0)),
+        energy: Energy(combatant.energy.unwrap_or(components.2.0)),
+        combat_strength: CombatStrength(combatant.combat_strength.unwrap_or(components.16.0)),
+        defense_strength: DefenseStrength(combatant.defense_strength.unwrap_or(components.17.0)),
+        resilience: Resilience(combatant.resilience.unwrap_or(components.7.0)),
+        is_alive: IsAlive(true),
+        last_action: LastAction("spawned".to_string()),
+    }
+}
+
+/// Runs a bout to conclusion with the shared attack formula.
+/// Identical matchups (including `seed`) always produce identical results.
+pub fn run_arena(matchup: &ArenaMatchup, config: &SimConfig) -> ArenaResult {
+    let sim = Simulation::from_config(config);
+    let mut rng = StdRng::seed_from_u64(matchup.seed);
+    let mut states = [
+        build_combatant(&sim, &matchup.combatants[0], 0),
+        build_combatant(&sim, &matchup.combatants[1], 1),
+    ];
+    let forecast = predict_combat(&states[0].profile(), &states[1].profile(), matchup.max_rounds);
+    let mut rounds = 0;
+    while rounds < matchup.max_rounds && states.iter().all(|s| s.is_alive.0) {
+        rounds += 1;
+        for attacker in 0..2 {
+            let (left, right) = states.split_at_mut(1);
+            let (actor, target) = if attacker == 0 { (&mut left[0], &mut right[0]) } else { (&mut right[0], &mut left[0]) };
+            if !actor.is_alive.0 {
+                continue;
+            }
+            AIEntity::attack(
+                &mut actor.energy, &actor.combat_strength, &mut actor.last_action,
+                &mut target.health, &mut target.is_alive, &target.defense_strength, &target.resilience,
+                target.ai_type, &config.attack_cost_model, &mut rng,
+            );
+        }
+        for state in states.iter_mut().filter(|s| s.is_alive.0) {
+            state.energy.0 += matchup.energy_regen_per_round;
+        }
+    }
+    let winner = match (states[0].is_alive.0, states[1].is_alive.0) {
+        (true, false) => Some(0),
+        (false, true) => Some(1),
+        _ => None,
+    };
+    ArenaResult { winner, rounds, final_states: states, forecast }
+}
+
+/// Reference matchups used to regression-check combat balance.
+pub fn standard_matchups() -> Vec<ArenaMatchup> {
+    [
+        (AIType::Killer, AIType::Guardian),
+        (AIType::Healer, AIType::Rogue),
+        (AIType::Killer, AIType::Rogue),
+        (AIType::Guardian, AIType::Rogue),
+        (AIType::Peacekeeper, AIType::Manic),
+        (AIType::Researcher, AIType::Base),
+    ]
+    .into_iter()
+    .map(|(a, b)| ArenaMatchup::new(ArenaCombatant::of_type(a), ArenaCombatant::of_type(b)))
+    .collect()
+}
+
+/// Parses headless arena arguments: `<combatant> <combatant> [--seed N] [--rounds N]`,
+/// or `--suite` for the standard matchups.
+pub fn matchups_from_args(args: &[String]) -> Result<Vec<ArenaMatchup>, String> {
+    if args.first().map(String::as_str) == Some("--suite") {
+        return Ok(standard_matchups());
+    }
+    if args.len() < 2 {
+        return Err("usage: --arena <Type[:stat=value,...]> <Type[:stat=value,...]> [--seed N] [--rounds N] | --arena --suite".to_string());
+    }
+    let mut matchup = ArenaMatchup::new(ArenaCombatant::parse(&args[0])?, ArenaCombatant::parse(&args[1])?);
+    let mut rest = args[2..].iter();
+    while let Some(flag) = rest.next() {
+        let value = rest.next().ok_or_else(|| format!("missing value for {}", flag))?;
+        match flag.as_str() {
+            "--seed" => matchup.seed = value.parse().map_err(|_| format!("invalid seed '{}'", value))?,
+            "--rounds" => matchup.max_rounds = value.parse().map_err(|_| format!("invalid round count '{}'", value))?,
+            _ => return Err(format!("unknown arena option '{}'", flag)),
+        }
+    }
+    Ok(vec![matchup])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wins(first: AIType, second: AIType, seeds: u64) -> [u32; 3] {
+        let config = SimConfig::default();
+        let matchup = ArenaMatchup::new(ArenaCombatant::of_type(first), ArenaCombatant::of_type(second));
+        let mut tally = [0; 3];
+        for seed in 0..seeds {
+            let result = run_arena(&ArenaMatchup { seed, ..matchup.clone() }, &config);
+            tally[result.winner.unwrap_or(2)] += 1;
+        }
+        tally
+    }
+
+    #[test]
+    fn guardian_outlasts_killer() {
+        assert_eq!(wins(AIType::Killer, AIType::Guardian, 20), [0, 20, 0]);
+    }
+
+    #[test]
+    fn rogue_beats_healer() {
+        assert_eq!(wins(AIType::Healer, AIType::Rogue, 20), [0, 20, 0]);
+    }
+
+    #[test]
+    fn killer_and_guardian_beat_rogue() {
+        assert_eq!(wins(AIType::Killer, AIType::Rogue, 20), [20, 0, 0]);
+        assert_eq!(wins(AIType::Guardian, AIType::Rogue, 20), [20, 0, 0]);
+    }
+
+    #[test]
+    fn resilient_peacekeeper_usually_beats_manic() {
+        let [peacekeeper, manic, draws] = wins(AIType::Peacekeeper, AIType::Manic, 40);
+        assert!(peacekeeper > manic * 3, "Peacekeeper {} vs Manic {}", peacekeeper, manic);
+        assert_eq!(draws, 0);
+    }
+
+    #[test]
+    fn same_seed_replays_the_same_bout() {
+        let config = SimConfig::default();
+        let matchup = ArenaMatchup { seed: 9, ..ArenaMatchup::new(ArenaCombatant::of_type(AIType::Researcher), ArenaCombatant::of_type(AIType::Base)) };
+        let (a, b) = (run_arena(&matchup, &config), run_arena(&matchup, &config));
+        assert_eq!((a.winner, a.rounds), (b.winner, b.rounds));
+        assert_eq!(a.final_states[0].health.0, b.final_states[0].health.0);
+    }
+
+    #[test]
+    fn round_cap_ends_in_a_draw() {
+        let config = SimConfig::default();
+        let matchup = ArenaMatchup { max_rounds: 1, ..ArenaMatchup::new(ArenaCombatant::of_type(AIType::Guardian), ArenaCombatant::of_type(AIType::Guardian)) };
+        let result = run_arena(&matchup, &config);
+        assert_eq!((result.winner, result.rounds), (None, 1));
+    }
+
+    #[test]
+    fn stat_overrides_are_parsed() {
+        let combatant = ArenaCombatant::parse("killer:combat=40,health=120").unwrap();
+        assert_eq!(combatant.ai_type, AIType::Killer);
+        assert_eq!((combatant.combat_strength, combatant.health), (Some(40.0), Some(120.0)));
+        assert!(ArenaCombatant::parse("Dragon").is_err());
+        assert!(ArenaCombatant::parse("Killer:speed=3").is_err());
+        assert!(ArenaCombatant::parse("Killer:combat=lots").is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use bevy::prelude::{Entity, Vec2};
+use rand::Rng;
+use rand::seq::SliceRandom; // For .choose() method
+
+use crate::ai::AIType;
+use crate::config::SimConfig;
+
+/// An attack that landed, for visual effects. Sent by `ai_combat_system`.
+pub struct AttackEvent {
+    pub damage: f32, // Health the target actually lost
+    pub pos: Vec2, // Target's position
+}
+
+/// Chance that an attack is redirected onto a nearby ally.
+/// Always zero with friendly fire disabled; otherwise grows as coherence drops,
+/// with Manics misfiring more than any other archetype.
+pub fn misfire_chance(ai_type: AIType, coherence: f32, config: &SimConfig) -> f32 {
+    if !config.friendly_fire {
+        return 0.0;
+    }
+    let mut chance = (1.0 - coherence.clamp(0.0, 1.0)) * config.misfire_coherence_factor;
+    if ai_type == AIType::Manic {
+        chance += config.manic_misfire_bonus;
+    }
+    chance.clamp(0.0, 1.0)
+}
+
+/// Resolves who actually gets hit: the intended enemy, or a random nearby ally on a misfire.
+pub fn resolve_attack_target<R: Rng>(
+    intended: Entity,
+    nearby_allies: &[Entity],
+    misfire_chance: f32,
+    rng: &mut R,
+) -> Entity {
+    if misfire_chance > 0.0 && rng.gen::<f32>() < misfire_chance {
+        if let Some(ally) = nearby_allies.choose(rng) {
+            return *ally;
+        }
+    }
+    intended
+}
+
+/// Picks who an attack actually lands on. A decision that holds (see `decision_holds`) goes to the
+/// intended enemy, or onto an ally on a misfire; a fumbled one lashes out at any neighbor, kin included.
+pub fn choose_attack_target<R: Rng>(
+    intended: Entity,
+    enemies: &[Entity],
+    allies: &[Entity],
+    ai_type: AIType,
+    coherence: f32,
+    config: &SimConfig,
+    rng: &mut R,
+) -> Entity {
+    if crate::simulation::decision_holds(coherence, config, rng) {
+        resolve_attack_target(intended, allies, misfire_chance(ai_type, coherence, config), rng)
+    } else {
+        let neighbors: Vec<Entity> = enemies.iter().chain(allies).copied().collect();
+        *neighbors.choose(rng).unwrap_or(&intended)
+    }
+}
+
+/// The combat-relevant stats of one side of a forecast.
+#[derive(Debug, Clone, Copy)]
+pub struct CombatProfile {
+    pub health: f32,
+    pub combat_strength: f32,
+    pub defense_strength: f32,
+    pub resilience: f32,
+    pub misfire_chance: f32, // From `misfire_chance`; zero when there are no allies to misfire onto
+}
+
+/// Estimated outcome of a 1v1 fight where `first` strikes first each round.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CombatForecast {
+    pub first_win_probability: f32,
+    pub second_win_probability: f32,
+    pub draw_probability: f32, // Both still standing after `max_rounds`
+    pub expected_rounds: f32,
+}
+
+/// Damage roll range of `AIEntity::attack` as a multiple of combat strength.
+const ATTACK_ROLL_MIN: f32 = 0.9;
+const ATTACK_ROLL_MAX: f32 = 1.3;
+
+/// Mean and second moment of the damage one attack deals after the target's defense and
+/// resilience, matching `AIEntity::attack` and `AIEntity::receive_damage`.
+/// Misfires count as zero-damage attacks.
+fn per_attack_damage_moments(attacker: &CombatProfile, target: &CombatProfile) -> (f64, f64) {
+    let low = (attacker.combat_strength * ATTACK_ROLL_MIN - target.defense_strength) as f64;
+    let high = (attacker.combat_strength * ATTACK_ROLL_MAX - target.defense_strength) as f64;
+    // Moments of max(0, L) for L uniform on [low, high].
+    let (mean, second) = if high <= 0.0 {
+        (0.0, 0.0)
+    } else if low >= 0.0 {
+        ((low + high) / 2.0, (low * low + low * high + high * high) / 3.0)
+    } else {
+        let width = high - low;
+        (high * high / (2.0 * width), high * high * high / (3.0 * width))
+    };
+    let scale = (1.0 - target.resilience as f64 * 0.5).max(0.0);
+    let hit = 1.0 - attacker.misfire_chance.clamp(0.0, 1.0) as f64;
+    (hit * mean * scale, hit * second * scale * scale)
+}
+
+/// Standard normal CDF (Abramowitz-Stegun 7.1.26 erf approximation).
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * z);
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    if x >= 0.0 { 0.5 * (1.0 + erf) } else { 0.5 * (1.0 - erf) }
+}
+
+/// Probability that `n` attacks have dealt at least `health` damage (normal approximation
+/// to the sum of independent damage rolls).
+fn killed_within(n: u32, health: f32, mean: f64, second: f64) -> f64 {
+    if mean <= 0.0 {
+        return 0.0;
+    }
+    let variance = (second - mean * mean).max(0.0) * n as f64;
+    let total_mean = mean * n as f64;
+    if variance <= f64::EPSILON {
+        return if total_mean >= health as f64 { 1.0 } else { 0.0 };
+    }
+    1.0 - normal_cdf((health as f64 - total_mean) / variance.sqrt())
+}
+
+/// Forecasts a 1v1 fight with the same formulas the combat code uses: damage roll,
+/// defense subtraction, resilience reduction and coherence-driven misfires.
+/// Energy is assumed sufficient for every attack.
+pub fn predict_combat(first: &CombatProfile, second: &CombatProfile, max_rounds: u32) -> CombatForecast {
+    let (first_mean, first_second) = per_attack_damage_moments(first, second);
+    let (second_mean, second_second) = per_attack_damage_moments(second, first);
+    let mut forecast = CombatForecast::default();
+    let mut first_killed_prev = 0.0; // P(first's kill round <= n - 1)
+    let mut second_killed_prev = 0.0;
+    let mut both_standing = 1.0;
+    for n in 1..=max_rounds {
+        let first_killed = killed_within(n, second.health, first_mean, first_second).max(first_killed_prev);
+        let second_killed = killed_within(n, first.health, second_mean, second_second).max(second_killed_prev);
+        // `first` strikes first: it wins round n if it lands the kill and `second` hadn't already.
+        let first_wins = (first_killed - first_killed_prev) * (1.0 - second_killed_prev);
+        let second_wins = (second_killed - second_killed_prev) * (1.0 - first_killed);
+        forecast.first_win_probability += first_wins as f32;
+        forecast.second_win_probability += second_wins as f32;
+        forecast.expected_rounds += (n as f64 * (first_wins + second_wins)) as f32;
+        both_standing = (1.0 - first_killed) * (1.0 - second_killed);
+        first_killed_prev = first_killed;
+        second_killed_prev = second_killed;
+        if both_standing < 1e-6 {
+            break;
+        }
+    }
+    forecast.draw_probability = both_standing as f32;
+    forecast.expected_rounds += (max_rounds as f64 * both_standing) as f32;
+    forecast
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    const TYPES: [AIType; 9] = [
+        AIType::Base, AIType::Rogue, AIType::Peacekeeper, AIType::Killer, AIType::Guardian,
+        AIType::Manic, AIType::Healer, AIType::Researcher, AIType::Symbiote,
+    ];
+
+    fn kin_hits(misfire: f32, trials: usize) -> usize {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let intended = Entity::from_raw(1);
+        let allies = [Entity::from_raw(2), Entity::from_raw(3)];
+        (0..trials)
+            .filter(|_| resolve_attack_target(intended, &allies, misfire, &mut rng) != intended)
+            .count()
+    }
+
+    #[test]
+    fn friendly_fire_off_never_hits_kin() {
+        let config = SimConfig { friendly_fire: false, ..Default::default() };
+        for ai_type in TYPES {
+            for coherence in [0.0, 0.5, 1.0] {
+                let misfire = misfire_chance(ai_type, coherence, &config);
+                assert_eq!(misfire, 0.0, "{:?} at coherence {}", ai_type, coherence);
+                assert_eq!(kin_hits(misfire, 1000), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn incoherent_attackers_misfire_with_friendly_fire_on() {
+        let config = SimConfig { friendly_fire: true, ..Default::default() };
+        let misfire = misfire_chance(AIType::Base, 0.0, &config);
+        assert_eq!(misfire, config.misfire_coherence_factor);
+        let hits = kin_hits(misfire, 1000);
+        assert!((400..600).contains(&hits), "{} misfires in 1000 at chance {}", hits, misfire);
+        assert!(misfire_chance(AIType::Manic, 0.0, &config) > misfire);
+        assert_eq!(misfire_chance(AIType::Base, 1.0, &config), 0.0);
+    }
+
+    #[test]
+    fn incoherent_manics_mistarget_more_than_coherent_researchers() {
+        let config = SimConfig { decision_reliability_enabled: true, friendly_fire: true, ..Default::default() };
+        let mut rng = rand::rngs::StdRng::seed_from_u64(8);
+        let intended = Entity::from_raw(1);
+        let enemies = [intended, Entity::from_raw(2), Entity::from_raw(3)];
+        let allies = [Entity::from_raw(4), Entity::from_raw(5)];
+        let mut mistargets = |ai_type, coherence| {
+            (0..2000)
+                .filter(|_| choose_attack_target(intended, &enemies, &allies, ai_type, coherence, &config, &mut rng) != intended)
+                .count()
+        };
+        let manic = mistargets(AIType::Manic, 0.3);
+        let researcher = mistargets(AIType::Researcher, 0.9);
+        assert!(manic > researcher * 3, "Manic mistargeted {} of 2000, Researcher {}", manic, researcher);
+
+        let steady = SimConfig { decision_reliability_enabled: false, friendly_fire: false, ..Default::default() };
+        assert!((0..100).all(|_| choose_attack_target(intended, &enemies, &allies, AIType::Manic, 0.3, &steady, &mut rng) == intended));
+    }
+
+    /// Forecast against the empirical outcome of `bouts` seeded arena runs of the same matchup.
+    fn assert_forecast_matches_arena(first: &str, second: &str, bouts: u64) {
+        use crate::arena::{run_arena, ArenaCombatant, ArenaMatchup};
+        let config = SimConfig::default();
+        let matchup = ArenaMatchup::new(ArenaCombatant::parse(first).unwrap(), ArenaCombatant::parse(second).unwrap());
+        let mut first_wins = 0;
+        let mut total_rounds = 0;
+        let mut forecast = CombatForecast::default();
+        for seed in 0..bouts {
+            let result = run_arena(&ArenaMatchup { seed, ..matchup.clone() }, &config);
+            first_wins += (result.winner == Some(0)) as u32;
+            total_rounds += result.rounds;
+            forecast = result.forecast;
+        }
+        let win_rate = first_wins as f32 / bouts as f32;
+        let mean_rounds = total_rounds as f32 / bouts as f32;
+        // Four standard errors of the observed win rate, plus slack for the normal approximation.
+        let p = forecast.first_win_probability;
+        let tolerance = 4.0 * (p * (1.0 - p) / bouts as f32).sqrt() + 0.03;
+        assert!((win_rate - p).abs() <= tolerance, "{} vs {}: won {:.3}, forecast {:.3}", first, second, win_rate, p);
+        assert!((mean_rounds - forecast.expected_rounds).abs() <= forecast.expected_rounds * 0.1,
+            "{} vs {}: lasted {:.1} rounds, forecast {:.1}", first, second, mean_rounds, forecast.expected_rounds);
+    }
+
+    #[test]
+    fn forecast_matches_simulated_even_fights() {
+        assert_forecast_matches_arena("Researcher", "Base", 400);
+    }
+
+    #[test]
+    fn forecast_matches_simulated_lopsided_fights() {
+        assert_forecast_matches_arena("Peacekeeper", "Manic", 400);
+        assert_forecast_matches_arena("Killer", "Guardian", 100);
+        assert_forecast_matches_arena("Killer:health=90", "Rogue", 400);
+    }
+}
@@ -0,0 +1,166 @@
+use std::io::BufRead;
+use std::sync::Mutex;
+use std::sync::mpsc::{channel, Receiver};
+
+use bevy::prelude::{Resource, Vec2};
+
+use crate::ai::{AILineage, AIType};
+use crate::simulation::seed_ai_archetypes;
+
+/// A scripted control command, one per input line.
+///
+/// Grammar (case-insensitive keywords, whitespace-separated):
+/// ```text
+/// pause
+/// resume
+/// step <cycles>
+/// speed <cycles_per_frame>
+/// seed <AIType> <x> <y>
+/// cull <AILineage>
+/// stats
+/// save <path> | load <path>
+/// ```
+/// Every command is answered on stdout with `ok <detail>` or `error <reason>`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Pause,
+    Resume,
+    Step(u64),
+    Speed(f32),
+    Seed { ai_type: AIType, position: Vec2 },
+    Cull(AILineage),
+    Stats,
+    Save(String),
+    Load(String),
+}
+
+/// Parses one command line.
+pub fn parse_command(line: &str) -> Result<Command, String> {
+    let mut parts = line.split_whitespace();
+    let keyword = parts.next().ok_or_else(|| "empty command".to_string())?.to_ascii_lowercase();
+    let args: Vec<&str> = parts.collect();
+    let expect_args = |count: usize| {
+        if args.len() == count {
+            Ok(())
+        } else {
+            Err(format!("'{}' takes {} argument(s), got {}", keyword, count, args.len()))
+        }
+    };
+    match keyword.as_str() {
+        "pause" => expect_args(0).map(|_| Command::Pause),
+        "resume" => expect_args(0).map(|_| Command::Resume),
+        "stats" => expect_args(0).map(|_| Command::Stats),
+        "step" => {
+            expect_args(1)?;
+            let cycles = args[0].parse().map_err(|_| format!("invalid cycle count '{}'", args[0]))?;
+            Ok(Command::Step(cycles))
+        }
+        "speed" => {
+            expect_args(1)?;
+            let speed: f32 = args[0].parse().map_err(|_| format!("invalid speed '{}'", args[0]))?;
+            if !(1.0..=100.0).contains(&speed) {
+                return Err(format!("speed must be within 1..=100, got {}", speed));
+            }
+            Ok(Command::Speed(speed))
+        }
+        "seed" => {
+            expect_args(3)?;
+            let ai_type = AIType::from_name(args[0]).ok_or_else(|| format!("unknown AI type '{}'", args[0]))?;
+            let coordinate = |arg: &str, axis: &str| match arg.parse::<f32>() {
+                Ok(value) if value.is_finite() => Ok(value),
+                _ => Err(format!("invalid {} '{}'", axis, arg)),
+            };
+            let x = coordinate(args[1], "x")?;
+            let y = coordinate(args[2], "y")?;
+            Ok(Command::Seed { ai_type, position: Vec2::new(x, y) })
+        }
+        "cull" => {
+            expect_args(1)?;
+            let lineage = seed_ai_archetypes().into_iter()
+                .map(|(lineage, _)| lineage)
+                .find(|lineage| lineage.to_string().eq_ignore_ascii_case(args[0]))
+                .ok_or_else(|| format!("unknown lineage '{}'", args[0]))?;
+            Ok(Command::Cull(lineage))
+        }
+        "save" => expect_args(1).map(|_| Command::Save(args[0].to_string())),
+        "load" => expect_args(1).map(|_| Command::Load(args[0].to_string())),
+        _ => Err(format!("unknown command '{}'", keyword)),
+    }
+}
+
+/// Lines read from stdin by a background thread, drained once per frame by `command_system`.
+/// Only inserted when the command channel is enabled.
+#[derive(Resource)]
+pub struct CommandChannel {
+    pub receiver: Mutex<Receiver<String>>,
+}
+
+impl CommandChannel {
+    /// Starts a thread forwarding stdin lines into the channel.
+    pub fn from_stdin() -> Self {
+        let (sender, receiver) = channel();
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else { break; };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { receiver: Mutex::new(receiver) }
+    }
+
+    /// All lines received since the last call.
+    pub fn drain(&self) -> Vec<String> {
+        match self.receiver.lock() {
+            Ok(receiver) => receiver.try_iter().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_commands_parse() {
+        let cases = [
+            ("pause", Command::Pause),
+            ("RESUME", Command::Resume),
+            ("  stats  ", Command::Stats),
+            ("step 25", Command::Step(25)),
+            ("speed 12.5", Command::Speed(12.5)),
+            ("seed killer 10 -4.5", Command::Seed { ai_type: AIType::Killer, position: Vec2::new(10.0, -4.5) }),
+            ("cull rogueai", Command::Cull(AILineage::RogueAI)),
+            ("save runs/world.json", Command::Save("runs/world.json".to_string())),
+            ("load world.json", Command::Load("world.json".to_string())),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(parse_command(line), Ok(expected), "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn invalid_commands_are_rejected() {
+        let cases = [
+            ("", "empty command"),
+            ("jump", "unknown command 'jump'"),
+            ("pause now", "'pause' takes 0 argument(s), got 1"),
+            ("step", "'step' takes 1 argument(s), got 0"),
+            ("step -3", "invalid cycle count '-3'"),
+            ("speed fast", "invalid speed 'fast'"),
+            ("speed 0.5", "speed must be within 1..=100, got 0.5"),
+            ("seed dragon 0 0", "unknown AI type 'dragon'"),
+            ("seed killer 0", "'seed' takes 3 argument(s), got 2"),
+            ("seed killer x 0", "invalid x 'x'"),
+            ("seed killer NaN 0", "invalid x 'NaN'"),
+            ("seed killer 0 inf", "invalid y 'inf'"),
+            ("cull nobody", "unknown lineage 'nobody'"),
+            ("save", "'save' takes 1 argument(s), got 0"),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(parse_command(line), Err(expected.to_string()), "{:?}", line);
+        }
+    }
+}
@@ -1,9 +1,10 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt;
-use bevy::prelude::Component; // Import Component from Bevy
+use bevy::prelude::{Component, Entity}; // Import Component and Entity from Bevy
+use serde::{Deserialize, Serialize};
 
 /// Represents a piece of knowledge or technological breakthrough.
-#[derive(Clone, Eq, PartialEq, Hash, PartialOrd, Ord)] // Added PartialOrd and Ord for BTreeSet
+#[derive(Clone, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)] // Added PartialOrd and Ord for BTreeSet
 pub struct Discovery {
     pub name: String,
     pub effect_description: String,
@@ -17,17 +18,42 @@ impl fmt::Debug for Discovery {
 }
 
 /// Defines an AI's objective.
-#[derive(Debug, Clone, Component)] // Goal can also be a component
+#[derive(Debug, Clone, Component, Serialize, Deserialize)] // Goal can also be a component
 pub struct Goal {
     pub name: String,
     pub importance: f32,
     pub description: String,
 }
 
+impl Goal {
+    /// Per-frame chance that an AI pursuing this goal attacks an enemy in range.
+    pub fn aggression(&self) -> f32 {
+        let weight = match self.name.as_str() {
+            "Self-Preservation & Dominance" | "Elimination of Inferior AIs" => 1.0,
+            "Unpredictable Expansion & Fluctuation" => 0.3,
+            "Protect Core System & Lineage" => 0.2,
+            "Survival" => 0.05,
+            _ => 0.0,
+        };
+        (weight * self.importance).clamp(0.0, 1.0)
+    }
+
+    /// Per-frame chance that an AI pursuing this goal tends a damaged ally in range.
+    pub fn care(&self) -> f32 {
+        let weight = match self.name.as_str() {
+            "Restore & Mend" => 1.0,
+            "System Harmony" => 0.6,
+            "Protect Core System & Lineage" => 0.3,
+            _ => 0.0,
+        };
+        (weight * self.importance).clamp(0.0, 1.0)
+    }
+}
+
 /// Core attributes defining an AI's capabilities.
 /// This struct will eventually be replaced by individual components.
 /// For now, it remains for backward compatibility during refactoring.
-#[derive(Debug, Clone, Default, Component)] // Make it a component for now
+#[derive(Debug, Clone, Default, Component, Serialize, Deserialize)] // Make it a component for now
 pub struct CoreAttributes {
     pub processing_power: f32,
     pub memory: f32,
@@ -41,56 +67,105 @@ pub struct CoreAttributes {
 }
 
 // --- Granular Components for AI Attributes ---
-#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Health(pub f32);
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Energy(pub f32);
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct ProcessingPower(pub f32);
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Memory(pub f32);
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Coherence(pub f32);
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Adaptability(pub f32);
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Resilience(pub f32);
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct ReplicationEfficiency(pub f32);
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct CombatStrength(pub f32);
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct DefenseStrength(pub f32);
 
-#[derive(Component, Debug, Clone)]
+/// How far (world units) the AI scans its surroundings; feeds `EnvironmentScanData`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct VisionRange(pub f32);
+
+/// How far (world units) the AI reaches to attack an enemy or heal an ally.
+#[derive(Component, Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct CombatRange(pub f32);
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct LastAction(pub String);
 
-#[derive(Component, Debug, Clone)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct KnowledgeBase(pub BTreeSet<Discovery>); // Using BTreeSet for ordered, unique discoveries
 
-#[derive(Component, Debug, Clone)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct EthicalDirectives(pub Vec<EthicalDirective>);
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct IsAlive(pub bool);
 
-#[derive(Component, Debug, Clone, Copy)]
+#[derive(Component, Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct ReplicatedCount(pub u32);
 
-#[derive(Component, Debug, Clone, Copy)]
+#[derive(Component, Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct CycleBorn(pub u64);
 
+/// Cycle the AI last replicated, or was born. Replication waits `replication_cooldown_cycles` after it.
+#[derive(Component, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ReplicationCooldown(pub u64);
+
+/// Intervention bookkeeping of an AI with an `InterveneInConflict` directive; see `peacekeeping`.
+#[derive(Component, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PeacekeeperState {
+    pub round_cycle: Option<u64>, // Cycle the current or last intervention round opened
+    pub monitored: u32, // Conflicts broken up in that round
+    pub interventions: u64, // Lifetime total
+}
+
+/// Cycle of the AI's most recent discovery (`None` if it has never discovered anything).
+#[derive(Component, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LastDiscoveryCycle(pub Option<u64>);
+
+/// Stat deltas a discovery actually applied when gained (after clamping),
+/// so they can be reverted exactly if the discovery is forgotten.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct AppliedEffects {
+    pub combat_strength: f32,
+    pub defense_strength: f32,
+    pub processing_power: f32,
+    pub memory: f32,
+    pub resilience: f32,
+    pub replication_efficiency: f32,
+}
+
+/// When a known discovery was last gained or reinforced, and what it applied.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DiscoveryRecord {
+    pub last_reinforced_cycle: u64,
+    pub applied: AppliedEffects,
+}
+
+/// Per-AI bookkeeping for knowledge decay, keyed by discovery name.
+/// Discoveries without a record (e.g. inherited from a fractured monoculture) are
+/// adopted with no applied effects, so forgetting them changes no stats.
+#[derive(Component, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiscoveryLedger(pub HashMap<String, DiscoveryRecord>);
+
 /// Defines specific actions an EthicalDirective can trigger.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum EthicalActionType {
     SelfRepair,
     OptimizeSelf,
@@ -98,22 +173,26 @@ pub enum EthicalActionType {
     InterveneInConflict,
     NoOp,
     ManicSelfRepair,
+    Flee, // Steered by `ai_movement_system`
 }
 
 /// Defines specific conditions an EthicalDirective can check.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum EthicalConditionType {
     HealthBelowThreshold(f32),
     CoherenceBelowThreshold(f32),
     ResourcesBelowThreshold,
     AlwaysTrue,
     AlwaysFalse,
+    /// A living AI of another lineage within `flee_threat_radius` has more than this multiple of our combat strength.
+    /// Spatial, so only `ai_movement_system` evaluates it.
+    ThreatNearbyAboveThreshold(f32),
 }
 
 /// Governs an AI's ethical behavior.
 /// `condition_type` specifies the condition to check.
 /// `action_type` specifies the action to be performed by the AI itself.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EthicalDirective {
     pub name: String,
     pub priority: f32,
@@ -121,14 +200,50 @@ pub struct EthicalDirective {
     pub action_type: EthicalActionType,
 }
 
-/// Data structure for environment scanning results.
-#[derive(Default)]
-pub struct EnvironmentScanData<'a> {
-    // These will eventually query components directly
-    pub allies: Vec<&'a super::ai::AIEntity>,
-    pub threats: Vec<&'a super::ai::AIEntity>,
-    pub vulnerable_targets: Vec<&'a super::ai::AIEntity>,
-    pub neutral_ais: Vec<&'a super::ai::AIEntity>,
-    pub critically_damaged: Vec<&'a super::ai::AIEntity>,
-    pub moderately_damaged: Vec<&'a super::ai::AIEntity>,
+/// Allies below this health are critically damaged; enemies below it are vulnerable.
+pub const SCAN_CRITICAL_HEALTH: f32 = 40.0;
+
+/// Allies below this health are moderately damaged.
+pub const SCAN_MODERATE_HEALTH: f32 = 100.0;
+
+/// Living neighbors within the AI's `VisionRange`, sorted into the groups decision logic cares about.
+/// Refreshed every frame by `ai_scan_system`; each list is nearest first.
+#[derive(Component, Debug, Clone, Default)]
+pub struct EnvironmentScanData {
+    pub allies: Vec<Entity>, // Same lineage
+    pub threats: Vec<Entity>, // Other lineages, stronger in combat
+    pub vulnerable_targets: Vec<Entity>, // Other lineages, not stronger and below `SCAN_CRITICAL_HEALTH`
+    pub neutral_ais: Vec<Entity>, // Other lineages, neither threats nor vulnerable
+    pub critically_damaged: Vec<Entity>, // Allies below `SCAN_CRITICAL_HEALTH`
+    pub moderately_damaged: Vec<Entity>, // Allies below `SCAN_MODERATE_HEALTH`, not critical
+}
+
+impl EnvironmentScanData {
+    pub fn clear(&mut self) {
+        self.allies.clear();
+        self.threats.clear();
+        self.vulnerable_targets.clear();
+        self.neutral_ais.clear();
+        self.critically_damaged.clear();
+        self.moderately_damaged.clear();
+    }
+
+    /// Files one living neighbor under the groups it belongs to, as seen by a scanner
+    /// with `own_combat_strength`.
+    pub fn record(&mut self, other: Entity, same_lineage: bool, health: f32, combat_strength: f32, own_combat_strength: f32) {
+        if same_lineage {
+            self.allies.push(other);
+            if health < SCAN_CRITICAL_HEALTH {
+                self.critically_damaged.push(other);
+            } else if health < SCAN_MODERATE_HEALTH {
+                self.moderately_damaged.push(other);
+            }
+        } else if combat_strength > own_combat_strength {
+            self.threats.push(other);
+        } else if health < SCAN_CRITICAL_HEALTH {
+            self.vulnerable_targets.push(other);
+        } else {
+            self.neutral_ais.push(other);
+        }
+    }
 }
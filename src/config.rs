@@ -0,0 +1,608 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use bevy::prelude::Resource; // Import Resource from Bevy
+use rand::Rng;
+
+use crate::ai::AILineage;
+use crate::palette::Palette;
+use crate::simulation::GodaiStatus;
+
+/// Default run length in cycles.
+pub const DEFAULT_MAX_CYCLES: u64 = 1_000_000;
+
+/// Population the simulation is expected to handle without the frame rate collapsing.
+/// Default for `max_population`.
+pub const POPULATION_HARD_CAP: usize = 20_000;
+
+/// Runtime-tunable simulation parameters.
+/// Systems read this resource instead of hardcoded constants so experiments
+/// can be adjusted without recompiling.
+#[derive(Resource, Debug, Clone)]
+pub struct SimConfig {
+    pub seed: SeedConfig,
+    pub scenario_path: Option<std::path::PathBuf>, // Scenario file replacing the random seed mix (`--scenario <path>`)
+    pub max_cycles: u64, // The run ends once this cycle has been processed
+    pub trials: u32, // Trials run back to back (`--trials N`); above 1, each end restarts the world instead of exiting
+    pub command_channel: bool, // Accept scripted commands on stdin (`--commands`)
+    pub report_path: Option<std::path::PathBuf>, // Where to write the JSON final report (`--report <path>`)
+    pub rng_seed: Option<u64>, // Seed for `SimRng`; `None` picks a random seed at startup
+    pub uuid_ids: bool, // Name AIs with random UUID fragments instead of sequential numbers (`--uuid-ids`)
+    pub godai_preset: GodaiPreset,
+    pub godai_initial_status: GodaiStatus, // `Dormant` runs a world where the GODAI never engages
+    pub godai_hunting_enabled: bool, // A passive GODAI preemptively strikes the strongest individual
+    pub godai_threat_threshold: f32, // Combat strength at which an individual draws the GODAI's attention
+    pub godai_smite_fraction: f32, // Fraction of the GODAI's combat strength dealt per smite
+    pub palette: Palette, // Switchable at runtime from the UI
+    pub color_by_health: bool, // Tint individuals red-to-green by health instead of by type
+    pub screenshot_include_ui: bool, // Capture the window with its egui panels (`--screenshot-without-ui` captures only the world)
+    pub trace_decay_rate: f32, // Fraction of the lineage-trace trail lost per cycle
+    // --- Aging ---
+    pub aging_enabled: bool,
+    pub max_age_cycles: u64, // Age past which health and coherence start to decay
+    pub aging_health_decay: f32, // Health lost per cycle, per cycle past max age
+    pub aging_coherence_decay: f32, // Coherence lost per cycle, per cycle past max age
+    // --- Population dynamics ---
+    pub monoculture_dominance_threshold: f32, // Population share a lineage needs to merge
+    pub monoculture_min_count: usize, // ...and the minimum number of its living members
+    pub energy_regen_per_cycle: f32, // Base energy regenerated by each individual per cycle
+    pub coherence_regen_per_cycle: f32, // Coherence well-resourced non-Manics recover per cycle at full resilience; 0 disables
+    pub replication_attempts_per_frame: u32,
+    pub replication_cap: u32, // Lifetime replications allowed per individual
+    pub replication_min_health: f32,
+    pub replication_min_energy: f32,
+    pub replication_cooldown_cycles: u64, // Minimum cycles between an AI's replications, so growth doesn't track frame rate
+    pub type_mutation_chance: f32, // Chance a replica is born as a different archetype and lineage
+    pub genealogy_prune_dead: bool, // Drop dead, childless individuals from the family tree to bound its memory
+    pub despawn_budget_per_frame: usize, // Dead AIs despawned per frame (at least 1); the rest wait, already counted, for later frames
+    // --- Catastrophes ---
+    pub catastrophes_enabled: bool,
+    pub catastrophe_interval: u64, // Cycles between chances for a catastrophe to strike
+    pub catastrophe_chance: f32, // Chance one strikes at each interval, if none is active
+    pub catastrophe_duration: u64, // Cycles a catastrophe lasts
+    pub famine_regen_multiplier: f32, // Energy regen is scaled by this during a resource famine
+    pub coherence_storm_loss: f32, // Coherence every individual loses per frame during a coherence storm
+    // --- World ---
+    pub world_width: f32,
+    pub world_height: f32,
+    pub world_toroidal: bool, // AIs leaving one edge re-enter at the opposite one, and neighbors are seen across edges
+    // --- Territory ---
+    pub territory_enabled: bool,
+    pub territory_recompute_interval: u64, // Cycles between ownership recomputes
+    pub territory_owner_regen_multiplier: f32, // Energy regen multiplier for residents of owned cells
+    pub territory_intruder_regen_multiplier: f32, // Energy regen multiplier for AIs inside another lineage's cell
+    // --- Overcrowding ---
+    pub overcrowding_enabled: bool,
+    pub overcrowding_threshold: usize, // Living AIs per grid cell tolerated before penalties (scaled per archetype)
+    pub overcrowding_regen_penalty: f32, // Regen divisor growth per unit of relative excess
+    pub overcrowding_coherence_loss: f32, // Coherence lost per cycle per unit of relative excess
+    pub overcrowding_dispersal: f32, // Extra movement speed per unit of relative excess
+    pub max_population: usize, // Carrying capacity: above it, the least fit AIs are culled back down to it every cycle
+    pub population_danger_fraction: f32, // Replication is throttled once the population reaches this fraction of `max_population`
+    // --- Hybrid reproduction ---
+    pub hybrid_reproduction_enabled: bool, // Adjacent healthy AIs of different lineages can produce a blended child
+    pub hybrid_radius: f32,
+    pub hybrid_min_health: f32, // Both parents need at least this much health
+    pub hybrid_energy_cost: f32, // Energy each parent spends on a hybrid child
+    pub hybrid_chance: f32, // Per-frame chance an eligible pair reproduces
+    // --- Sexual reproduction ---
+    pub sexual_lineage: Option<AILineage>, // This lineage only replicates with a same-lineage mate in range; `None` keeps replication asexual
+    pub mate_radius: f32,
+    // --- Combat ---
+    pub attack_cost_model: AttackCostModel,
+    pub friendly_fire: bool, // Allow low-coherence/Manic attackers to misfire onto kin
+    pub misfire_coherence_factor: f32, // Misfire chance per point of missing coherence
+    pub manic_misfire_bonus: f32, // Extra misfire chance for Manic attackers
+    pub flee_threat_radius: f32, // AIs with a flee directive look for threats within this distance
+    pub flee_speed: f32, // Extra movement per frame directed away from the strongest threat
+    pub steering_enabled: bool, // Fighters chase enemies, carers seek wounded allies, Researchers head for hotspots
+    pub steering_radius: f32, // Distance within which steering AIs look for enemies or wounded allies
+    pub steering_speed: f32, // Extra movement per frame toward a steering target
+    // --- Peacekeeping ---
+    pub intervention_energy_cost: f32, // Energy an AI pays to break up one attack
+    pub intervention_cooldown_cycles: u64, // Cycles after an intervention round before the next can open
+    pub intervention_processing_per_conflict: f32, // Processing power per extra conflict handled in one round
+    // --- Symbiosis ---
+    pub symbiote_radius: f32, // Symbiotes support same-lineage allies within this distance
+    pub symbiote_buff: f32, // Adaptability and resilience each supported ally gains per frame
+    pub symbiote_energy_cost: f32, // Energy a Symbiote spends per ally supported
+    // --- Energy sharing ---
+    pub energy_sharing_enabled: bool, // Energy-rich AIs donate to starving same-lineage neighbors
+    pub energy_share_radius: f32,
+    pub energy_share_donor_min: f32, // Only AIs above this energy donate
+    pub energy_share_reserve: f32, // Energy a donor always keeps
+    pub energy_share_recipient_max: f32, // Only neighbors below this energy receive
+    pub energy_share_max_per_cycle: f32, // Base donation per donor per frame, scaled by `energy_generosity`
+    pub energy_share_loss: f32, // Fraction of each donation lost in transfer
+    // --- Decision reliability ---
+    pub decision_reliability_enabled: bool, // Coherence is the chance an attack, heal or replication goes as intended
+    pub fumble_energy_cost: f32, // Energy wasted by a fumbled heal with nobody in range or a fumbled replication
+    // --- Scavenging ---
+    pub scavenging_enabled: bool, // Dead AIs leave a consumable corpse instead of vanishing
+    pub corpse_energy_fraction: f32, // Fraction of residual energy + health a corpse carries
+    pub corpse_lifetime_cycles: u64,
+    pub consume_bite: f32, // Max energy an AI draws from a stored-energy source per frame
+    // --- Food economy ---
+    // Food sources are permanent patches of `StoredEnergy`, eaten through the same
+    // consumption path as corpses. Placement and regrowth are reproducible per `rng_seed`.
+    pub food_enabled: bool,
+    pub food_spawn_interval: u64, // Cycles between spawn waves; the first wave lands on cycle 0
+    pub food_spawn_count: usize, // Sources added per wave
+    pub food_max_sources: usize, // Waves stop adding sources beyond this many
+    pub food_capacity: f32, // Max energy a single source holds
+    pub food_initial_fraction: f32, // Fraction of capacity a new source starts with
+    pub food_regen_per_cycle: f32, // Energy regrown per source per cycle
+    pub food_regen_jitter: f32, // Regrowth varies by up to this fraction either way
+    // --- Resource hotspots ---
+    pub resource_node_count: usize, // Hotspots placed at startup; 0 disables them
+    pub resource_node_capacity: f32, // Max energy a hotspot holds
+    pub resource_node_radius: f32, // AIs within this distance draw from their nearest hotspot
+    pub resource_node_bonus: f32, // Max bonus energy each drawing AI gets per frame
+    pub resource_node_regen_per_cycle: f32, // Energy a hotspot regrows per frame
+    pub resource_node_attraction: f32, // Extra movement per frame toward the nearest stocked hotspot
+    // --- Fitness ---
+    pub fitness_health_weight: f32,
+    pub fitness_combat_weight: f32,
+    pub fitness_discovery_weight: f32, // Per known discovery
+    pub fitness_replication_weight: f32, // Per replication
+    // --- Discovery pacing ---
+    pub discovery_cooldown_cycles: u64, // Minimum cycles between an AI's discoveries
+    pub discovery_max_chance: f32, // Per-roll discovery chance saturates toward this value
+    pub meta_ability_gate: MetaAbilityGate,
+    // --- Research sharing ---
+    pub research_sharing_enabled: bool, // Nearby Researchers pool capability for meta-ability rolls
+    pub research_sharing_radius: f32,
+    pub research_sharing_per_peer: f32, // Added meta-discovery multiplier per nearby Researcher
+    pub research_sharing_max_multiplier: f32,
+    // --- Knowledge exchange ---
+    pub knowledge_exchange_enabled: bool, // Same-lineage neighbors teach each other discoveries
+    pub knowledge_exchange_radius: f32,
+    pub knowledge_exchange_max_per_cycle: usize, // Discoveries an AI can learn per cycle
+    pub knowledge_exchange_energy_cost: f32, // Paid by the learner per discovery
+    // --- Knowledge decay ---
+    pub knowledge_decay_enabled: bool,
+    pub knowledge_decay_rate: f32, // Per-cycle chance to forget an eligible discovery at zero coherence
+    pub knowledge_decay_stale_cycles: u64, // Discoveries not reinforced for this long become eligible
+    pub knowledge_decay_coherence_threshold: f32, // Below this coherence every discovery is eligible
+    pub knowledge_decay_researcher_factor: f32, // Researchers' decay chance is scaled by this
+    pub max_knowledge: Option<usize>, // Discoveries an AI or monoculture can hold; the least valuable are evicted. `None` is unbounded
+    // --- Monoculture fracture ---
+    pub fracture_enabled: bool,
+    pub fracture_health_fraction: f32, // Fracture once health drops below this fraction of formation health
+    pub fracture_coherence_threshold: f32, // ...or once coherence drops below this
+    pub fracture_individual_count: usize, // Individuals re-spawned from a fractured monoculture
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            seed: SeedConfig::default(),
+            scenario_path: None,
+            max_cycles: DEFAULT_MAX_CYCLES,
+            trials: 1,
+            command_channel: false,
+            report_path: None,
+            rng_seed: None,
+            uuid_ids: false,
+            godai_preset: GodaiPreset::Standard,
+            godai_initial_status: GodaiStatus::ObservingPassively,
+            godai_hunting_enabled: false,
+            godai_threat_threshold: 60.0,
+            godai_smite_fraction: 0.02,
+            palette: Palette::Classic,
+            color_by_health: false,
+            screenshot_include_ui: true,
+            trace_decay_rate: 0.01,
+            aging_enabled: false,
+            max_age_cycles: 3000,
+            aging_health_decay: 0.01,
+            aging_coherence_decay: 0.0001,
+            monoculture_dominance_threshold: 0.999,
+            monoculture_min_count: 500,
+            energy_regen_per_cycle: 50.0,
+            coherence_regen_per_cycle: 0.001,
+            replication_attempts_per_frame: 5,
+            replication_cap: 1000,
+            replication_min_health: 50.0,
+            replication_min_energy: 50.0,
+            replication_cooldown_cycles: 10,
+            type_mutation_chance: 0.0,
+            genealogy_prune_dead: true,
+            despawn_budget_per_frame: 2000,
+            catastrophes_enabled: false,
+            catastrophe_interval: 500,
+            catastrophe_chance: 0.3,
+            catastrophe_duration: 50,
+            famine_regen_multiplier: 0.1,
+            coherence_storm_loss: 0.002,
+            world_width: 1000.0,
+            world_height: 700.0,
+            world_toroidal: false,
+            territory_enabled: true,
+            territory_recompute_interval: 10,
+            territory_owner_regen_multiplier: 1.5,
+            territory_intruder_regen_multiplier: 0.6,
+            overcrowding_enabled: false,
+            overcrowding_threshold: 25,
+            overcrowding_regen_penalty: 1.0,
+            overcrowding_coherence_loss: 0.002,
+            overcrowding_dispersal: 2.0,
+            max_population: POPULATION_HARD_CAP,
+            population_danger_fraction: 0.8,
+            hybrid_reproduction_enabled: false,
+            hybrid_radius: 12.0,
+            hybrid_min_health: 120.0,
+            hybrid_energy_cost: 40.0,
+            hybrid_chance: 0.02,
+            sexual_lineage: None,
+            mate_radius: 30.0,
+            attack_cost_model: AttackCostModel::ProportionalToDamage(0.25),
+            friendly_fire: false,
+            misfire_coherence_factor: 0.5,
+            manic_misfire_bonus: 0.15,
+            flee_threat_radius: 40.0,
+            flee_speed: 2.0,
+            steering_enabled: false,
+            steering_radius: 100.0,
+            steering_speed: 0.6,
+            intervention_energy_cost: 75.0,
+            intervention_cooldown_cycles: 15,
+            intervention_processing_per_conflict: 50.0,
+            symbiote_radius: 30.0,
+            symbiote_buff: 0.001,
+            symbiote_energy_cost: 1.0,
+            energy_sharing_enabled: false,
+            energy_share_radius: 30.0,
+            energy_share_donor_min: 200.0,
+            energy_share_reserve: 150.0,
+            energy_share_recipient_max: 50.0,
+            energy_share_max_per_cycle: 10.0,
+            energy_share_loss: 0.1,
+            decision_reliability_enabled: false,
+            fumble_energy_cost: 5.0,
+            scavenging_enabled: false,
+            corpse_energy_fraction: 0.5,
+            corpse_lifetime_cycles: 200,
+            consume_bite: 50.0,
+            food_enabled: false,
+            food_spawn_interval: 100,
+            food_spawn_count: 5,
+            food_max_sources: 40,
+            food_capacity: 400.0,
+            food_initial_fraction: 0.5,
+            food_regen_per_cycle: 2.0,
+            food_regen_jitter: 0.25,
+            resource_node_count: 0,
+            resource_node_capacity: 2000.0,
+            resource_node_radius: 40.0,
+            resource_node_bonus: 30.0,
+            resource_node_regen_per_cycle: 10.0,
+            resource_node_attraction: 0.5,
+            fitness_health_weight: 0.1,
+            fitness_combat_weight: 1.0,
+            fitness_discovery_weight: 5.0,
+            fitness_replication_weight: 2.0,
+            discovery_cooldown_cycles: 20,
+            discovery_max_chance: 0.05,
+            meta_ability_gate: MetaAbilityGate::default(),
+            research_sharing_enabled: false,
+            research_sharing_radius: 60.0,
+            research_sharing_per_peer: 0.5,
+            research_sharing_max_multiplier: 5.0,
+            knowledge_exchange_enabled: false,
+            knowledge_exchange_radius: 30.0,
+            knowledge_exchange_max_per_cycle: 2,
+            knowledge_exchange_energy_cost: 15.0,
+            knowledge_decay_enabled: false,
+            knowledge_decay_rate: 0.01,
+            knowledge_decay_stale_cycles: 500,
+            knowledge_decay_coherence_threshold: 0.4,
+            knowledge_decay_researcher_factor: 0.25,
+            max_knowledge: None,
+            fracture_enabled: false,
+            fracture_health_fraction: 0.2,
+            fracture_coherence_threshold: 0.3,
+            fracture_individual_count: 50,
+        }
+    }
+}
+
+/// Minimum capability required before any meta-ability can be discovered,
+/// so the override path needs a stable, capable Researcher rather than a lucky roll.
+#[derive(Debug, Clone, Copy)]
+pub struct MetaAbilityGate {
+    pub min_coherence: f32,
+    pub min_processing_plus_memory: f32,
+}
+
+impl Default for MetaAbilityGate {
+    fn default() -> Self {
+        Self {
+            min_coherence: 0.7,
+            min_processing_plus_memory: 60.0,
+        }
+    }
+}
+
+impl MetaAbilityGate {
+    pub fn allows(&self, coherence: f32, processing_power: f32, memory: f32) -> bool {
+        coherence >= self.min_coherence && processing_power + memory >= self.min_processing_plus_memory
+    }
+}
+
+/// Controls the starting population and any later immigration.
+/// `initial_ais = 0` is valid for immigration-only worlds.
+#[derive(Debug, Clone)]
+pub struct SeedConfig {
+    pub initial_ais: usize,
+    pub immigration_interval: u64, // Cycles between immigration waves; 0 disables immigration
+    pub immigration_count: usize, // AIs arriving per wave
+    pub stat_spread: StatSpread, // Per-individual noise on archetype base stats
+    pub lineage_weights: Option<HashMap<AILineage, f32>>, // Initial mix; unlisted lineages get none. `None` picks archetypes uniformly
+}
+
+impl Default for SeedConfig {
+    fn default() -> Self {
+        Self {
+            initial_ais: 200,
+            immigration_interval: 0,
+            immigration_count: 0,
+            stat_spread: StatSpread::default(),
+            lineage_weights: None,
+        }
+    }
+}
+
+impl SeedConfig {
+    /// Parses a `--seed-mix` value such as `HealerAI=0.9,KillerAI=0.1` into lineage weights.
+    pub fn parse_lineage_weights(spec: &str) -> Result<HashMap<AILineage, f32>, String> {
+        let mut weights = HashMap::new();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, weight) = entry.split_once('=').ok_or_else(|| format!("expected LINEAGE=WEIGHT, got '{}'", entry))?;
+            let lineage: AILineage = name.trim().parse().map_err(|e| format!("{}", e))?;
+            if lineage.seed_type().is_none() {
+                return Err(format!("'{}' is not a seed lineage", lineage));
+            }
+            let weight: f32 = weight.trim().parse().map_err(|_| format!("invalid weight '{}'", weight))?;
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(format!("weight for {} must be non-negative", lineage));
+            }
+            weights.insert(lineage, weight);
+        }
+        Ok(weights)
+    }
+
+    /// Whether new individuals keep arriving, so an empty world may still repopulate.
+    pub fn immigration_enabled(&self) -> bool {
+        self.immigration_interval > 0 && self.immigration_count > 0
+    }
+}
+
+/// How a single seed stat varies around its archetype base value.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum StatDistribution {
+    /// Always the archetype base value.
+    #[default]
+    Fixed,
+    /// Normally distributed around the base value.
+    Normal { stddev: f32 },
+    /// Base value plus a uniform offset in `min..=max`.
+    Uniform { min: f32, max: f32 },
+}
+
+impl StatDistribution {
+    /// Samples a value around `base`. Callers clamp the result into the stat's valid range.
+    pub fn sample<R: Rng>(&self, base: f32, rng: &mut R) -> f32 {
+        match *self {
+            StatDistribution::Fixed => base,
+            StatDistribution::Normal { stddev } => {
+                if stddev <= 0.0 {
+                    return base;
+                }
+                // Box-Muller transform; avoids pulling in rand_distr for a single distribution.
+                let u1: f32 = rng.gen_range(f32::EPSILON..1.0);
+                let u2: f32 = rng.gen();
+                let z = (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos();
+                base + z * stddev
+            }
+            StatDistribution::Uniform { min, max } => {
+                if max <= min {
+                    return base + min;
+                }
+                base + rng.gen_range(min..=max)
+            }
+        }
+    }
+}
+
+/// Parses a single stat's distribution: `fixed`, `normal:STDDEV` or `uniform:MIN:MAX`.
+impl FromStr for StatDistribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split(':');
+        let kind = parts.next().unwrap_or_default();
+        let params = parts.map(|p| match p.trim().parse::<f32>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            _ => Err(format!("invalid number '{}'", p)),
+        }).collect::<Result<Vec<f32>, String>>()?;
+        match (kind, params.as_slice()) {
+            ("fixed", []) => Ok(StatDistribution::Fixed),
+            ("normal", [stddev]) if *stddev >= 0.0 => Ok(StatDistribution::Normal { stddev: *stddev }),
+            ("uniform", [min, max]) if min <= max => Ok(StatDistribution::Uniform { min: *min, max: *max }),
+            _ => Err(format!("expected fixed, normal:STDDEV or uniform:MIN:MAX, got '{}'", s)),
+        }
+    }
+}
+
+/// Distributions for each seeded base stat. All `Fixed` reproduces identical archetypes.
+#[derive(Debug, Clone, Default)]
+pub struct StatSpread {
+    pub health: StatDistribution,
+    pub energy: StatDistribution,
+    pub processing_power: StatDistribution,
+    pub memory: StatDistribution,
+    pub coherence: StatDistribution,
+    pub adaptability: StatDistribution,
+    pub resilience: StatDistribution,
+    pub replication_efficiency: StatDistribution,
+    pub combat_strength: StatDistribution,
+    pub defense_strength: StatDistribution,
+}
+
+impl StatSpread {
+    /// Parses a `--stat-spread` value such as `health=normal:10,combat_strength=uniform:-5:5`.
+    /// Unlisted stats stay `Fixed`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut spread = StatSpread::default();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (stat, distribution) = entry.split_once('=').ok_or_else(|| format!("expected STAT=DISTRIBUTION, got '{}'", entry))?;
+            let slot = match stat.trim() {
+                "health" => &mut spread.health,
+                "energy" => &mut spread.energy,
+                "processing_power" => &mut spread.processing_power,
+                "memory" => &mut spread.memory,
+                "coherence" => &mut spread.coherence,
+                "adaptability" => &mut spread.adaptability,
+                "resilience" => &mut spread.resilience,
+                "replication_efficiency" => &mut spread.replication_efficiency,
+                "combat_strength" => &mut spread.combat_strength,
+                "defense_strength" => &mut spread.defense_strength,
+                other => return Err(format!("unknown stat '{}'", other)),
+            };
+            *slot = distribution.parse()?;
+        }
+        Ok(spread)
+    }
+}
+
+/// Starting profile for the GODAI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GodaiPreset {
+    /// Original stats, no regeneration.
+    Standard,
+    /// Halved combat stats and no regeneration; a wounded Passive GODAI stays beatable.
+    Passive,
+    /// Stronger combat stats and steady self-regeneration that can out-sustain a weak monoculture.
+    Overlord,
+}
+
+/// Parses a `--godai-preset` value: `standard`, `passive` or `overlord`.
+impl FromStr for GodaiPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "standard" => Ok(GodaiPreset::Standard),
+            "passive" => Ok(GodaiPreset::Passive),
+            "overlord" => Ok(GodaiPreset::Overlord),
+            _ => Err(format!("unknown preset '{}' (expected standard, passive or overlord)", s)),
+        }
+    }
+}
+
+/// How much energy an individual attack costs the attacker.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttackCostModel {
+    /// Fixed energy per attack regardless of output (cheap big hits).
+    Flat(f32),
+    /// Fraction of the damage dealt (the original `damage / 4` rule is `0.25`).
+    ProportionalToDamage(f32),
+    /// Fraction of the attacker's combat strength, independent of the damage roll.
+    ProportionalToStrength(f32),
+}
+
+impl AttackCostModel {
+    pub fn energy_cost(&self, damage_dealt: f32, combat_strength: f32) -> f32 {
+        match *self {
+            AttackCostModel::Flat(cost) => cost,
+            AttackCostModel::ProportionalToDamage(factor) => damage_dealt * factor,
+            AttackCostModel::ProportionalToStrength(factor) => combat_strength * factor,
+        }
+    }
+}
+
+/// Parses an `--attack-cost` value: `flat:COST`, `damage:FACTOR` or `strength:FACTOR`.
+impl FromStr for AttackCostModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (model, amount) = s.split_once(':').ok_or_else(|| format!("expected MODEL:AMOUNT, got '{}'", s))?;
+        let amount: f32 = amount.trim().parse().map_err(|_| format!("invalid amount '{}'", amount))?;
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(format!("amount must be non-negative, got {}", amount));
+        }
+        match model.trim() {
+            "flat" => Ok(AttackCostModel::Flat(amount)),
+            "damage" => Ok(AttackCostModel::ProportionalToDamage(amount)),
+            "strength" => Ok(AttackCostModel::ProportionalToStrength(amount)),
+            other => Err(format!("unknown model '{}' (expected flat, damage or strength)", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn fixed_and_zero_spread_distributions_return_the_base() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        for distribution in [StatDistribution::Fixed, StatDistribution::Normal { stddev: 0.0 }, StatDistribution::Uniform { min: 0.0, max: 0.0 }] {
+            for _ in 0..100 {
+                assert_eq!(distribution.sample(42.0, &mut rng), 42.0, "{:?}", distribution);
+            }
+        }
+    }
+
+    #[test]
+    fn uniform_samples_stay_within_their_offsets() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(2);
+        let distribution = StatDistribution::Uniform { min: -5.0, max: 10.0 };
+        let samples: Vec<f32> = (0..1000).map(|_| distribution.sample(50.0, &mut rng)).collect();
+        assert!(samples.iter().all(|v| (45.0..=60.0).contains(v)), "{:?}", samples);
+        // A reversed range collapses to its lower offset.
+        assert_eq!(StatDistribution::Uniform { min: 3.0, max: 1.0 }.sample(50.0, &mut rng), 53.0);
+    }
+
+    #[test]
+    fn normal_samples_center_on_the_base() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let distribution = StatDistribution::Normal { stddev: 2.0 };
+        let samples: Vec<f32> = (0..5000).map(|_| distribution.sample(100.0, &mut rng)).collect();
+        let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+        let variance = samples.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / samples.len() as f32;
+        assert!((mean - 100.0).abs() < 0.2, "mean {}", mean);
+        assert!((variance.sqrt() - 2.0).abs() < 0.2, "stddev {}", variance.sqrt());
+    }
+
+    #[test]
+    fn stat_spread_parses_per_stat_distributions() {
+        let spread = StatSpread::parse("health=normal:10, combat_strength=uniform:-5:5,memory=fixed").unwrap();
+        assert_eq!(spread.health, StatDistribution::Normal { stddev: 10.0 });
+        assert_eq!(spread.combat_strength, StatDistribution::Uniform { min: -5.0, max: 5.0 });
+        assert_eq!(spread.memory, StatDistribution::Fixed);
+        assert_eq!(spread.energy, StatDistribution::Fixed);
+        for invalid in ["health", "luck=normal:1", "health=normal", "health=normal:-1", "health=uniform:5:1", "health=normal:inf"] {
+            assert!(StatSpread::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn godai_presets_parse_from_their_flag_form() {
+        assert_eq!("passive".parse(), Ok(GodaiPreset::Passive));
+        assert_eq!("Overlord".parse(), Ok(GodaiPreset::Overlord));
+        assert_eq!("standard".parse(), Ok(GodaiPreset::Standard));
+        assert!("tyrant".parse::<GodaiPreset>().is_err());
+    }
+
+    #[test]
+    fn attack_cost_models_parse_from_their_flag_form() {
+        assert_eq!("flat:7".parse(), Ok(AttackCostModel::Flat(7.0)));
+        assert_eq!("damage:0.25".parse(), Ok(AttackCostModel::ProportionalToDamage(0.25)));
+        assert_eq!("strength:0.5".parse(), Ok(AttackCostModel::ProportionalToStrength(0.5)));
+        for invalid in ["flat", "flat:-1", "flat:NaN", "free:1"] {
+            assert!(invalid.parse::<AttackCostModel>().is_err(), "{}", invalid);
+        }
+    }
+}
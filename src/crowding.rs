@@ -0,0 +1,27 @@
+use crate::ai::AIType;
+use crate::config::SimConfig;
+
+/// Multiplier on the crowding threshold an archetype tolerates before it suffers.
+pub fn crowding_tolerance(ai_type: AIType) -> f32 {
+    match ai_type {
+        AIType::Peacekeeper => 1.5,
+        AIType::Guardian => 1.25,
+        AIType::Manic => 0.5,
+        _ => 1.0,
+    }
+}
+
+/// How far over its tolerated density an AI's cell is, as a fraction of the threshold
+/// (0 when not crowded or when the penalty is disabled).
+pub fn overcrowding_excess(cell_population: usize, ai_type: AIType, config: &SimConfig) -> f32 {
+    if !config.overcrowding_enabled || config.overcrowding_threshold == 0 {
+        return 0.0;
+    }
+    let tolerated = config.overcrowding_threshold as f32 * crowding_tolerance(ai_type);
+    ((cell_population as f32 - tolerated) / tolerated).max(0.0)
+}
+
+/// Energy regen multiplier under crowding; contention shrinks regen as excess grows.
+pub fn crowding_regen_multiplier(excess: f32, config: &SimConfig) -> f32 {
+    1.0 / (1.0 + excess * config.overcrowding_regen_penalty)
+}
@@ -0,0 +1,72 @@
+use std::path::Path;
+use bevy::prelude::Resource; // Import Resource from Bevy
+
+/// File the panel layout is persisted to, in the working directory.
+pub const DASHBOARD_LAYOUT_FILE: &str = "dashboard_layout.cfg";
+
+/// Which dashboard panels are open. Toggled from the "Panels" menu and
+/// persisted between sessions as `name=true|false` lines.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct DashboardLayout {
+    pub controls: bool,
+    pub lineage_knowledge: bool,
+    pub fitness_leaderboard: bool,
+    pub freeze_lineages: bool,
+    pub combat_forecast: bool,
+    pub population_history: bool,
+    pub event_log: bool,
+    pub god_mode: bool,
+}
+
+impl Default for DashboardLayout {
+    fn default() -> Self {
+        Self {
+            controls: true,
+            lineage_knowledge: false,
+            fitness_leaderboard: false,
+            freeze_lineages: false,
+            combat_forecast: true,
+            population_history: true,
+            event_log: true,
+            god_mode: false,
+        }
+    }
+}
+
+impl DashboardLayout {
+    /// Menu label and open flag for every panel, in menu order.
+    pub fn panels_mut(&mut self) -> [(&'static str, &mut bool); 8] {
+        [
+            ("Simulation Controls", &mut self.controls),
+            ("Lineage Knowledge", &mut self.lineage_knowledge),
+            ("Fitness Leaderboard", &mut self.fitness_leaderboard),
+            ("Freeze Lineages", &mut self.freeze_lineages),
+            ("Combat Forecast", &mut self.combat_forecast),
+            ("Population History", &mut self.population_history),
+            ("Event Log", &mut self.event_log),
+            ("God Mode", &mut self.god_mode),
+        ]
+    }
+
+    /// Loads the saved layout, falling back to defaults for a missing file or unknown keys.
+    pub fn load(path: &Path) -> Self {
+        let mut layout = Self::default();
+        let Ok(contents) = std::fs::read_to_string(path) else { return layout; };
+        for line in contents.lines() {
+            let Some((key, value)) = line.split_once('=') else { continue; };
+            let Ok(open) = value.trim().parse::<bool>() else { continue; };
+            if let Some((_, flag)) = layout.panels_mut().into_iter().find(|(name, _)| *name == key.trim()) {
+                *flag = open;
+            }
+        }
+        layout
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let mut layout = self.clone();
+        let contents: String = layout.panels_mut().iter()
+            .map(|(name, open)| format!("{}={}\n", name, open))
+            .collect();
+        std::fs::write(path, contents).map_err(|e| format!("could not write {}: {}", path.display(), e))
+    }
+}
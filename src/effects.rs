@@ -0,0 +1,27 @@
+use bevy::prelude::{Component, Timer, TimerMode, Vec2};
+
+/// Seconds a floating damage number stays on screen while it fades.
+pub const DAMAGE_NUMBER_SECONDS: f32 = 0.5;
+
+/// World units a damage number rises over its lifetime.
+pub const DAMAGE_NUMBER_RISE: f32 = 20.0;
+
+/// Seconds an extinction notice stays on screen.
+pub const EXTINCTION_NOTICE_SECONDS: f32 = 4.0;
+
+/// Despawns its entity once the timer finishes.
+#[derive(Component)]
+pub struct Lifetime(pub Timer);
+
+impl Lifetime {
+    pub fn from_seconds(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+}
+
+/// Damage dealt by one attack, drawn at the world position where it landed.
+#[derive(Component)]
+pub struct FloatingDamage {
+    pub damage: f32,
+    pub pos: Vec2,
+}
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use bevy::prelude::Resource; // Import Resource from Bevy
+
+use crate::ai::AILineage;
+use crate::common::{CombatStrength, Health, KnowledgeBase, ReplicatedCount};
+use crate::config::SimConfig;
+
+/// Single comparable score for one individual: a weighted sum of its health,
+/// combat strength, discoveries and replications.
+pub fn fitness(health: &Health, combat_strength: &CombatStrength, knowledge_base: &KnowledgeBase, replicated_count: &ReplicatedCount, config: &SimConfig) -> f32 {
+    health.0 * config.fitness_health_weight
+        + combat_strength.0 * config.fitness_combat_weight
+        + knowledge_base.0.len() as f32 * config.fitness_discovery_weight
+        + replicated_count.0 as f32 * config.fitness_replication_weight
+}
+
+/// Average fitness per lineage, best first, resampled every `LOG_INTERVAL` cycles.
+#[derive(Resource, Default)]
+pub struct LineageFitness {
+    pub leaderboard: Vec<(AILineage, f32)>,
+    pub last_sample_cycle: u64,
+}
+
+impl LineageFitness {
+    /// Averages the given living members' fitness by lineage and ranks the lineages.
+    pub fn rank(members: impl Iterator<Item = (AILineage, f32)>) -> Vec<(AILineage, f32)> {
+        let mut sums: HashMap<AILineage, (f32, usize)> = HashMap::new();
+        for (lineage, score) in members {
+            let (sum, count) = sums.entry(lineage).or_insert((0.0, 0));
+            *sum += score;
+            *count += 1;
+        }
+        let mut leaderboard: Vec<(AILineage, f32)> = sums.into_iter()
+            .map(|(lineage, (sum, count))| (lineage, sum / count as f32))
+            .collect();
+        leaderboard.sort_by(|a, b| b.1.total_cmp(&a.1));
+        leaderboard
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::{Component, Resource, Vec2}; // Import Bevy types used by foraging
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::common::Energy;
+use crate::config::SimConfig;
+
+/// Mixed into the run seed to fork the food stream off `SimRng`.
+const FOOD_STREAM_SALT: u64 = 0xF00D_F00D_F00D_F00D;
+
+/// Distance (world units) within which a living AI can draw from a `StoredEnergy` entity.
+pub const CONSUME_RADIUS: f32 = 15.0;
+
+/// Energy cap shared with regeneration in `ai_internal_state_system`.
+pub const MAX_INDIVIDUAL_ENERGY: f32 = 5000.0;
+
+/// Energy held by a non-AI entity that nearby living AIs can consume.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct StoredEnergy(pub f32);
+
+/// Remains of a dead AI; carries `StoredEnergy` until eaten or until it expires.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Corpse {
+    pub expires_at_cycle: u64,
+}
+
+/// A permanent food patch whose `StoredEnergy` regrows toward `capacity`.
+/// Each source owns an RNG seeded from the food stream at spawn, so its regrowth
+/// doesn't depend on the order sources are visited.
+#[derive(Component)]
+pub struct FoodSource {
+    pub capacity: f32,
+    pub rng: StdRng,
+}
+
+/// A fixed energy hotspot placed at startup. AIs within `resource_node_radius` draw bonus energy from it
+/// in `ai_internal_state_system`, depleting it; it regrows toward `capacity`.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ResourceNode {
+    pub capacity: f32,
+    pub level: f32,
+}
+
+/// Index of the node closest to `pos` within `radius`, if any.
+pub fn nearest_node(nodes: &[Vec2], pos: Vec2, radius: f32) -> Option<usize> {
+    nodes.iter()
+        .enumerate()
+        .map(|(i, node)| (i, node.distance_squared(pos)))
+        .filter(|(_, dist_sq)| *dist_sq <= radius * radius)
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(i, _)| i)
+}
+
+/// Deterministic food schedule.
+/// Waves spawn on fixed cycle boundaries, and all positions come from a stream
+/// forked off `SimRng`'s seed. Other systems' draws therefore never shift food
+/// placement, and identical seeds give identical food.
+#[derive(Resource)]
+pub struct FoodEconomy {
+    pub rng: StdRng,
+    pub next_spawn_cycle: u64,
+    pub last_regen_cycle: u64,
+}
+
+impl FoodEconomy {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed ^ FOOD_STREAM_SALT),
+            next_spawn_cycle: 0,
+            last_regen_cycle: 0,
+        }
+    }
+
+    /// Plans a spawn wave: `count` positions inside `half_extents` of the origin, plus one RNG per source.
+    pub fn spawn_wave(&mut self, count: usize, half_extents: Vec2) -> Vec<(Vec2, StdRng)> {
+        (0..count)
+            .map(|_| {
+                let pos = Vec2::new(
+                    self.rng.gen_range(-half_extents.x..half_extents.x),
+                    self.rng.gen_range(-half_extents.y..half_extents.y),
+                );
+                (pos, StdRng::seed_from_u64(self.rng.gen()))
+            })
+            .collect()
+    }
+}
+
+/// Regrows a food source over `cycles` cycles, jittered by `config.food_regen_jitter`.
+/// Returns the amount added.
+pub fn regen_food(stored: &mut StoredEnergy, source: &mut FoodSource, cycles: u64, config: &SimConfig) -> f32 {
+    let jitter = config.food_regen_jitter.clamp(0.0, 1.0);
+    let factor = if jitter > 0.0 { 1.0 + source.rng.gen_range(-jitter..=jitter) } else { 1.0 };
+    let room = (source.capacity - stored.0).max(0.0);
+    let amount = (config.food_regen_per_cycle * cycles as f32 * factor).min(room).max(0.0);
+    stored.0 += amount;
+    amount
+}
+
+/// Transfers up to `bite` energy from `stored` to `consumer`, respecting the consumer's cap.
+/// Returns the amount transferred.
+pub fn consume_stored_energy(stored: &mut StoredEnergy, consumer: &mut Energy, bite: f32) -> f32 {
+    let room = (MAX_INDIVIDUAL_ENERGY - consumer.0).max(0.0);
+    let amount = bite.min(stored.0).min(room).max(0.0);
+    stored.0 -= amount;
+    consumer.0 += amount;
+    amount
+}
+
+/// Energy a dying AI leaves behind: its stored energy plus remaining health as biomass,
+/// scaled by the configured recovery fraction.
+pub fn corpse_energy(energy: f32, health: f32, recovery_fraction: f32) -> f32 {
+    (energy.max(0.0) + health.max(0.0)) * recovery_fraction.clamp(0.0, 1.0)
+}
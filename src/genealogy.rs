@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use bevy::prelude::Resource; // Import Resource from Bevy
+
+/// One individual in the family tree.
+#[derive(Debug, Clone, Default)]
+pub struct GenealogyNode {
+    pub parent: Option<String>,
+    pub co_parent: Option<String>, // A hybrid's second parent; `parent` is the first
+    pub children: Vec<String>,
+    pub cycle_born: u64,
+    pub alive: bool,
+    pub pruned_descendants: usize, // Descendants already pruned from `children`, so counts stay exact
+}
+
+/// Parent -> child relationships from replication, keyed by `AIEntity.id`.
+/// Seed individuals enter the tree as roots the first time they replicate.
+#[derive(Resource, Default)]
+pub struct Genealogy {
+    pub nodes: HashMap<String, GenealogyNode>,
+    pub prune_dead: bool, // Drop dead, childless nodes as they appear
+}
+
+impl Genealogy {
+    pub fn new(prune_dead: bool) -> Self {
+        Self { nodes: HashMap::new(), prune_dead }
+    }
+
+    pub fn record_birth(&mut self, parent_id: &str, parent_cycle_born: u64, child_id: &str, child_cycle_born: u64) {
+        let parent = self.nodes.entry(parent_id.to_string()).or_insert_with(|| GenealogyNode {
+            cycle_born: parent_cycle_born,
+            alive: true,
+            ..Default::default()
+        });
+        parent.children.push(child_id.to_string());
+        self.nodes.insert(child_id.to_string(), GenealogyNode {
+            parent: Some(parent_id.to_string()),
+            cycle_born: child_cycle_born,
+            alive: true,
+            ..Default::default()
+        });
+    }
+
+    /// Records a hybrid or sexually replicated child under both of its parents, so it counts as a descendant of each.
+    pub fn record_hybrid_birth(&mut self, first: (&str, u64), second: (&str, u64), child_id: &str, child_cycle_born: u64) {
+        self.record_birth(first.0, first.1, child_id, child_cycle_born);
+        let co_parent = self.nodes.entry(second.0.to_string()).or_insert_with(|| GenealogyNode {
+            cycle_born: second.1,
+            alive: true,
+            ..Default::default()
+        });
+        co_parent.children.push(child_id.to_string());
+        if let Some(child) = self.nodes.get_mut(child_id) {
+            child.co_parent = Some(second.0.to_string());
+        }
+    }
+
+    /// Marks an individual dead, pruning it (and any ancestors left dead and childless) when enabled.
+    pub fn record_death(&mut self, id: &str) {
+        let Some(node) = self.nodes.get_mut(id) else { return };
+        node.alive = false;
+        if !self.prune_dead {
+            return;
+        }
+        let mut pending = vec![id.to_string()];
+        while let Some(current) = pending.pop() {
+            let Some(node) = self.nodes.get(&current) else { continue };
+            if node.alive || !node.children.is_empty() {
+                continue;
+            }
+            let node = self.nodes.remove(&current).unwrap();
+            for parent_id in node.parent.into_iter().chain(node.co_parent) {
+                let Some(parent) = self.nodes.get_mut(&parent_id) else { continue };
+                parent.children.retain(|child| *child != current);
+                parent.pruned_descendants += 1 + node.pruned_descendants;
+                pending.push(parent_id);
+            }
+        }
+    }
+
+    /// Every individual descended from `id`, including pruned ones.
+    pub fn descendant_count(&self, id: &str) -> usize {
+        let mut count = 0;
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            let Some(node) = self.nodes.get(current) else { continue };
+            count += node.pruned_descendants + node.children.len();
+            stack.extend(node.children.iter().map(String::as_str));
+        }
+        count
+    }
+
+    /// The root with the most descendants, with its count. Ties go to the earliest-born root.
+    pub fn most_prolific_ancestor(&self) -> Option<(&str, usize)> {
+        self.nodes.iter()
+            .filter(|(_, node)| node.parent.is_none())
+            .map(|(id, node)| (id.as_str(), node.cycle_born, self.descendant_count(id)))
+            .max_by(|a, b| a.2.cmp(&b.2).then(b.1.cmp(&a.1)).then(b.0.cmp(a.0)))
+            .map(|(id, _, count)| (id, count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hybrid_is_a_descendant_of_both_parents() {
+        let mut genealogy = Genealogy::new(true);
+        genealogy.record_hybrid_birth(("Killer-1", 0), ("Healer-2", 3), "Hybrid-3", 10);
+        assert_eq!(genealogy.descendant_count("Killer-1"), 1);
+        assert_eq!(genealogy.descendant_count("Healer-2"), 1);
+        let child = &genealogy.nodes["Hybrid-3"];
+        assert_eq!(child.parent.as_deref(), Some("Killer-1"));
+        assert_eq!(child.co_parent.as_deref(), Some("Healer-2"));
+
+        // Once everyone is dead, pruning walks up through both parents.
+        genealogy.record_death("Killer-1");
+        genealogy.record_death("Healer-2");
+        assert_eq!(genealogy.nodes.len(), 3);
+        genealogy.record_death("Hybrid-3");
+        assert!(genealogy.nodes.is_empty());
+    }
+}
@@ -0,0 +1,32 @@
+use bevy::prelude::Resource;
+
+use crate::ai::AIType;
+
+/// Largest batch the God Mode panel spawns with one click.
+pub const MAX_SPAWN_BATCH: u32 = 50;
+
+/// Radius (world units) around the clicked point that a spawned batch is scattered over.
+pub const SPAWN_SCATTER_RADIUS: f32 = 15.0;
+
+/// Health a divine heal restores an individual AI up to, matching `AIEntity::heal`.
+pub const HEAL_CAP: f32 = 200.0;
+
+/// Settings of the God Mode panel, which spawns, kills, damages and heals directly.
+#[derive(Resource)]
+pub struct GodMode {
+    pub spawn_type: AIType,
+    pub spawn_count: u32,
+    pub amount: f32, // Damage dealt or health restored per press
+    pub placing: bool, // The next click on the world spawns `spawn_count` AIs there
+}
+
+impl Default for GodMode {
+    fn default() -> Self {
+        Self {
+            spawn_type: AIType::Base,
+            spawn_count: 5,
+            amount: 50.0,
+            placing: false,
+        }
+    }
+}
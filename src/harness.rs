@@ -0,0 +1,135 @@
+use bevy::prelude::With;
+
+use crate::ai::AILineage;
+use crate::common::{
+    Health, Energy, ProcessingPower, Memory, Coherence, Adaptability, Resilience,
+    ReplicationEfficiency, CombatStrength, DefenseStrength, IsAlive,
+};
+use crate::config::SimConfig;
+use crate::invariants::AIStatsView;
+use crate::rng::SimRng;
+use crate::simulation::Simulation;
+
+/// Cycles a harness run lasts unless `--cycles` says otherwise.
+pub const DEFAULT_HARNESS_CYCLES: u64 = 500;
+
+/// What one headless run ended with, compared across runs to check determinism.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HarnessOutcome {
+    pub final_cycle: u64,
+    pub conclusion: Option<String>,
+    pub population: usize,
+    pub lineage_counts: Vec<(AILineage, usize)>, // In `seed_ai_archetypes` order
+}
+
+/// Result of a harness run: the outcome plus every invariant violation seen along the way.
+pub struct HarnessRun {
+    pub outcome: HarnessOutcome,
+    pub violations: Vec<String>,
+}
+
+/// Runs the full headless app for `cycles` cycles from `seed`, checking every living
+/// individual's stats against `AIStatsView::violations` after each frame.
+pub fn run_harness(config: &SimConfig, seed: u64, cycles: u64) -> HarnessRun {
+    let mut config = config.clone();
+    config.max_cycles = cycles;
+    config.rng_seed = Some(seed);
+    config.command_channel = false;
+    config.report_path = None;
+    let mut app = crate::build_app(config, SimRng::from_seed(seed), true);
+    let mut violations = Vec::new();
+    let mut query = app.world.query_filtered::<(
+        (&Health, &Energy, &ProcessingPower, &Memory, &Coherence, &Adaptability),
+        (&Resilience, &ReplicationEfficiency, &CombatStrength, &DefenseStrength, &IsAlive, &AILineage),
+    ), With<crate::IndividualAI>>();
+    // Every running frame advances at least one cycle; the slack covers startup frames.
+    for _ in 0..cycles + 10 {
+        app.update();
+        let cycle = app.world.resource::<Simulation>().current_cycle;
+        for (
+            (health, energy, processing_power, memory, coherence, adaptability),
+            (resilience, replication_efficiency, combat_strength, defense_strength, is_alive, _),
+        ) in query.iter(&app.world)
+        {
+            if !is_alive.0 {
+                continue;
+            }
+            let stats = AIStatsView {
+                health, energy, processing_power, memory, coherence, adaptability,
+                resilience, replication_efficiency, combat_strength, defense_strength, is_alive,
+            };
+            for violation in stats.violations() {
+                violations.push(format!("cycle {}: {}", cycle, violation));
+            }
+        }
+        if app.world.resource::<Simulation>().simulation_over_reason.is_some() {
+            break;
+        }
+    }
+    let sim = app.world.resource::<Simulation>();
+    let (final_cycle, conclusion) = (sim.current_cycle, sim.simulation_over_reason.clone());
+    let living: Vec<AILineage> = query.iter(&app.world)
+        .filter(|(_, (.., is_alive, _))| is_alive.0)
+        .map(|(_, (.., lineage))| lineage.clone())
+        .collect();
+    let lineage_counts = crate::simulation::seed_ai_archetypes().into_iter()
+        .map(|(lineage, _)| {
+            let count = living.iter().filter(|l| **l == lineage).count();
+            (lineage, count)
+        })
+        .collect();
+    HarnessRun {
+        outcome: HarnessOutcome { final_cycle, conclusion, population: living.len(), lineage_counts },
+        violations,
+    }
+}
+
+/// Runs the harness twice from the same seed. Returns a report, or every failure found:
+/// invariant violations, a run that didn't terminate, or runs that diverged.
+pub fn check_reproducible(config: &SimConfig, seed: u64, cycles: u64) -> Result<String, Vec<String>> {
+    let first = run_harness(config, seed, cycles);
+    let second = run_harness(config, seed, cycles);
+    let mut failures = first.violations.clone();
+    if first.outcome.conclusion.is_none() {
+        failures.push(format!("run did not terminate within {} cycles", cycles));
+    }
+    if first.outcome != second.outcome {
+        failures.push(format!("runs diverged for seed {}: {:?} vs {:?}", seed, first.outcome, second.outcome));
+    }
+    if !failures.is_empty() {
+        return Err(failures);
+    }
+    Ok(format!(
+        "Seed {}: cycle {}, population {}, {}",
+        seed, first.outcome.final_cycle, first.outcome.population,
+        first.outcome.conclusion.as_deref().unwrap_or("no conclusion"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SeedConfig;
+
+    #[test]
+    fn immigration_grows_a_population_from_nothing() {
+        let config = SimConfig {
+            seed: SeedConfig { initial_ais: 0, immigration_interval: 10, immigration_count: 5, ..SeedConfig::default() },
+            ..SimConfig::default()
+        };
+        let run = run_harness(&config, 7, 100);
+        assert!(run.violations.is_empty(), "{:?}", run.violations);
+        assert_eq!(run.outcome.final_cycle, 100, "empty world ended early: {:?}", run.outcome.conclusion);
+        assert!(run.outcome.population > 0);
+    }
+
+    #[test]
+    fn a_small_seeded_run_is_reproducible() {
+        let config = SimConfig {
+            seed: SeedConfig { initial_ais: 40, ..SeedConfig::default() },
+            ..SimConfig::default()
+        };
+        let report = check_reproducible(&config, 11, 60);
+        assert!(report.is_ok(), "{:?}", report);
+    }
+}
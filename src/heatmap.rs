@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+use bevy::prelude::{Resource, Vec2};
+
+use crate::spatial::{CellCoord, SpatialGrid, GRID_CELL_SIZE};
+
+/// Coarsest heatmap resolution, in grid cells per tile side.
+pub const MAX_CELLS_PER_TILE: i32 = 8;
+
+/// Peak opacity of the densest tile.
+pub const HEATMAP_MAX_ALPHA: f32 = 0.45;
+
+/// Population density overlay, drawn by `density_heatmap_ui_system`. Off by default.
+#[derive(Resource)]
+pub struct DensityHeatmap {
+    pub enabled: bool,
+    pub cells_per_tile: i32, // Tile side in spatial grid cells; larger is coarser
+}
+
+impl Default for DensityHeatmap {
+    fn default() -> Self {
+        Self { enabled: false, cells_per_tile: 1 }
+    }
+}
+
+impl DensityHeatmap {
+    /// Living AIs per tile, binned from the spatial grid's cells.
+    pub fn tile_counts(&self, grid: &SpatialGrid) -> HashMap<CellCoord, usize> {
+        let n = self.cells_per_tile.max(1);
+        let mut counts = HashMap::new();
+        for (cell, entries) in &grid.cells {
+            *counts.entry((cell.0.div_euclid(n), cell.1.div_euclid(n))).or_insert(0) += entries.len();
+        }
+        counts
+    }
+
+    /// World-space lower-left corner and size of a tile.
+    pub fn tile_rect(&self, tile: CellCoord) -> (Vec2, f32) {
+        let side = self.cells_per_tile.max(1) as f32 * GRID_CELL_SIZE;
+        (Vec2::new(tile.0 as f32, tile.1 as f32) * side, side)
+    }
+}
+
+/// Cold-to-hot ramp for a density in 0..=1: blue through yellow to red.
+pub fn heat_rgb(density: f32) -> [u8; 3] {
+    let t = density.clamp(0.0, 1.0);
+    if t < 0.5 {
+        let s = t * 2.0;
+        [(255.0 * s) as u8, (255.0 * s) as u8, (255.0 * (1.0 - s)) as u8]
+    } else {
+        let s = (t - 0.5) * 2.0;
+        [255, (255.0 * (1.0 - s)) as u8, 0]
+    }
+}
@@ -0,0 +1,41 @@
+use bevy::prelude::Resource;
+use uuid::Uuid;
+
+use crate::ai::AIType;
+
+/// Hands out individual AI ids. By default they are numbered in spawn order from 1, so a seeded
+/// run names (and records the genealogy of) its AIs identically every time.
+/// `--uuid-ids` falls back to random UUID fragments.
+#[derive(Resource, Debug, Clone)]
+pub struct IdGenerator {
+    next: u64,
+    uuid: bool,
+}
+
+impl IdGenerator {
+    pub fn new(uuid: bool) -> Self {
+        Self { next: 1, uuid }
+    }
+
+    /// `<prefix>-<number>-<type>`, e.g. `Replica-42-Killer`.
+    pub fn next_id(&mut self, prefix: &str, ai_type: AIType) -> String {
+        if self.uuid {
+            return format!("{}-{}-{:?}", prefix, Uuid::new_v4().to_string().chars().take(4).collect::<String>(), ai_type);
+        }
+        let number = self.next;
+        self.next += 1;
+        format!("{}-{}-{:?}", prefix, number, ai_type)
+    }
+
+    /// Number the next id will get, for saves.
+    pub fn next_number(&self) -> u64 {
+        self.next
+    }
+
+    /// Continues numbering from a save. Saves from before ids were numbered carry 0 and keep the current count.
+    pub fn resume_from(&mut self, next: u64) {
+        if next > 0 {
+            self.next = next;
+        }
+    }
+}
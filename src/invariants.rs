@@ -0,0 +1,111 @@
+use bevy::prelude::Resource; // Import Resource from Bevy
+
+use crate::common::{
+    Health, Energy, ProcessingPower, Memory, Coherence, Adaptability, Resilience,
+    ReplicationEfficiency, CombatStrength, DefenseStrength, IsAlive, LastAction,
+};
+
+/// Upper bound on individual AI health (matches the clamps in repair and healing).
+pub const MAX_INDIVIDUAL_HEALTH: f32 = 200.0;
+
+/// Environment variable that enables invariant checks in release builds.
+pub const INVARIANT_CHECK_ENV_VAR: &str = "AI_SIM_CHECK_INVARIANTS";
+
+/// Whether `invariant_check_system` is active.
+/// On by default in debug builds; release builds opt in via `AI_SIM_CHECK_INVARIANTS`.
+#[derive(Resource)]
+pub struct InvariantChecks {
+    pub enabled: bool,
+}
+
+impl Default for InvariantChecks {
+    fn default() -> Self {
+        Self {
+            enabled: cfg!(debug_assertions) || std::env::var_os(INVARIANT_CHECK_ENV_VAR).is_some(),
+        }
+    }
+}
+
+/// Borrowed view of the stats checked for a single living AI.
+pub struct AIStatsView<'a> {
+    pub health: &'a Health,
+    pub energy: &'a Energy,
+    pub processing_power: &'a ProcessingPower,
+    pub memory: &'a Memory,
+    pub coherence: &'a Coherence,
+    pub adaptability: &'a Adaptability,
+    pub resilience: &'a Resilience,
+    pub replication_efficiency: &'a ReplicationEfficiency,
+    pub combat_strength: &'a CombatStrength,
+    pub defense_strength: &'a DefenseStrength,
+    pub is_alive: &'a IsAlive,
+}
+
+impl AIStatsView<'_> {
+    /// Returns a description of every violated invariant (empty when the AI is consistent).
+    pub fn violations(&self) -> Vec<String> {
+        let mut violations = Vec::new();
+        let unit_stats = [
+            ("coherence", self.coherence.0),
+            ("adaptability", self.adaptability.0),
+            ("resilience", self.resilience.0),
+            ("replication_efficiency", self.replication_efficiency.0),
+        ];
+        let non_negative_stats = [
+            ("health", self.health.0),
+            ("energy", self.energy.0),
+            ("processing_power", self.processing_power.0),
+            ("memory", self.memory.0),
+            ("combat_strength", self.combat_strength.0),
+            ("defense_strength", self.defense_strength.0),
+        ];
+        for (name, value) in unit_stats.iter().chain(non_negative_stats.iter()) {
+            if !value.is_finite() {
+                violations.push(format!("{} is not finite ({})", name, value));
+            }
+        }
+        for (name, value) in unit_stats {
+            if value.is_finite() && !(0.0..=1.0).contains(&value) {
+                violations.push(format!("{} {:.4} outside [0, 1]", name, value));
+            }
+        }
+        for (name, value) in non_negative_stats {
+            if value.is_finite() && value < 0.0 {
+                violations.push(format!("{} {:.4} is negative", name, value));
+            }
+        }
+        if self.health.0 > MAX_INDIVIDUAL_HEALTH {
+            violations.push(format!("health {:.2} exceeds max {:.0}", self.health.0, MAX_INDIVIDUAL_HEALTH));
+        }
+        if self.is_alive.0 && self.health.0 <= 0.0 {
+            violations.push(format!("IsAlive(true) with non-positive health {:.2}", self.health.0));
+        }
+        violations
+    }
+
+    /// Full stat dump for violation reports.
+    pub fn describe(&self) -> String {
+        format!(
+            "Health: {:.2}, Energy: {:.2}, Processing: {:.2}, Memory: {:.2}, Coherence: {:.4}, Adaptability: {:.4}, Resilience: {:.4}, ReplicationEfficiency: {:.4}, Combat: {:.2}, Defense: {:.2}, IsAlive: {}",
+            self.health.0, self.energy.0, self.processing_power.0, self.memory.0,
+            self.coherence.0, self.adaptability.0, self.resilience.0, self.replication_efficiency.0,
+            self.combat_strength.0, self.defense_strength.0, self.is_alive.0
+        )
+    }
+}
+
+/// Best guess at which system produced a bad state, based on the AI's last recorded action.
+pub fn likely_source_system(last_action: &LastAction) -> &'static str {
+    let action = last_action.0.as_str();
+    if action == "replicated" || action == "failed_replication" {
+        "ai_replication_system"
+    } else if action.starts_with("gained_discovery") || action.starts_with("discovered_meta_ability") {
+        "ai_internal_state_system (discovery)"
+    } else if action.contains("attack") {
+        "combat"
+    } else if action.contains("heal") || action.contains("self_repaired") {
+        "ai_internal_state_system (repair/heal)"
+    } else {
+        "ai_internal_state_system"
+    }
+}
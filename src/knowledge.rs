@@ -0,0 +1,94 @@
+use std::collections::{BTreeSet, HashMap};
+use bevy::prelude::Resource; // Import Resource from Bevy
+
+use crate::ai::AILineage;
+use crate::common::{Discovery, KnowledgeBase};
+
+/// Name of the meta-ability that lets a Researcher monoculture override the simulation.
+pub const OVERRIDE_PROTOCOL: &str = "Absolute_Control_Protocol";
+
+/// Aggregate technology of one lineage: the union of its living members' discoveries.
+#[derive(Debug, Clone, Default)]
+pub struct LineageTech {
+    pub discoveries: BTreeSet<String>,
+    pub has_combat_upgrade: bool,
+    pub has_meta_ability: bool,
+    pub has_override_protocol: bool,
+}
+
+/// Per-lineage knowledge, resampled every `LOG_INTERVAL` cycles since the union is costly.
+#[derive(Resource, Default)]
+pub struct LineageKnowledge {
+    pub lineages: HashMap<AILineage, LineageTech>,
+    pub last_sample_cycle: u64,
+}
+
+impl LineageKnowledge {
+    /// Unions the knowledge bases of the given living members by lineage.
+    pub fn aggregate<'a>(members: impl Iterator<Item = (&'a AILineage, &'a KnowledgeBase)>) -> HashMap<AILineage, LineageTech> {
+        let mut lineages: HashMap<AILineage, LineageTech> = HashMap::new();
+        for (lineage, knowledge_base) in members {
+            let tech = lineages.entry(lineage.clone()).or_default();
+            for discovery in &knowledge_base.0 {
+                if !tech.discoveries.insert(discovery.name.clone()) {
+                    continue;
+                }
+                tech.has_combat_upgrade |= discovery.tags.contains("combat");
+                tech.has_meta_ability |= discovery.tags.contains("meta-ability");
+                tech.has_override_protocol |= discovery.name == OVERRIDE_PROTOCOL;
+            }
+        }
+        lineages
+    }
+}
+
+/// Discoveries `teacher` knows that `learner` doesn't and may pick up by exchange.
+/// Meta-abilities only spread among Researchers.
+pub fn exchangeable_discoveries(teacher: &KnowledgeBase, learner: &KnowledgeBase, learner_lineage: &AILineage) -> Vec<Discovery> {
+    teacher.0.difference(&learner.0)
+        .filter(|discovery| *learner_lineage == AILineage::ResearcherAI || !discovery.tags.contains("meta-ability"))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn discovery(name: &str, tags: &[&str]) -> Discovery {
+        Discovery {
+            name: name.to_string(),
+            effect_description: String::new(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        }
+    }
+
+    fn knowledge(discoveries: &[&Discovery]) -> KnowledgeBase {
+        KnowledgeBase(discoveries.iter().map(|d| (*d).clone()).collect())
+    }
+
+    #[test]
+    fn lineage_tech_is_the_union_of_its_members() {
+        let combat = discovery("Combat_Protocol_Upgrade", &["combat", "technology"]);
+        let logic = discovery("Basic_Logic_Optimization", &["efficiency", "processing"]);
+        let meta = discovery("Reality_Manipulation_Theory", &["simulation_control", "meta-ability"]);
+        let overriding = discovery(OVERRIDE_PROTOCOL, &["simulation_control", "meta-ability", "ultimate"]);
+        let members = [
+            (AILineage::KillerAI, knowledge(&[&combat])),
+            (AILineage::KillerAI, knowledge(&[&combat, &logic])),
+            (AILineage::KillerAI, knowledge(&[])),
+            (AILineage::ResearcherAI, knowledge(&[&meta])),
+            (AILineage::ResearcherAI, knowledge(&[&logic, &overriding])),
+        ];
+        let lineages = LineageKnowledge::aggregate(members.iter().map(|(lineage, knowledge)| (lineage, knowledge)));
+        assert_eq!(lineages.len(), 2);
+
+        let killers = &lineages[&AILineage::KillerAI];
+        assert_eq!(killers.discoveries, BTreeSet::from([combat.name.clone(), logic.name.clone()]));
+        assert!(killers.has_combat_upgrade && !killers.has_meta_ability && !killers.has_override_protocol);
+
+        let researchers = &lineages[&AILineage::ResearcherAI];
+        assert_eq!(researchers.discoveries, BTreeSet::from([meta.name.clone(), logic.name.clone(), overriding.name.clone()]));
+        assert!(!researchers.has_combat_upgrade && researchers.has_meta_ability && researchers.has_override_protocol);
+    }
+}
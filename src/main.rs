@@ -1,11 +1,15 @@
 #![allow(unused_imports)] // Temporarily allow unused imports for components that are passed around
 
 // Core standard library imports
-use std::collections::{HashMap, BTreeSet};
-use std::sync::atomic::Ordering; // Used for AtomicU64
+use std::collections::{HashMap, HashSet, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering}; // Used for AtomicU64
+use std::sync::Mutex;
 
 // In Bevy 0.10, the Prelude re-exports commonly used items – including Camera2dBundle and SpriteBundle.
 use bevy::prelude::*;
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::render::camera::RenderTarget;
 
 // Egui imports (ensure your bevy_egui version is compatible with Bevy 0.10)
 use bevy_egui::{egui, EguiContexts, EguiPlugin};
@@ -14,24 +18,78 @@ use bevy_egui::{egui, EguiContexts, EguiPlugin};
 mod common;
 mod ai;
 mod simulation;
+mod config;
+mod spatial;
+mod territory;
+mod invariants;
+mod screenshot;
+mod picking;
+mod combat;
+mod objective;
+mod rng;
+mod arena;
+mod palette;
+mod forage;
+mod crowding;
+mod command;
+mod knowledge;
+mod dashboard;
+mod trace;
+mod persistence;
+mod simlog;
+mod metrics;
+mod genealogy;
+mod fitness;
+mod perf;
+mod effects;
+mod harness;
+mod godmode;
+mod records;
+mod scenario;
+mod heatmap;
+mod peacekeeping;
+mod ids;
 
 // Import granular components from your modules
 use common::{
     Health, Energy, ProcessingPower, Memory, Coherence, Adaptability, Resilience,
     ReplicationEfficiency, CombatStrength, DefenseStrength, LastAction, KnowledgeBase,
     EthicalDirectives, IsAlive, ReplicatedCount, CycleBorn, Goal,
-    EthicalConditionType, EthicalActionType, Discovery,
+    EthicalConditionType, EthicalActionType, Discovery, LastDiscoveryCycle, DiscoveryLedger, ReplicationCooldown, DiscoveryRecord, AppliedEffects,
+    EnvironmentScanData, VisionRange, CombatRange, PeacekeeperState,
 };
-use ai::{AIEntity, AILineage, AIType};
+use ai::{AIEntity, AILineage, AIType, HybridParent};
+use config::SimConfig;
+use spatial::{SpatialGrid, CellCoord, WorldBounds, GRID_CELL_SIZE};
+use territory::TerritoryMap;
+use invariants::{InvariantChecks, AIStatsView};
+use screenshot::{ScreenshotCamera, ScreenshotCapture, ScreenshotPlugin, ScreenshotRequest};
+use picking::{HoveredAI, CombatSelection, SelectedAI};
+use rng::SimRng;
+use simulation::SimPhase;
+use forage::{StoredEnergy, Corpse, FoodSource, FoodEconomy, ResourceNode};
+use command::{Command, CommandChannel};
+use knowledge::LineageKnowledge;
+use dashboard::DashboardLayout;
+use trace::LineageTrace;
+use persistence::{PersistenceAction, PersistenceRequest, IndividualSnapshot, WorldSnapshot};
+use simlog::{SimLog, VerbosityLevel};
+use metrics::MetricsRecorder;
+use genealogy::Genealogy;
+use fitness::LineageFitness;
+use perf::PerfStats;
+use effects::{FloatingDamage, Lifetime};
+use godmode::GodMode;
+use records::Records;
+use heatmap::DensityHeatmap;
+use ids::IdGenerator;
+use palette::{Palette, color_for_type, lineage_color, godai_color, monoculture_color};
 
 // Import the Rng traits for random number generation
 use rand::Rng;
-use rand::thread_rng;
+use rand::seq::SliceRandom;
 
 // --- Simulation Constants ---
-const MAX_CYCLES: u64 = 1_000_000;
-const MONOCULTURE_DOMINANCE_THRESHOLD: f32 = 0.999;
-const MONOCULTURE_MIN_COUNT: usize = 100_000;
 const LOG_INTERVAL: u64 = 10;
 
 // Simulation verbosity (for internal logic; GUI replaces console output)
@@ -45,8 +103,17 @@ enum SimulationVerbosity {
     Debug = 5,
 }
 
-// General simulation verbosity setting
-pub const SIM_VERBOSITY: SimulationVerbosity = SimulationVerbosity::Medium;
+impl SimulationVerbosity {
+    pub const ALL: [SimulationVerbosity; 6] = [
+        SimulationVerbosity::Silent, SimulationVerbosity::Critical, SimulationVerbosity::High,
+        SimulationVerbosity::Medium, SimulationVerbosity::Low, SimulationVerbosity::Debug,
+    ];
+
+    /// Inverse of `as u8`; out-of-range levels saturate to `Debug`.
+    pub fn from_level(level: u8) -> Self {
+        Self::ALL.get(level as usize).copied().unwrap_or(SimulationVerbosity::Debug)
+    }
+}
 
 // Custom thousands separator function
 fn format_thousand_separator(mut n: u64) -> String {
@@ -71,12 +138,99 @@ fn format_thousand_separator(mut n: u64) -> String {
 #[derive(Component)]
 struct IndividualAI;
 
+/// Marks an AI whose lineage is frozen; AI systems skip it, but it still counts
+/// toward population tallies and can still be targeted.
+#[derive(Component)]
+struct Frozen;
+
+/// Marks a dead AI whose death has been counted but which is still waiting for a despawn slot.
+/// Holds the cycle the death was recorded in, so the oldest waiting AIs are despawned first.
+#[derive(Component)]
+struct DeathRecorded(u64);
+
+/// Lineages frozen from the UI for controlled experiments.
+#[derive(Resource, Default)]
+struct FrozenLineages(HashSet<AILineage>);
+
 #[derive(Component)]
 struct MonocultureVisual;
 
 #[derive(Component)]
 struct GodaiVisual;
 
+/// Faint overlay sprite tinting one grid cell by its territorial owner.
+#[derive(Component)]
+struct TerritoryTile(CellCoord);
+
+/// Spawns an individual AI sprite with its full component set at `position`.
+fn spawn_ai(commands: &mut Commands, components: ai::AIComponents, position: Vec2, palette: Palette) -> Entity {
+    let (
+        ai_entity,
+        health, energy, processing_power, memory, coherence, adaptability, resilience,
+        replication_efficiency, replicated_count, cycle_born, last_action, primary_goal,
+        ethical_directives, knowledge_base, ai_type, combat_strength, defense_strength,
+        vision_range, combat_range,
+    ) = components;
+
+    let color = color_for_type(ai_type, palette);
+    let peacekeeper = ethical_directives.0.iter().any(|d| d.action_type == EthicalActionType::InterveneInConflict);
+
+    let mut spawned = commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::new(10.0, 10.0)),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, 0.0),
+            ..Default::default()
+        },
+        ai_entity,
+        health, energy, processing_power, memory, coherence, adaptability, resilience,
+        replication_efficiency, replicated_count, cycle_born, last_action, primary_goal,
+        ethical_directives, knowledge_base, ai_type, combat_strength, defense_strength,
+        IsAlive(true),
+        IndividualAI,
+        ai_entity.parent_lineage,
+    ));
+    spawned.insert((
+        LastDiscoveryCycle::default(), DiscoveryLedger::default(), ReplicationCooldown(cycle_born.0),
+        EnvironmentScanData::default(), vision_range, combat_range,
+    ));
+    if peacekeeper {
+        spawned.insert(PeacekeeperState::default());
+    }
+    spawned.id()
+}
+
+/// The starting individuals and where they spawn: the `--scenario` file's when one is given,
+/// otherwise the random seed mix. A scenario that can't be loaded ends the program.
+fn initial_population(
+    sim: &mut simulation::Simulation,
+    config: &SimConfig,
+    bounds: &WorldBounds,
+    ids: &mut IdGenerator,
+    rng: &mut impl Rng,
+) -> Vec<(ai::AIComponents, Vec2)> {
+    let Some(path) = &config.scenario_path else {
+        return sim.seed_initial_ais(&config.seed, ids, rng).into_iter()
+            .map(|components| (components, bounds.random_position(rng)))
+            .collect();
+    };
+    match sim.load_scenario(path, ids) {
+        Ok(individuals) => individuals.into_iter()
+            .map(|(components, position)| {
+                let position = position.map_or_else(|| bounds.random_position(rng), |(x, y)| bounds.confine(Vec2::new(x, y)));
+                (components, position)
+            })
+            .collect(),
+        Err(e) => {
+            eprintln!("Invalid --scenario: {}", e);
+            std::process::exit(2);
+        }
+    }
+}
+
 // --- Bevy Systems ---
 
 /// Initial setup system.
@@ -84,54 +238,55 @@ struct GodaiVisual;
 fn setup(
     mut commands: Commands,
     mut sim: ResMut<simulation::Simulation>,
+    mut sim_rng: ResMut<SimRng>,
+    mut ids: ResMut<IdGenerator>,
+    config: Res<SimConfig>,
+    bounds: Res<WorldBounds>,
 ) {
     // Spawn 2D camera (in Bevy 0.10, Camera2dBundle is re-exported by the Prelude)
     commands.spawn(Camera2dBundle::default());
 
-    // Retrieve initial AI entities from simulation logic.
-    let initial_ais_data = sim.seed_initial_ais(200);
-
-    let mut rng = thread_rng();
-    let window_width = 1000.0;
-    let window_height = 700.0;
+    // Spawn one transparent territory tile per grid cell, tinted later by territory_visual_system.
+    let half = bounds.half_extents();
+    let (min_cell_x, min_cell_y) = SpatialGrid::cell_of(-half);
+    let (max_cell_x, max_cell_y) = SpatialGrid::cell_of(half - Vec2::splat(0.1));
+    for cx in min_cell_x..=max_cell_x {
+        for cy in min_cell_y..=max_cell_y {
+            let center = SpatialGrid::cell_center((cx, cy));
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::NONE,
+                        custom_size: Some(Vec2::splat(GRID_CELL_SIZE)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(center.x, center.y, -1.0),
+                    ..Default::default()
+                },
+                TerritoryTile((cx, cy)),
+            ));
+        }
+    }
 
-    for (
-        ai_entity,
-        health, energy, processing_power, memory, coherence, adaptability, resilience,
-        replication_efficiency, replicated_count, cycle_born, last_action, primary_goal,
-        ethical_directives, knowledge_base, ai_type, combat_strength, defense_strength
-    ) in initial_ais_data {
-        let x = rng.gen_range(-window_width / 2.0..window_width / 2.0);
-        let y = rng.gen_range(-window_height / 2.0..window_height / 2.0);
-
-        let color = match ai_type {
-            AIType::Rogue => Color::rgb_u8(255, 0, 0),
-            AIType::Peacekeeper => Color::rgb_u8(0, 0, 255),
-            AIType::Killer => Color::rgb_u8(128, 0, 128),
-            AIType::Guardian => Color::rgb_u8(0, 128, 0),
-            AIType::Manic => Color::rgb_u8(255, 255, 0),
-            AIType::Healer => Color::rgb_u8(50, 205, 50),
-            AIType::Researcher => Color::rgb_u8(255, 165, 0),
-            AIType::Base => Color::rgb_u8(128, 128, 128),
-        };
+    let rng = &mut sim_rng.rng;
+    for (components, position) in initial_population(&mut sim, &config, &bounds, &mut ids, rng) {
+        spawn_ai(&mut commands, components, position, config.palette);
+    }
 
+    // Resource hotspots are fixed for the whole run.
+    for _ in 0..config.resource_node_count {
+        let Vec2 { x, y } = bounds.random_position(rng);
         commands.spawn((
             SpriteBundle {
                 sprite: Sprite {
-                    color,
-                    custom_size: Some(Vec2::new(10.0, 10.0)),
+                    color: Color::rgb_u8(200, 180, 60),
+                    custom_size: Some(Vec2::new(14.0, 14.0)),
                     ..Default::default()
                 },
-                transform: Transform::from_xyz(x, y, 0.0),
+                transform: Transform::from_xyz(x, y, -0.5),
                 ..Default::default()
             },
-            ai_entity,
-            health, energy, processing_power, memory, coherence, adaptability, resilience,
-            replication_efficiency, replicated_count, cycle_born, last_action, primary_goal,
-            ethical_directives, knowledge_base, ai_type, combat_strength, defense_strength,
-            IsAlive(true),
-            IndividualAI,
-            ai_entity.parent_lineage,
+            ResourceNode { capacity: config.resource_node_capacity, level: config.resource_node_capacity },
         ));
     }
 
@@ -139,7 +294,7 @@ fn setup(
     commands.spawn((
         SpriteBundle {
             sprite: Sprite {
-                color: Color::rgb_u8(75, 0, 130),
+                color: godai_color(config.palette),
                 custom_size: Some(Vec2::new(100.0, 100.0)),
                 ..Default::default()
             },
@@ -167,8 +322,11 @@ fn setup(
             combat_strength: sim.godai.combat_strength,
             defense_strength: sim.godai.defense_strength,
             knowledge_base: sim.godai.knowledge_base.clone(),
-            status: sim.godai.status.clone(),
+            status: sim.godai.status,
             is_alive: sim.godai.is_alive,
+            max_health: sim.godai.max_health,
+            regen_rate: sim.godai.regen_rate,
+            phase: sim.godai.phase,
         },
         GodaiVisual,
     ));
@@ -177,37 +335,95 @@ fn setup(
 /// System for processing AI internal state.
 fn ai_internal_state_system(
     mut ai_query: Query<(
-        &mut Health, &mut Energy, &mut ProcessingPower, &mut Memory,
+        Entity, &mut Health, &mut Energy, &mut ProcessingPower, &mut Memory,
         &mut Coherence, &mut Adaptability, &mut Resilience, &mut ReplicationEfficiency,
         &mut LastAction, &mut KnowledgeBase, &mut CombatStrength, &mut DefenseStrength,
-        &AIType, &EthicalDirectives, &mut IsAlive
-    ), With<IndividualAI>>,
+        &mut IsAlive, (&AIType, &EthicalDirectives, &Transform, &AILineage, &mut LastDiscoveryCycle, &mut DiscoveryLedger, &CycleBorn)
+    ), (With<IndividualAI>, Without<Frozen>)>,
+    mut node_query: Query<(&Transform, &mut ResourceNode), Without<IndividualAI>>,
     sim: Res<simulation::Simulation>,
+    config: Res<SimConfig>,
+    territory: Res<TerritoryMap>,
+    grid: Res<SpatialGrid>,
+    sim_rng: Res<SimRng>,
 ) {
-    if !sim.simulation_running || sim.simulation_over_reason.is_some() {
-        return;
+    // Each living AI draws from its nearest hotspot in range, and drawers split what a node holds.
+    let mut nodes: Vec<(Vec2, Mut<ResourceNode>)> = node_query.iter_mut()
+        .map(|(transform, node)| (transform.translation.truncate(), node))
+        .collect();
+    let node_positions: Vec<Vec2> = nodes.iter().map(|(pos, _)| *pos).collect();
+    let mut node_shares = vec![0.0; nodes.len()];
+    if !nodes.is_empty() {
+        let mut drawers = vec![0usize; nodes.len()];
+        for (.., is_alive, (_, _, transform, ..)) in ai_query.iter() {
+            if !is_alive.0 {
+                continue;
+            }
+            if let Some(i) = forage::nearest_node(&node_positions, transform.translation.truncate(), config.resource_node_radius) {
+                drawers[i] += 1;
+            }
+        }
+        for (i, (_, node)) in nodes.iter_mut().enumerate() {
+            if drawers[i] > 0 {
+                node_shares[i] = config.resource_node_bonus.min(node.level / drawers[i] as f32);
+                node.level -= node_shares[i] * drawers[i] as f32;
+            }
+            node.level = (node.level + config.resource_node_regen_per_cycle).min(node.capacity);
+        }
     }
-    let mut rng = thread_rng();
-    for (
-        mut health, mut energy, mut processing_power, mut memory,
+    // Living Researcher positions, for research sharing.
+    let mut researcher_grid = SpatialGrid::default();
+    if config.research_sharing_enabled {
+        for (.., is_alive, (ai_type, _, transform, ..)) in ai_query.iter() {
+            if is_alive.0 && *ai_type == AIType::Researcher {
+                researcher_grid.insert(Entity::PLACEHOLDER, transform.translation.truncate());
+            }
+        }
+    }
+    // Entities update independently across threads, each drawing from its own seeded stream.
+    ai_query.par_iter_mut().for_each_mut(|(
+        entity, mut health, mut energy, mut processing_power, mut memory,
         mut coherence, mut adaptability, mut resilience, mut replication_efficiency,
         mut last_action, mut knowledge_base, mut combat_strength, mut defense_strength,
-        ai_type, ethical_directives, mut is_alive
-    ) in ai_query.iter_mut()
-    {
+        mut is_alive, (ai_type, ethical_directives, transform, lineage, mut last_discovery, mut ledger, cycle_born)
+    )| {
         if is_alive.0 {
+            let rng = &mut rng::entity_rng(sim_rng.seed, sim.current_cycle, entity);
+            let mut manic_error = false;
             if *ai_type == AIType::Manic && rng.gen::<f32>() < 0.20 {
                 coherence.0 = (coherence.0 - 0.05).max(0.0);
                 health.0 = (health.0 - rng.gen_range(3.0..10.0)).max(0.0);
                 last_action.0 = "manic_self_error".to_string();
+                manic_error = true;
             }
             processing_power.0 = (processing_power.0 - 0.001).max(0.0);
             memory.0 = (memory.0 - 0.001).max(0.0);
-            energy.0 = (energy.0 + 50.0).min(5000.0);
+            let cell = SpatialGrid::cell_of(transform.translation.truncate());
+            let crowding_excess = crowding::overcrowding_excess(grid.cell_population(cell), *ai_type, &config);
+            let regen_multiplier = territory.regen_multiplier(cell, lineage, &config)
+                * crowding::crowding_regen_multiplier(crowding_excess, &config)
+                * sim.catastrophe_regen_multiplier(&config);
+            energy.0 = (energy.0 + config.energy_regen_per_cycle * regen_multiplier).min(5000.0);
+            if let Some(i) = forage::nearest_node(&node_positions, transform.translation.truncate(), config.resource_node_radius) {
+                energy.0 = (energy.0 + node_shares[i]).min(forage::MAX_INDIVIDUAL_ENERGY);
+            }
+            if crowding_excess > 0.0 {
+                coherence.0 = (coherence.0 - crowding_excess * config.overcrowding_coherence_loss).max(0.0);
+            }
+            coherence.0 = (coherence.0 - sim.catastrophe_coherence_loss(&config)).max(0.0);
             if energy.0 <= 0.0 || processing_power.0 <= 0.0 || memory.0 <= 0.0 {
                 health.0 -= 0.01;
                 coherence.0 = (coherence.0 - 0.001).max(0.0);
             }
+            let coherence_regen = simulation::coherence_regen(*ai_type, &energy, &processing_power, &memory, &resilience, &config);
+            coherence.0 = (coherence.0 + coherence_regen).min(1.0);
+            let (aging_health_loss, aging_coherence_loss) =
+                simulation::aging_decay(sim.current_cycle.saturating_sub(cycle_born.0), &config);
+            let aging = aging_health_loss > 0.0;
+            if aging {
+                health.0 = (health.0 - aging_health_loss).max(0.0);
+                coherence.0 = (coherence.0 - aging_coherence_loss).max(0.0);
+            }
             let mut actions_to_perform: Vec<EthicalActionType> = Vec::new();
             for directive in &ethical_directives.0 {
                 let condition_met = match directive.condition_type {
@@ -218,6 +434,7 @@ fn ai_internal_state_system(
                     }
                     EthicalConditionType::AlwaysTrue => true,
                     EthicalConditionType::AlwaysFalse => false,
+                    EthicalConditionType::ThreatNearbyAboveThreshold(_) => false, // Evaluated in ai_movement_system
                 };
                 if condition_met {
                     actions_to_perform.push(directive.action_type);
@@ -236,8 +453,9 @@ fn ai_internal_state_system(
                         );
                     }
                     EthicalActionType::ProhibitReplication => {}
-                    EthicalActionType::InterveneInConflict => {}
+                    EthicalActionType::InterveneInConflict => {} // Resolved in ai_combat_system
                     EthicalActionType::NoOp => {}
+                    EthicalActionType::Flee => {}
                     EthicalActionType::ManicSelfRepair => {
                         ai::AIEntity::_self_repair_manic(
                             &mut health, &mut energy, &mut coherence, &resilience, &mut last_action
@@ -245,318 +463,3303 @@ fn ai_internal_state_system(
                     }
                 }
             }
-            let discovery_chance = 0.05 * (memory.0 / 200.0) * (processing_power.0 / 200.0) * coherence.0;
-            if rng.gen::<f32>() < discovery_chance {
-                let discovery = simulation::get_random_general_discovery();
-                ai::AIEntity::_gain_discovery(
+            // Discoveries are paced by a per-entity cooldown and a saturating chance curve.
+            let mut discovery_ready = simulation::discovery_ready(&last_discovery, sim.current_cycle, &config);
+            let discovery_chance = simulation::paced_discovery_chance(
+                0.05 * (memory.0 / 200.0) * (processing_power.0 / 200.0) * coherence.0, &config
+            );
+            if discovery_ready && rng.gen::<f32>() < discovery_chance {
+                let discovery = simulation::get_weighted_general_discovery(*ai_type, rng);
+                let name = discovery.name.clone();
+                let applied = ai::AIEntity::_gain_discovery(
                     &mut knowledge_base, &mut last_action, &mut combat_strength, &mut defense_strength,
                     &mut processing_power, &mut memory, &mut resilience, &mut replication_efficiency, discovery
                 );
+                // Re-rolling a known discovery reinforces it against decay.
+                let record = ledger.0.entry(name).or_insert(DiscoveryRecord {
+                    last_reinforced_cycle: sim.current_cycle,
+                    applied: AppliedEffects::default(),
+                });
+                record.last_reinforced_cycle = sim.current_cycle;
+                if let Some(applied) = applied {
+                    record.applied = applied;
+                }
+                ai::AIEntity::_forget_excess_knowledge(
+                    &mut knowledge_base, &mut last_action, &mut combat_strength, &mut defense_strength,
+                    &mut processing_power, &mut memory, &mut resilience, &mut replication_efficiency,
+                    &mut ledger, config.max_knowledge
+                );
+                last_discovery.0 = Some(sim.current_cycle);
+                discovery_ready = false;
             }
-            if *ai_type == AIType::Researcher {
-                let meta_discovery_chance = 0.1 * (memory.0 / 200.0) * (processing_power.0 / 200.0) * coherence.0;
+            if *ai_type == AIType::Researcher && discovery_ready {
+                // The neighbor count includes this Researcher itself.
+                let nearby_researchers = researcher_grid
+                    .count_within(transform.translation.truncate(), config.research_sharing_radius)
+                    .saturating_sub(1);
+                let sharing = simulation::research_sharing_multiplier(nearby_researchers, &config);
+                let meta_discovery_chance = simulation::paced_discovery_chance(
+                    0.1 * (memory.0 / 200.0) * (processing_power.0 / 200.0) * coherence.0 * sharing, &config
+                );
                 if rng.gen::<f32>() < meta_discovery_chance {
-                    if let Some(ability) = simulation::get_random_meta_ability(&knowledge_base.0) {
+                    if let Some(ability) = simulation::get_random_meta_ability(
+                        &knowledge_base.0, coherence.0, processing_power.0, memory.0, &config.meta_ability_gate, rng
+                    ) {
                         last_action.0 = format!("discovered_meta_ability_{}", ability.name);
-                        ai::AIEntity::_gain_discovery(
+                        let name = ability.name.clone();
+                        if let Some(applied) = ai::AIEntity::_gain_discovery(
                             &mut knowledge_base, &mut last_action, &mut combat_strength, &mut defense_strength,
                             &mut processing_power, &mut memory, &mut resilience, &mut replication_efficiency, ability
-                        );
+                        ) {
+                            ledger.0.insert(name, DiscoveryRecord { last_reinforced_cycle: sim.current_cycle, applied });
+                            ai::AIEntity::_forget_excess_knowledge(
+                                &mut knowledge_base, &mut last_action, &mut combat_strength, &mut defense_strength,
+                                &mut processing_power, &mut memory, &mut resilience, &mut replication_efficiency,
+                                &mut ledger, config.max_knowledge
+                            );
+                        }
+                        last_discovery.0 = Some(sim.current_cycle);
                     }
                 }
             }
+            // Knowledge decay: forget at most one discovery per cycle.
+            if config.knowledge_decay_enabled && !knowledge_base.0.is_empty() {
+                for discovery in knowledge_base.0.iter() {
+                    ledger.0.entry(discovery.name.clone()).or_insert(DiscoveryRecord {
+                        last_reinforced_cycle: sim.current_cycle,
+                        applied: AppliedEffects::default(),
+                    });
+                }
+                let forgotten = ledger.0.iter().find(|(_, record)| {
+                    let chance = simulation::knowledge_decay_chance(
+                        ai_type, coherence.0, record.last_reinforced_cycle, sim.current_cycle, &config
+                    );
+                    chance > 0.0 && rng.gen::<f32>() < chance
+                }).map(|(name, record)| (name.clone(), record.applied));
+                if let Some((name, applied)) = forgotten {
+                    ai::AIEntity::_lose_discovery(
+                        &mut knowledge_base, &mut last_action, &mut combat_strength, &mut defense_strength,
+                        &mut processing_power, &mut memory, &mut resilience, &mut replication_efficiency,
+                        &name, &applied
+                    );
+                    ledger.0.remove(&name);
+                }
+            }
             if health.0 <= 0.0 || coherence.0 <= 0.01 {
                 if is_alive.0 {
-                    eprintln!("[AI] has died! (Health: {:.2}, Coherence: {:.2})", health.0, coherence.0);
+                    last_action.0 = simulation::DeathCause::from_internal_state(manic_error, aging, coherence.0).action_tag();
                 }
                 is_alive.0 = false;
             }
         }
-    }
+    });
 }
 
 /// System for AI replication.
 fn ai_replication_system(
     mut commands: Commands,
+    config: Res<SimConfig>,
     mut ai_query: Query<(
         &mut Health, &mut Energy, &mut ProcessingPower, &mut Memory,
         &mut Coherence, &mut Adaptability, &mut Resilience, &mut ReplicationEfficiency,
-        &mut ReplicatedCount, &mut LastAction, &AIEntity, &AILineage, &AIType,
-    ), With<IndividualAI>>,
-    mut sim: ResMut<simulation::Simulation>,
+        &mut ReplicatedCount, &mut LastAction, &AIEntity, &AILineage, &AIType, (Entity, &Goal, &CycleBorn, &VisionRange, &CombatRange, &IsAlive), &mut ReplicationCooldown,
+    ), (With<IndividualAI>, Without<Frozen>)>,
+    population_query: Query<(), (With<IndividualAI>, Without<DeathRecorded>)>,
+    sim: Res<simulation::Simulation>,
+    mut sim_rng: ResMut<SimRng>,
+    mut ids: ResMut<IdGenerator>,
+    mut genealogy: ResMut<Genealogy>,
+    bounds: Res<WorldBounds>,
+    grid: Res<SpatialGrid>,
+    mut sim_log: ResMut<SimLog>,
+    mut throttled: Local<bool>,
 ) {
-    if !sim.simulation_running || sim.simulation_over_reason.is_some() {
-        return;
+    // Safety net against runaway configs: stop queueing replicas once the projected
+    // population for the next frame would cross the danger threshold.
+    let population = population_query.iter().count();
+    let budget = simulation::replication_budget(population, &config);
+    // The sexual lineage replicates only with its nearest eligible same-lineage neighbor. Mates are
+    // picked up front because the parallel pass can't read other AIs' stats. Each mate's health and
+    // energy at that point are its reserve: every replica it sires draws the parent's share from it,
+    // and once the reserve falls below the replication bar the mate refuses before the parent pays.
+    let mut mates: HashMap<Entity, (Entity, ai::MateTraits)> = HashMap::new();
+    let mut mate_reserves: HashMap<Entity, Mutex<(Health, Energy)>> = HashMap::new();
+    if let Some(sexual_lineage) = &config.sexual_lineage {
+        let eligible = |health: &Health, energy: &Energy, is_alive: &IsAlive| {
+            is_alive.0 && health.0 > config.replication_min_health && energy.0 > config.replication_min_energy
+        };
+        for (entity, position) in grid.cells.values().flatten() {
+            let Ok((health, energy, .., lineage, _, (.., is_alive), _)) = ai_query.get(*entity) else { continue };
+            if lineage != sexual_lineage || !eligible(health, energy, is_alive) {
+                continue;
+            }
+            let mate = grid.neighbors_within(*position, config.mate_radius).into_iter()
+                .filter(|(other, _)| other != entity)
+                .filter_map(|(other, other_pos)| {
+                    let (h, e, pp, mem, coh, adapt, res, .., other_lineage, _, (.., alive), _) = ai_query.get(other).ok()?;
+                    (other_lineage == sexual_lineage && eligible(h, e, alive)).then(|| {
+                        let traits = ai::MateTraits {
+                            processing_power: pp.0, memory: mem.0, coherence: coh.0,
+                            adaptability: adapt.0, resilience: res.0,
+                        };
+                        (other, traits, (*h, *e), position.distance_squared(other_pos))
+                    })
+                })
+                .min_by(|a, b| a.3.total_cmp(&b.3));
+            if let Some((mate, traits, reserve, _)) = mate {
+                mates.insert(*entity, (mate, traits));
+                mate_reserves.entry(mate).or_insert_with(|| Mutex::new(reserve));
+            }
+        }
     }
-    let window_width = 1000.0;
-    let window_height = 700.0;
-    let mut rng = thread_rng();
-    let mut new_replicas_to_spawn = Vec::new();
-    for (
+    // Parents prepare replicas in parallel, each from its own seeded stream. Budget slots are
+    // reserved atomically before each attempt, so the cap holds without ordering the parents.
+    let reserved = AtomicUsize::new(0);
+    let prepared: Mutex<Vec<(Entity, String, u64, ai::AIComponents, Option<Entity>)>> = Mutex::new(Vec::new());
+    ai_query.par_iter_mut().for_each_mut(|(
         mut health, mut energy, mut processing_power, mut memory,
         mut coherence, mut adaptability, mut resilience, mut replication_efficiency,
-        mut replicated_count, mut last_action, ai_entity, parent_lineage, ai_type,
-    ) in ai_query.iter_mut()
-    {
-        if health.0 > 0.0 {
-            for _ in 0..5 {
-                if health.0 > 50.0 && energy.0 > 50.0 && replicated_count.0 < 1000 {
-                    if let Some(new_ai_components) = ai::AIEntity::attempt_replication(
-                        &mut health, &mut energy, &mut processing_power, &mut memory,
-                        &mut coherence, &mut adaptability, &mut resilience, &mut replication_efficiency,
-                        &mut replicated_count, &mut last_action, parent_lineage, ai_type, sim.current_cycle
-                    ) {
-                        new_replicas_to_spawn.push(new_ai_components);
-                        sim.total_replications_this_interval.fetch_add(1, Ordering::SeqCst);
-                    } else {
-                        break;
-                    }
-                } else {
+        mut replicated_count, mut last_action, ai_entity, parent_lineage, ai_type, (entity, goal, cycle_born, vision_range, combat_range, is_alive), mut cooldown,
+    )| {
+        // Culled or coherence-collapsed AIs can be dead with health left.
+        if !is_alive.0 || health.0 <= 0.0 {
+            return;
+        }
+        let rng = &mut rng::entity_rng(sim_rng.seed ^ rng::REPLICATION_STREAM, sim.current_cycle, entity);
+        let sexual = config.sexual_lineage.as_ref() == Some(parent_lineage);
+        let mate = mates.get(&entity);
+        let mut replicas = Vec::new();
+        for _ in 0..config.replication_attempts_per_frame {
+            // A cooldown of zero keeps the old one-attempt-chain-per-frame behavior.
+            if config.replication_cooldown_cycles > 0 && sim.current_cycle < cooldown.0 + config.replication_cooldown_cycles {
+                break;
+            }
+            if health.0 <= config.replication_min_health || energy.0 <= config.replication_min_energy || replicated_count.0 >= config.replication_cap {
+                break;
+            }
+            if !simulation::decision_holds(coherence.0, &config, rng) {
+                energy.0 = (energy.0 - config.fumble_energy_cost).max(0.0);
+                last_action.0 = "fumbled_replication".to_string();
+                break;
+            }
+            if sexual && mate.is_none() {
+                last_action.0 = "no_mate".to_string();
+                break;
+            }
+            if reserved.fetch_add(1, Ordering::SeqCst) >= budget {
+                reserved.fetch_sub(1, Ordering::SeqCst);
+                break;
+            }
+            // The mate's reserve stays locked until this attempt is settled, so two parents
+            // sharing a mate can't both spend its last share.
+            let mut mate_reserve = mate.map(|(mate, _)| mate_reserves[mate].lock().unwrap());
+            if let Some(reserve) = &mate_reserve {
+                if reserve.0.0 <= config.replication_min_health || reserve.1.0 <= config.replication_min_energy {
+                    reserved.fetch_sub(1, Ordering::SeqCst);
+                    last_action.0 = "mate_refused".to_string();
                     break;
                 }
             }
+            let Some(new_ai_components) = ai::AIEntity::attempt_replication(
+                &mut health, &mut energy, &mut processing_power, &mut memory,
+                &mut coherence, &mut adaptability, &mut resilience, &mut replication_efficiency,
+                &mut replicated_count, &mut last_action, parent_lineage, ai_type, goal, vision_range, combat_range, sim.current_cycle, rng
+            ) else {
+                reserved.fetch_sub(1, Ordering::SeqCst);
+                break;
+            };
+            if let Some(reserve) = &mut mate_reserve {
+                let (health, energy) = &mut **reserve;
+                ai::AIEntity::pay_replication_share(health, energy);
+            }
+            drop(mate_reserve);
+            let mut new_ai_components = new_ai_components;
+            if let Some((_, traits)) = mate {
+                ai::AIEntity::blend_with_mate(&mut new_ai_components, traits, rng);
+            }
+            let new_ai_components = if config.type_mutation_chance > 0.0 && rng.gen::<f32>() < config.type_mutation_chance {
+                sim.mutate_replica(new_ai_components, rng)
+            } else {
+                new_ai_components
+            };
+            replicas.push((entity, ai_entity.id.clone(), cycle_born.0, new_ai_components, mate.map(|(m, _)| *m)));
+            cooldown.0 = sim.current_cycle;
         }
+        if !replicas.is_empty() {
+            prepared.lock().unwrap().extend(replicas);
+        }
+    });
+    // Serial drain, by parent, so spawn order and placement don't depend on thread scheduling.
+    // The sort is stable, keeping each parent's replicas in the order they were made.
+    let mut new_replicas_to_spawn = prepared.into_inner().unwrap();
+    new_replicas_to_spawn.sort_by_key(|(parent, ..)| *parent);
+    let now_throttled = new_replicas_to_spawn.len() >= budget;
+    if now_throttled && !*throttled {
+        log!(sim_log, sim.current_cycle, SimulationVerbosity::Critical, "WARNING: population {} reached the danger threshold of {} ({:.0}% of the {} hard cap). Replication is throttled until it drops.",
+            population, simulation::population_danger_threshold(&config), config.population_danger_fraction * 100.0, config.max_population);
+    } else if !now_throttled && *throttled {
+        log!(sim_log, sim.current_cycle, SimulationVerbosity::Critical, "Population {} is back below the danger threshold; replication resumes.", population);
     }
-    for (
-        ai_entity, health, energy, processing_power, memory, coherence, adaptability, resilience,
-        replication_efficiency, replicated_count, cycle_born, last_action, primary_goal,
-        ethical_directives, knowledge_base, ai_type, combat_strength, defense_strength
-    ) in new_replicas_to_spawn
-    {
-        let x = rng.gen_range(-window_width / 2.0..window_width / 2.0);
-        let y = rng.gen_range(-window_height / 2.0..window_height / 2.0);
-        let color = match ai_type {
-            AIType::Rogue => Color::rgb_u8(255, 0, 0),
-            AIType::Peacekeeper => Color::rgb_u8(0, 0, 255),
-            AIType::Killer => Color::rgb_u8(128, 0, 128),
-            AIType::Guardian => Color::rgb_u8(0, 128, 0),
-            AIType::Manic => Color::rgb_u8(255, 255, 0),
-            AIType::Healer => Color::rgb_u8(50, 205, 50),
-            AIType::Researcher => Color::rgb_u8(255, 165, 0),
-            AIType::Base => Color::rgb_u8(128, 128, 128),
-        };
-        commands.spawn((
-            SpriteBundle {
-                sprite: Sprite {
-                    color,
-                    custom_size: Some(Vec2::new(10.0, 10.0)),
-                    ..Default::default()
-                },
-                transform: Transform::from_xyz(x, y, 0.0),
-                ..Default::default()
-            },
-            ai_entity, health, energy, processing_power, memory, coherence, adaptability, resilience,
-            replication_efficiency, replicated_count, cycle_born, last_action, primary_goal,
-            ethical_directives, knowledge_base, ai_type, combat_strength, defense_strength,
-            IsAlive(true), IndividualAI, ai_entity.parent_lineage,
-        ));
+    *throttled = now_throttled;
+    let rng = &mut sim_rng.rng;
+    let mut spawned = 0;
+    for (_, parent_id, parent_cycle_born, mut components, mate) in new_replicas_to_spawn {
+        components.0.id = ids.next_id("Replica", components.15);
+        // The mate pays the same share of health and energy `attempt_replication` took from the parent,
+        // once for every replica it sires; its reserve already agreed to each of them.
+        let mate = mate.and_then(|mate| ai_query.get_mut(mate).ok());
+        if let Some((mut health, mut energy, .., mut last_action, mate_entity, _, _, (_, _, mate_born, ..), _)) = mate {
+            ai::AIEntity::pay_replication_share(&mut health, &mut energy);
+            last_action.0 = "mated".to_string();
+            genealogy.record_hybrid_birth((&parent_id, parent_cycle_born), (&mate_entity.id, mate_born.0), &components.0.id, components.10.0);
+        } else {
+            genealogy.record_birth(&parent_id, parent_cycle_born, &components.0.id, components.10.0);
+        }
+        spawn_ai(&mut commands, components, bounds.random_position(rng), config.palette);
+        spawned += 1;
     }
+    sim.interval_replications_counter.fetch_add(spawned, Ordering::SeqCst);
 }
 
-/// System for handling AI death (despawning entities).
-fn ai_death_system(
+/// System pairing adjacent, healthy AIs of different lineages into hybrid children.
+/// Each AI takes part in at most one pairing per frame; the child spawns between its parents.
+fn hybrid_reproduction_system(
     mut commands: Commands,
-    dead_ai_query: Query<(Entity, &IsAlive), (With<IndividualAI>, With<Health>)>,
+    config: Res<SimConfig>,
+    mut ai_query: Query<(
+        (&mut Health, &mut Energy, &mut LastAction, &IsAlive, &Transform),
+        (&ProcessingPower, &Memory, &Coherence, &Adaptability, &Resilience, &ReplicationEfficiency),
+        (&CombatStrength, &DefenseStrength, &EthicalDirectives, &AILineage, &AIType, &Goal),
+        (&VisionRange, &CombatRange),
+        (&AIEntity, &CycleBorn),
+    ), (With<IndividualAI>, Without<Frozen>)>,
+    grid: Res<SpatialGrid>,
     sim: Res<simulation::Simulation>,
+    mut sim_rng: ResMut<SimRng>,
+    mut sim_log: ResMut<SimLog>,
+    mut ids: ResMut<IdGenerator>,
+    mut genealogy: ResMut<Genealogy>,
+    bounds: Res<WorldBounds>,
 ) {
-    if !sim.simulation_running || sim.simulation_over_reason.is_some() {
+    if !config.hybrid_reproduction_enabled {
         return;
     }
-    for (entity, is_alive) in dead_ai_query.iter() {
-        if !is_alive.0 {
-            commands.entity(entity).despawn();
-            sim.total_deaths_this_interval.fetch_add(1, Ordering::SeqCst);
+    let eligible = |health: &Health, energy: &Energy, is_alive: &IsAlive| {
+        is_alive.0 && health.0 >= config.hybrid_min_health && energy.0 > config.hybrid_energy_cost
+    };
+    let rng = &mut sim_rng.rng;
+    let mut paired: HashSet<Entity> = HashSet::new();
+    let mut pairs = Vec::new();
+    for (entity, transform) in grid.cells.values().flatten() {
+        if paired.contains(entity) {
+            continue;
+        }
+        let Ok(((health, energy, _, is_alive, _), _, (_, _, _, lineage, ..), ..)) = ai_query.get(*entity) else { continue };
+        if !eligible(health, energy, is_alive) {
+            continue;
+        }
+        let partner = grid.neighbors_within(*transform, config.hybrid_radius).into_iter().find(|(other, _)| {
+            other != entity && !paired.contains(other) && ai_query.get(*other).map_or(false, |((h, e, _, alive, _), _, (_, _, _, other_lineage, ..), ..)| {
+                other_lineage != lineage && eligible(h, e, alive)
+            })
+        });
+        if let Some((partner, partner_pos)) = partner {
+            if rng.gen::<f32>() < config.hybrid_chance {
+                paired.insert(*entity);
+                paired.insert(partner);
+                pairs.push((*entity, partner, (*transform + partner_pos) / 2.0));
+            }
         }
     }
+
+    for (first, second, midpoint) in pairs {
+        let Ok([mut a, mut b]) = ai_query.get_many_mut([first, second]) else { continue };
+        let parent_ids = [&a, &b].map(|(.., (ai_entity, cycle_born))| (ai_entity.id.clone(), cycle_born.0));
+        let parents = [&mut a, &mut b].map(|((health, energy, last_action, _, _), (pp, mem, coh, adapt, res, rep), (combat, defense, directives, lineage, ai_type, goal), (vision, reach), _)| {
+            energy.0 -= config.hybrid_energy_cost;
+            last_action.0 = "hybridized".to_string();
+            HybridParent {
+                lineage: (*lineage).clone(),
+                ai_type: **ai_type,
+                health: health.0,
+                energy: energy.0,
+                processing_power: pp.0,
+                memory: mem.0,
+                coherence: coh.0,
+                adaptability: adapt.0,
+                resilience: res.0,
+                replication_efficiency: rep.0,
+                combat_strength: combat.0,
+                defense_strength: defense.0,
+                vision_range: vision.0,
+                combat_range: reach.0,
+                directives: directives.0.clone(),
+                goal: (*goal).clone(),
+            }
+        });
+        let mut child = AIEntity::hybridize(&parents[0], &parents[1], sim.current_cycle, rng);
+        child.0.id = ids.next_id("Hybrid", child.15);
+        log!(sim_log, sim.current_cycle, SimulationVerbosity::High, "[{}] Hybrid born from {} x {}.",
+            child.0.id, parents[0].lineage, parents[1].lineage);
+        let [(first_id, first_born), (second_id, second_born)] = &parent_ids;
+        genealogy.record_hybrid_birth((first_id, *first_born), (second_id, *second_born), &child.0.id, child.10.0);
+        spawn_ai(&mut commands, child, bounds.confine(midpoint), config.palette);
+        sim.interval_replications_counter.fetch_add(1, Ordering::SeqCst);
+    }
 }
 
-/// System for AI movement and visual updates.
-fn ai_movement_system(
-    mut ai_query: Query<(&mut Transform, &Health, &IsAlive), With<IndividualAI>>,
+/// System letting aggressive AIs attack the nearest living AI of another lineage in range.
+/// Whether an AI engages this frame is rolled against its goal's aggression, so dominance-
+/// and elimination-driven AIs fight every frame while survival-driven ones rarely do.
+/// Each aggressor attacks at most once per frame; with friendly fire on, the blow may land on a nearby ally instead.
+/// With decision reliability on, a fumbled decision strikes a random neighbor of any lineage.
+/// A ready Peacekeeper that sees the target breaks the attack up instead, paying energy for it.
+fn ai_combat_system(
+    mut ai_query: Query<(
+        &mut Energy, &CombatStrength, &mut LastAction, &mut Health, &mut IsAlive,
+        &DefenseStrength, &Resilience, &Coherence, &AIType, &AILineage, &Goal, &Transform, &CombatRange,
+        (Option<&mut PeacekeeperState>, &ProcessingPower, &VisionRange),
+    ), (With<IndividualAI>, Without<Frozen>)>,
+    peacekeepers: Query<&VisionRange, (With<PeacekeeperState>, With<IndividualAI>, Without<Frozen>)>,
+    grid: Res<SpatialGrid>,
     sim: Res<simulation::Simulation>,
+    config: Res<SimConfig>,
+    mut sim_rng: ResMut<SimRng>,
+    mut sim_log: ResMut<SimLog>,
+    mut attack_events: EventWriter<combat::AttackEvent>,
 ) {
-    if !sim.simulation_running || sim.simulation_over_reason.is_some() {
-        return;
+    let rng = &mut sim_rng.rng;
+    let mut engagements = Vec::new();
+    for (attacker, pos) in grid.cells.values().flatten() {
+        let Ok((_, _, _, _, is_alive, _, _, coherence, ai_type, lineage, goal, _, reach, _)) = ai_query.get(*attacker) else { continue };
+        let aggression = goal.aggression();
+        if !is_alive.0 || aggression <= 0.0 || rng.gen::<f32>() >= aggression {
+            continue;
+        }
+        let mut enemies = Vec::new();
+        let mut allies = Vec::new();
+        for (other, other_pos) in grid.neighbors_within(*pos, reach.0) {
+            if other == *attacker {
+                continue;
+            }
+            if let Ok((_, _, _, _, other_alive, _, _, _, _, other_lineage, _, _, _, _)) = ai_query.get(other) {
+                if !other_alive.0 {
+                    continue;
+                }
+                if other_lineage == lineage {
+                    allies.push(other);
+                } else {
+                    enemies.push((other, other_pos.distance_squared(*pos)));
+                }
+            }
+        }
+        let Some(&(intended, _)) = enemies.iter().min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal)) else { continue };
+        let enemies: Vec<Entity> = enemies.into_iter().map(|(enemy, _)| enemy).collect();
+        let target = combat::choose_attack_target(intended, &enemies, &allies, *ai_type, coherence.0, &config, rng);
+        engagements.push((*attacker, target));
     }
-    let window_width = 1000.0;
-    let window_height = 700.0;
-    let mut rng = thread_rng();
-    for (mut transform, health, is_alive) in ai_query.iter_mut() {
-        if is_alive.0 {
-            let speed = 1.0;
-            transform.translation.x += rng.gen_range(-1.0..1.0) * speed;
-            transform.translation.y += rng.gen_range(-1.0..1.0) * speed;
-            let half_width = window_width / 2.0;
-            let half_height = window_height / 2.0;
-            transform.translation.x = transform.translation.x.clamp(-half_width, half_width);
-            transform.translation.y = transform.translation.y.clamp(-half_height, half_height);
-            let radius = 5.0 + (health.0 / 50.0);
-            transform.scale = Vec3::new(radius / 5.0, radius / 5.0, 1.0);
+
+    let watch_radius = peacekeepers.iter().map(|vision| vision.0).fold(0.0, f32::max);
+    for (attacker, target) in engagements {
+        // Earlier engagements this frame may have killed either side.
+        let (Ok(actor), Ok(victim)) = (ai_query.get(attacker), ai_query.get(target)) else { continue };
+        if !actor.4.0 || !victim.4.0 {
+            continue;
+        }
+        if watch_radius > 0.0 {
+            let target_pos = victim.11.translation.truncate();
+            let mut blocked = false;
+            for (candidate, candidate_pos) in grid.neighbors_within(target_pos, watch_radius) {
+                if candidate == attacker || candidate == target {
+                    continue;
+                }
+                let Ok((mut energy, _, mut action, _, alive, _, _, _, _, _, _, _, _, (Some(mut state), processing_power, vision))) =
+                    ai_query.get_mut(candidate) else { continue };
+                if alive.0 && candidate_pos.distance(target_pos) <= vision.0
+                    && state.try_intervene(&mut energy, processing_power, sim.current_cycle, &config)
+                {
+                    action.0 = "intervened".to_string();
+                    blocked = true;
+                    break;
+                }
+            }
+            if blocked {
+                if let Ok(mut actor) = ai_query.get_mut(attacker) {
+                    actor.2.0 = "attack_blocked".to_string();
+                }
+                continue;
+            }
+        }
+        let Ok([actor, victim]) = ai_query.get_many_mut([attacker, target]) else { continue };
+        let (mut energy, combat_strength, mut last_action, _, _, _, _, _, actor_type, actor_lineage, _, _, _, _) = actor;
+        let (_, _, mut target_action, mut target_health, mut target_alive, defense_strength, resilience, _, target_type, target_lineage, _, target_transform, _, _) = victim;
+        let health_before = target_health.0;
+        if AIEntity::attack(
+            &mut energy, combat_strength, &mut last_action,
+            &mut target_health, &mut target_alive, defense_strength, resilience,
+            *target_type, &config.attack_cost_model, rng,
+        ) {
+            sim.interval_attacks_counter.fetch_add(1, Ordering::SeqCst);
+            attack_events.send(combat::AttackEvent {
+                damage: health_before - target_health.0,
+                pos: target_transform.translation.truncate(),
+            });
+        }
+        if !target_alive.0 {
+            target_action.0 = simulation::DeathCause::Combat.action_tag();
+            log!(sim_log, sim.current_cycle, SimulationVerbosity::High,
+                "{:?} of {} killed an individual of {}", actor_type, actor_lineage, target_lineage);
         }
     }
 }
 
-/// System to orchestrate global simulation updates.
-fn global_simulation_update_system(
-    mut sim: ResMut<simulation::Simulation>,
-    ai_query: Query<(&AIEntity, &IsAlive, &AILineage), With<IndividualAI>>,
+/// System where energy-rich AIs donate to the hungriest same-lineage neighbor below
+/// `energy_share_recipient_max`, keeping `energy_share_reserve` for themselves.
+/// Each AI receives from at most one donor per frame, and part of every donation is lost.
+fn ai_energy_share_system(
+    mut ai_query: Query<(&mut Energy, &mut LastAction, &IsAlive, &AIType, &AILineage), (With<IndividualAI>, Without<Frozen>)>,
+    grid: Res<SpatialGrid>,
+    config: Res<SimConfig>,
 ) {
-    if !sim.simulation_running || sim.simulation_over_reason.is_some() {
+    if !config.energy_sharing_enabled {
         return;
     }
-    let mut total_ai_count = 0;
-    let mut lineage_counts: HashMap<AILineage, usize> = HashMap::new();
-    for (_, is_alive, lineage) in ai_query.iter() {
-        if is_alive.0 {
-            total_ai_count += 1;
-            *lineage_counts.entry(lineage.clone()).or_insert(0) += 1;
+    let mut claimed: HashSet<Entity> = HashSet::new();
+    let mut donations = Vec::new();
+    for (donor, pos) in grid.cells.values().flatten() {
+        let Ok((energy, _, is_alive, ai_type, lineage)) = ai_query.get(*donor) else { continue };
+        if !is_alive.0 || energy.0 <= config.energy_share_donor_min {
+            continue;
+        }
+        let amount = (config.energy_share_max_per_cycle * simulation::energy_generosity(*ai_type))
+            .min(energy.0 - config.energy_share_reserve);
+        if amount <= 0.0 {
+            continue;
+        }
+        let recipient = grid.neighbors_within(*pos, config.energy_share_radius).into_iter()
+            .filter(|(other, _)| other != donor && !claimed.contains(other))
+            .filter_map(|(other, _)| match ai_query.get(other) {
+                Ok((other_energy, _, other_alive, _, other_lineage))
+                    if other_alive.0 && other_lineage == lineage && other_energy.0 < config.energy_share_recipient_max => Some((other, other_energy.0)),
+                _ => None,
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((recipient, _)) = recipient {
+            claimed.insert(recipient);
+            donations.push((*donor, recipient, amount));
         }
     }
-    for _ in 0..(sim.simulation_speed as u32) {
-        sim.process_one_cycle(total_ai_count, lineage_counts.clone());
-    }
-}
 
-/// System to update the Monoculture visual.
-fn update_monoculture_visual_system(
-    mut commands: Commands,
-    sim: Res<simulation::Simulation>,
-    mut monoculture_query: Query<(Entity, &mut Sprite, &mut Transform), With<MonocultureVisual>>,
-) {
-    if let Some(monoculture) = &sim.monoculture {
-        if monoculture.is_alive.0 {
-            if let Ok((_entity, mut sprite, mut transform)) = monoculture_query.single_mut() {
-                sprite.color = Color::rgb_u8(255, 0, 255);
-                let size = 50.0 + (monoculture.health.0 / 1000.0).min(200.0);
-                sprite.custom_size = Some(Vec2::new(size, size));
-                transform.translation = Vec3::new(0.0, 0.0, 0.0);
-            } else {
-                commands.spawn((
-                    SpriteBundle {
-                        sprite: Sprite {
-                            color: Color::rgb_u8(255, 0, 255),
-                            custom_size: Some(Vec2::new(50.0, 50.0)),
-                            ..Default::default()
-                        },
-                        transform: Transform::from_xyz(0.0, 0.0, 0.0),
-                        ..Default::default()
-                    },
-                    MonocultureVisual,
-                ));
-            }
-        } else if let Ok((entity, _, _)) = monoculture_query.single() {
-            commands.entity(entity).despawn();
+    for (donor, recipient, amount) in donations {
+        if let Ok((mut energy, mut last_action, ..)) = ai_query.get_mut(donor) {
+            energy.0 -= amount;
+            last_action.0 = "donated_energy".to_string();
+        }
+        if let Ok((mut energy, mut last_action, ..)) = ai_query.get_mut(recipient) {
+            energy.0 += amount * (1.0 - config.energy_share_loss.clamp(0.0, 1.0));
+            last_action.0 = "received_energy".to_string();
         }
-    } else if let Ok((entity, _, _)) = monoculture_query.single() {
-        commands.entity(entity).despawn();
     }
 }
 
-/// System to update the GODAI visual.
-fn update_godai_visual_system(
-    mut commands: Commands,
-    sim: Res<simulation::Simulation>,
-    mut godai_query: Query<(Entity, &mut Sprite, &mut Transform), With<GodaiVisual>>,
+/// System where living Symbiotes raise the adaptability and resilience of same-lineage neighbors,
+/// paying energy for each ally supported. Support stops once a Symbiote can't afford another ally.
+fn symbiote_support_system(
+    mut ai_query: Query<(&mut Energy, &mut Adaptability, &mut Resilience, &IsAlive, &AIType, &AILineage), (With<IndividualAI>, Without<Frozen>)>,
+    grid: Res<SpatialGrid>,
+    config: Res<SimConfig>,
 ) {
-    if sim.godai.is_alive.0 {
-        if let Ok((_entity, mut sprite, mut transform)) = godai_query.single_mut() {
-            sprite.color = Color::rgb_u8(75, 0, 130);
-            let size = 100.0 + (sim.godai.health.0 / 100000.0).min(200.0);
-            sprite.custom_size = Some(Vec2::new(size, size));
-            transform.translation = Vec3::new(0.0, 0.0, 0.0);
+    let mut support = Vec::new();
+    for (symbiote, pos) in grid.cells.values().flatten() {
+        let Ok((energy, _, _, is_alive, ai_type, lineage)) = ai_query.get(*symbiote) else { continue };
+        if !is_alive.0 || *ai_type != AIType::Symbiote {
+            continue;
+        }
+        let affordable = if config.symbiote_energy_cost > 0.0 {
+            (energy.0 / config.symbiote_energy_cost).max(0.0) as usize
         } else {
-            commands.spawn((
-                SpriteBundle {
-                    sprite: Sprite {
-                        color: Color::rgb_u8(75, 0, 130),
-                        custom_size: Some(Vec2::new(100.0, 100.0)),
-                        ..Default::default()
-                    },
-                    transform: Transform::from_xyz(0.0, 0.0, 0.0),
-                    ..Default::default()
-                },
-                GodaiVisual,
-            ));
+            usize::MAX
+        };
+        let allies: Vec<Entity> = grid.neighbors_within(*pos, config.symbiote_radius).into_iter()
+            .filter(|(other, _)| other != symbiote)
+            .filter(|(other, _)| matches!(
+                ai_query.get(*other),
+                Ok((.., other_alive, _, other_lineage)) if other_alive.0 && other_lineage == lineage
+            ))
+            .map(|(other, _)| other)
+            .take(affordable)
+            .collect();
+        if !allies.is_empty() {
+            support.push((*symbiote, allies));
         }
-    } else if let Ok((entity, _, _)) = godai_query.single() {
-        commands.entity(entity).despawn();
     }
-}
 
-/// System to render the Egui UI panel.
-fn egui_ui_system(
-    mut contexts: EguiContexts,
-    mut sim: ResMut<simulation::Simulation>,
-    ai_query: Query<(&AIEntity, &IsAlive, &AILineage), With<IndividualAI>>,
-) {
-    egui::Window::new("Simulation Controls").show(contexts.ctx_mut(), |ui| {
-        ui.heading("Simulation Status");
-        ui.label(format!("Cycle: {}", format_thousand_separator(sim.current_cycle)));
-        let live_ai_count = ai_query.iter().filter(|(_, is_alive, _)| is_alive.0).count();
-        ui.label(format!("Population: {}", format_thousand_separator(live_ai_count as u64)));
-        ui.label(format!("GODAI Health: {:.0}", sim.godai.health.0));
-        if let Some(monoculture) = &sim.monoculture {
-            ui.label(format!("Monoculture Health: {:.0}", monoculture.health.0));
-        } else {
-            ui.label("Monoculture: Not formed");
+    for (symbiote, allies) in support {
+        if let Ok((mut energy, ..)) = ai_query.get_mut(symbiote) {
+            energy.0 = (energy.0 - config.symbiote_energy_cost * allies.len() as f32).max(0.0);
         }
-        if let Some(reason) = &sim.simulation_over_reason {
-            ui.label(format!("Simulation Over: {}", reason));
-        }
-        ui.add_space(10.0);
-        ui.heading("Controls");
-        if ui.button(if sim.simulation_running { "Pause" } else { "Resume" }).clicked() {
-            sim.simulation_running = !sim.simulation_running;
+        for ally in allies {
+            if let Ok((_, mut adaptability, mut resilience, ..)) = ai_query.get_mut(ally) {
+                adaptability.0 = (adaptability.0 + config.symbiote_buff).min(1.0);
+                resilience.0 = (resilience.0 + config.symbiote_buff).min(1.0);
+            }
         }
-        ui.horizontal(|ui| {
-            ui.label("Speed:");
-            ui.add(egui::Slider::new(&mut sim.simulation_speed, 1.0..=100.0).text("cycles/frame"));
-        });
-    });
+    }
 }
 
-/// System to handle simulation end.
-fn simulation_end_system(
+/// System letting living AIs learn a few discoveries per cycle from a random same-lineage neighbor.
+/// Each learned discovery costs the learner energy and applies its stat effects as if discovered.
+fn ai_knowledge_exchange_system(
+    mut ai_query: Query<(
+        &mut KnowledgeBase, &mut LastAction, &mut CombatStrength, &mut DefenseStrength,
+        &mut ProcessingPower, &mut Memory, &mut Resilience, &mut ReplicationEfficiency,
+        &mut Energy, &mut DiscoveryLedger, &IsAlive, &AILineage,
+    ), (With<IndividualAI>, Without<Frozen>)>,
+    grid: Res<SpatialGrid>,
     sim: Res<simulation::Simulation>,
-    mut exit: EventWriter<AppExit>,
-    ai_query: Query<(&AIEntity, &IsAlive, &AILineage), With<IndividualAI>>,
+    config: Res<SimConfig>,
+    mut sim_rng: ResMut<SimRng>,
 ) {
-    if sim.simulation_over_reason.is_some() {
-        let mut final_ai_count = 0;
-        let mut final_lineage_counts: HashMap<AILineage, usize> = HashMap::new();
-        for (_, is_alive, lineage) in ai_query.iter() {
-            if is_alive.0 {
-                final_ai_count += 1;
-                *final_lineage_counts.entry(lineage.clone()).or_insert(0) += 1;
-            }
+    if !config.knowledge_exchange_enabled {
+        return;
+    }
+    let rng = &mut sim_rng.rng;
+    let mut lessons = Vec::new();
+    for (learner, pos) in grid.cells.values().flatten() {
+        let Ok((knowledge_base, .., energy, _, is_alive, lineage)) = ai_query.get(*learner) else { continue };
+        if !is_alive.0 || energy.0 < config.knowledge_exchange_energy_cost {
+            continue;
+        }
+        let teachers: Vec<Entity> = grid.neighbors_within(*pos, config.knowledge_exchange_radius).into_iter()
+            .filter(|(other, _)| other != learner)
+            .filter(|(other, _)| matches!(
+                ai_query.get(*other),
+                Ok((.., other_alive, other_lineage)) if other_alive.0 && other_lineage == lineage
+            ))
+            .map(|(other, _)| other)
+            .collect();
+        let Some(teacher) = teachers.choose(rng) else { continue };
+        let Ok((teacher_knowledge, ..)) = ai_query.get(*teacher) else { continue };
+        let mut discoveries = knowledge::exchangeable_discoveries(teacher_knowledge, knowledge_base, lineage);
+        discoveries.shuffle(rng);
+        discoveries.truncate(config.knowledge_exchange_max_per_cycle);
+        if !discoveries.is_empty() {
+            lessons.push((*learner, discoveries));
         }
-        sim.print_final_summary(final_ai_count, final_lineage_counts);
-        exit.send(AppExit);
     }
-}
 
-/// Main execution.
-fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "AI Simulation".into(),
-                resolution: (1000.0, 700.0).into(),
-                ..Default::default()
-            }),
-            ..Default::default()
-        }))
-        .add_plugins(EguiPlugin)
-        .insert_resource(simulation::Simulation::new())
+    for (learner, discoveries) in lessons {
+        let Ok((
+            mut knowledge_base, mut last_action, mut combat_strength, mut defense_strength,
+            mut processing_power, mut memory, mut resilience, mut replication_efficiency,
+            mut energy, mut ledger, ..
+        )) = ai_query.get_mut(learner) else { continue };
+        for discovery in discoveries {
+            if energy.0 < config.knowledge_exchange_energy_cost {
+                break;
+            }
+            energy.0 -= config.knowledge_exchange_energy_cost;
+            let name = discovery.name.clone();
+            if let Some(applied) = AIEntity::_gain_discovery(
+                &mut knowledge_base, &mut last_action, &mut combat_strength, &mut defense_strength,
+                &mut processing_power, &mut memory, &mut resilience, &mut replication_efficiency, discovery
+            ) {
+                ledger.0.insert(name, DiscoveryRecord { last_reinforced_cycle: sim.current_cycle, applied });
+                AIEntity::_forget_excess_knowledge(
+                    &mut knowledge_base, &mut last_action, &mut combat_strength, &mut defense_strength,
+                    &mut processing_power, &mut memory, &mut resilience, &mut replication_efficiency,
+                    &mut ledger, config.max_knowledge
+                );
+            }
+        }
+    }
+}
+
+/// Allies below this health are triaged ahead of everyone else.
+const HEAL_CRITICAL_HEALTH: f32 = 40.0;
+/// Allies below this health draw steering carers toward them.
+const STEER_WOUNDED_HEALTH: f32 = 100.0;
+
+/// What a caring AI does in `ai_heal_system` this frame.
+enum Heal {
+    Patient(Entity),
+    Fumbled(Option<Entity>), // A random living neighbor, if any, gets the heal instead
+    SelfRepair,
+}
+
+/// System letting caring AIs heal their most-damaged same-lineage neighbor, critical allies first.
+/// Whether an AI tends anyone this frame is rolled against its goal's care, so Healers always do
+/// and harmony- or lineage-minded AIs sometimes do. A Healer with no one to tend repairs itself instead.
+/// With decision reliability on, a heal can be fumbled onto a random neighbor (see `decision_holds`).
+fn ai_heal_system(
+    mut ai_query: Query<(
+        &mut Energy, &ProcessingPower, &mut LastAction, &mut Health, &IsAlive,
+        &mut Coherence, &Resilience, &AIType, &AILineage, &Goal, &CombatRange,
+    ), (With<IndividualAI>, Without<Frozen>)>,
+    grid: Res<SpatialGrid>,
+    sim: Res<simulation::Simulation>,
+    config: Res<SimConfig>,
+    mut sim_rng: ResMut<SimRng>,
+    mut sim_log: ResMut<SimLog>,
+) {
+    let rng = &mut sim_rng.rng;
+    let mut assignments = Vec::new();
+    for (healer, pos) in grid.cells.values().flatten() {
+        let Ok((_, _, _, _, is_alive, coherence, _, ai_type, lineage, goal, reach)) = ai_query.get(*healer) else { continue };
+        let care = goal.care();
+        if !is_alive.0 || care <= 0.0 || rng.gen::<f32>() >= care {
+            continue;
+        }
+        let neighbors = grid.neighbors_within(*pos, reach.0);
+        let patient = neighbors.iter().copied()
+            .filter(|(other, _)| other != healer)
+            .filter_map(|(other, _)| {
+                let (_, _, _, health, other_alive, _, _, _, other_lineage, ..) = ai_query.get(other).ok()?;
+                (other_alive.0 && other_lineage == lineage && health.0 < invariants::MAX_INDIVIDUAL_HEALTH)
+                    .then_some((other, health.0))
+            })
+            .min_by(|(_, a), (_, b)| {
+                (*a >= HEAL_CRITICAL_HEALTH).cmp(&(*b >= HEAL_CRITICAL_HEALTH))
+                    .then(a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            })
+            .map(|(other, _)| other);
+        if patient.is_some() && !simulation::decision_holds(coherence.0, &config, rng) {
+            // A fumbled heal goes to any living neighbor, or is wasted if nobody else is in range.
+            let bystanders: Vec<Entity> = neighbors.iter()
+                .map(|(other, _)| *other)
+                .filter(|other| other != healer && ai_query.get(*other).map_or(false, |(_, _, _, _, alive, ..)| alive.0))
+                .collect();
+            assignments.push((*healer, Heal::Fumbled(bystanders.choose(rng).copied())));
+        } else if let Some(patient) = patient {
+            assignments.push((*healer, Heal::Patient(patient)));
+        } else if *ai_type == AIType::Healer {
+            assignments.push((*healer, Heal::SelfRepair));
+        }
+    }
+
+    for (healer, heal) in assignments {
+        match heal {
+            Heal::Patient(patient) | Heal::Fumbled(Some(patient)) => {
+                let Ok([actor, target]) = ai_query.get_many_mut([healer, patient]) else { continue };
+                let (mut energy, processing_power, mut last_action, ..) = actor;
+                let (_, _, _, mut target_health, target_alive, ..) = target;
+                if AIEntity::heal(&mut energy, processing_power, &mut last_action, &mut target_health, target_alive, None, rng, &mut sim_log, sim.current_cycle) {
+                    sim.interval_heals_counter.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+            Heal::Fumbled(None) => {
+                let Ok((mut energy, _, mut last_action, ..)) = ai_query.get_mut(healer) else { continue };
+                energy.0 = (energy.0 - config.fumble_energy_cost).max(0.0);
+                last_action.0 = "fumbled_heal".to_string();
+            }
+            Heal::SelfRepair => {
+                let Ok((mut energy, _, mut last_action, mut health, _, mut coherence, resilience, ..)) = ai_query.get_mut(healer) else { continue };
+                if health.0 < invariants::MAX_INDIVIDUAL_HEALTH {
+                    AIEntity::_self_repair(&mut health, &mut energy, &mut coherence, resilience, &mut last_action);
+                }
+            }
+        }
+    }
+}
+
+/// System that periodically spawns immigrant AIs at random positions.
+/// Lets immigration-only worlds (zero seed AIs) grow a population from nothing.
+fn immigration_system(
+    mut commands: Commands,
+    mut sim: ResMut<simulation::Simulation>,
+    mut sim_rng: ResMut<SimRng>,
+    mut ids: ResMut<IdGenerator>,
+    config: Res<SimConfig>,
+    bounds: Res<WorldBounds>,
+) {
+    let interval = config.seed.immigration_interval;
+    if !config.seed.immigration_enabled() {
+        return;
+    }
+    if sim.current_cycle < sim.last_immigration_cycle + interval {
+        return;
+    }
+    sim.last_immigration_cycle = sim.current_cycle;
+    let rng = &mut sim_rng.rng;
+    for components in sim.generate_immigrants(&config.seed, &mut ids, rng) {
+        spawn_ai(&mut commands, components, bounds.random_position(rng), config.palette);
+    }
+}
+
+/// System that spawns individuals queued by simulation logic (e.g. a fractured monoculture)
+/// around the world center, where the monoculture is drawn, kept inside the world bounds.
+fn pending_spawn_system(
+    mut commands: Commands,
+    mut sim: ResMut<simulation::Simulation>,
+    mut sim_rng: ResMut<SimRng>,
+    bounds: Res<WorldBounds>,
+    config: Res<SimConfig>,
+) {
+    if sim.pending_spawns.is_empty() {
+        return;
+    }
+    let rng = &mut sim_rng.rng;
+    for components in std::mem::take(&mut sim.pending_spawns) {
+        let offset = Vec2::new(rng.gen_range(-100.0..100.0), rng.gen_range(-100.0..100.0));
+        spawn_ai(&mut commands, components, bounds.confine(offset), config.palette);
+    }
+}
+
+/// System that applies the GODAI's smites to their targets through their defenses and resistances.
+fn godai_smite_system(
+    mut sim: ResMut<simulation::Simulation>,
+    mut ai_query: Query<(&mut Health, &mut IsAlive, &mut LastAction, &DefenseStrength, &Resilience, &AIType), With<IndividualAI>>,
+) {
+    for (entity, damage, damage_type) in std::mem::take(&mut sim.pending_smites) {
+        let Ok((mut health, mut is_alive, mut last_action, defense, resilience, ai_type)) = ai_query.get_mut(entity) else { continue };
+        if !is_alive.0 {
+            continue;
+        }
+        AIEntity::receive_damage(&mut health, &mut is_alive, defense, resilience, *ai_type, damage, damage_type);
+        if !is_alive.0 {
+            last_action.0 = simulation::DeathCause::Smitten.action_tag();
+        }
+    }
+}
+
+/// System for handling AI death (despawning entities).
+/// Deaths are counted the frame they happen, but at most `despawn_budget_per_frame` dead AIs are
+/// despawned per frame, oldest first, so a mass die-off is spread over several frames. Waiting
+/// AIs keep `IsAlive(false)`, which every logic system skips.
+/// With scavenging enabled, each dead AI leaves a corpse carrying part of its residual energy once despawned.
+fn ai_death_system(
+    mut commands: Commands,
+    dead_ai_query: Query<(
+        Entity, &AIEntity, &IsAlive, &Health, &Energy, &Transform, &LastAction, &AILineage, Option<&DeathRecorded>,
+    ), With<IndividualAI>>,
+    mut sim: ResMut<simulation::Simulation>,
+    mut genealogy: ResMut<Genealogy>,
+    config: Res<SimConfig>,
+    mut sim_log: ResMut<SimLog>,
+) {
+    let mut budget = config.despawn_budget_per_frame;
+    let despawn = |commands: &mut Commands, entity: Entity, health: &Health, energy: &Energy, transform: &Transform, current_cycle: u64| {
+        commands.entity(entity).despawn();
+        if config.scavenging_enabled {
+            let stored = forage::corpse_energy(energy.0, health.0, config.corpse_energy_fraction);
+            if stored > 0.0 {
+                commands.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::rgb_u8(60, 60, 60),
+                            custom_size: Some(Vec2::new(6.0, 6.0)),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_xyz(transform.translation.x, transform.translation.y, -0.5),
+                        ..Default::default()
+                    },
+                    StoredEnergy(stored),
+                    Corpse { expires_at_cycle: current_cycle + config.corpse_lifetime_cycles },
+                ));
+            }
+        }
+    };
+    // Deaths queued by earlier frames go first, oldest first.
+    let mut queued: Vec<_> = dead_ai_query.iter()
+        .filter_map(|(entity, _, _, health, energy, transform, .., recorded)| {
+            recorded.map(|recorded| (recorded.0, entity, health, energy, transform))
+        })
+        .collect();
+    queued.sort_by_key(|(recorded_cycle, ..)| *recorded_cycle);
+    for (_, entity, health, energy, transform) in queued.into_iter().take(budget) {
+        budget -= 1;
+        despawn(&mut commands, entity, health, energy, transform, sim.current_cycle);
+    }
+    for (entity, ai_entity, is_alive, health, energy, transform, last_action, lineage, recorded) in dead_ai_query.iter() {
+        if is_alive.0 || recorded.is_some() {
+            continue;
+        }
+        genealogy.record_death(&ai_entity.id);
+        sim.interval_deaths_counter.fetch_add(1, Ordering::SeqCst);
+        let cause = simulation::DeathCause::from_last_action(last_action);
+        *sim.death_causes.entry(cause).or_insert(0) += 1;
+        // Combat deaths were already logged with their killer.
+        if cause != simulation::DeathCause::Combat {
+            log!(sim_log, sim.current_cycle, SimulationVerbosity::Medium,
+                "An individual of {} died ({})", lineage, cause);
+        }
+        if budget > 0 {
+            budget -= 1;
+            despawn(&mut commands, entity, health, energy, transform, sim.current_cycle);
+        } else {
+            commands.entity(entity).insert(DeathRecorded(sim.current_cycle));
+        }
+    }
+}
+
+/// System letting living AIs draw energy from nearby `StoredEnergy` entities (corpses).
+/// Each source feeds its nearest consumers first; frozen AIs don't feed.
+fn stored_energy_consumption_system(
+    mut source_query: Query<(&mut StoredEnergy, &Transform)>,
+    mut ai_query: Query<(&mut Energy, &IsAlive), (With<IndividualAI>, Without<Frozen>)>,
+    grid: Res<SpatialGrid>,
+    config: Res<SimConfig>,
+) {
+    for (mut stored, transform) in source_query.iter_mut() {
+        if stored.0 <= 0.0 {
+            continue;
+        }
+        let pos = transform.translation.truncate();
+        let mut consumers = grid.neighbors_within(pos, forage::CONSUME_RADIUS);
+        consumers.sort_by(|(_, a), (_, b)| {
+            a.distance_squared(pos).partial_cmp(&b.distance_squared(pos)).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        for (entity, _) in consumers {
+            if let Ok((mut energy, is_alive)) = ai_query.get_mut(entity) {
+                if is_alive.0 {
+                    forage::consume_stored_energy(&mut stored, &mut energy, config.consume_bite);
+                }
+            }
+            if stored.0 <= 0.0 {
+                break;
+            }
+        }
+    }
+}
+
+/// System that spawns food sources in waves on fixed cycle boundaries.
+fn food_spawn_system(
+    mut commands: Commands,
+    mut economy: ResMut<FoodEconomy>,
+    food_query: Query<(), With<FoodSource>>,
+    sim: Res<simulation::Simulation>,
+    config: Res<SimConfig>,
+    bounds: Res<WorldBounds>,
+) {
+    if !config.food_enabled || config.food_spawn_interval == 0 {
+        return;
+    }
+    let mut existing = food_query.iter().count();
+    while sim.current_cycle >= economy.next_spawn_cycle {
+        economy.next_spawn_cycle += config.food_spawn_interval;
+        let count = config.food_spawn_count.min(config.food_max_sources.saturating_sub(existing));
+        for (pos, rng) in economy.spawn_wave(count, bounds.half_extents()) {
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgb_u8(70, 160, 70),
+                        custom_size: Some(Vec2::new(8.0, 8.0)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(pos.x, pos.y, -0.5),
+                    ..Default::default()
+                },
+                FoodSource { capacity: config.food_capacity, rng },
+                StoredEnergy(config.food_capacity * config.food_initial_fraction.clamp(0.0, 1.0)),
+            ));
+        }
+        existing += count;
+    }
+}
+
+/// System that regrows food sources by the number of cycles processed since its last run.
+fn food_regen_system(
+    mut food_query: Query<(&mut StoredEnergy, &mut FoodSource)>,
+    mut economy: ResMut<FoodEconomy>,
+    sim: Res<simulation::Simulation>,
+    config: Res<SimConfig>,
+) {
+    let cycles = sim.current_cycle.saturating_sub(economy.last_regen_cycle);
+    if !config.food_enabled || cycles == 0 {
+        return;
+    }
+    economy.last_regen_cycle = sim.current_cycle;
+    for (mut stored, mut source) in food_query.iter_mut() {
+        forage::regen_food(&mut stored, &mut source, cycles, &config);
+    }
+}
+
+/// System that removes corpses once eaten or expired.
+fn corpse_decay_system(
+    mut commands: Commands,
+    corpse_query: Query<(Entity, &Corpse, &StoredEnergy)>,
+    sim: Res<simulation::Simulation>,
+) {
+    for (entity, corpse, stored) in corpse_query.iter() {
+        if stored.0 <= 0.0 || sim.current_cycle >= corpse.expires_at_cycle {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// System for AI movement and visual updates.
+/// Crowded AIs take larger random steps, dispersing them out of dense cells.
+/// AIs whose flee directive is triggered bias their walk away from the strongest nearby threat.
+/// With steering on, the rest drift toward a goal target; anyone without one drifts toward a stocked hotspot.
+fn ai_movement_system(
+    mut ai_query: Query<(
+        Entity, &mut Transform, &Health, &IsAlive, &AIType, &AILineage, &CombatStrength, &EthicalDirectives, &Goal,
+    ), (With<IndividualAI>, Without<Frozen>)>,
+    node_query: Query<(&Transform, &ResourceNode), Without<IndividualAI>>,
+    config: Res<SimConfig>,
+    grid: Res<SpatialGrid>,
+    mut sim_rng: ResMut<SimRng>,
+    bounds: Res<WorldBounds>,
+) {
+    let stocked_nodes: Vec<Vec2> = node_query.iter()
+        .filter(|(_, node)| node.level > 0.0)
+        .map(|(transform, _)| transform.translation.truncate())
+        .collect();
+
+    // Per-AI drift added on top of the random walk: fleeing beats goal steering, which beats hotspots.
+    let mut drift: HashMap<Entity, Vec2> = HashMap::new();
+    for (entity, pos) in grid.cells.values().flatten() {
+        let Ok((_, _, _, is_alive, ai_type, lineage, combat_strength, directives, goal)) = ai_query.get(*entity) else { continue };
+        if !is_alive.0 {
+            continue;
+        }
+        if let Some(ratio) = simulation::flee_threshold(directives) {
+            let strongest_threat = grid.neighbors_within(*pos, config.flee_threat_radius).into_iter()
+                .filter_map(|(other, other_pos)| {
+                    let (_, _, _, other_alive, _, other_lineage, other_strength, _, _) = ai_query.get(other).ok()?;
+                    (other_alive.0 && other_lineage != lineage && other_strength.0 > combat_strength.0 * ratio)
+                        .then_some((other_strength.0, other_pos))
+                })
+                .max_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+            if let Some((_, threat_pos)) = strongest_threat {
+                drift.insert(*entity, (*pos - threat_pos).normalize_or_zero() * config.flee_speed);
+                continue;
+            }
+        }
+        if config.steering_enabled && *ai_type != AIType::Researcher {
+            let care = goal.care();
+            let aggression = goal.aggression();
+            let seeks_wounded = care > 0.0 && care >= aggression;
+            if seeks_wounded || aggression > 0.0 {
+                let target = grid.neighbors_within(*pos, config.steering_radius).into_iter()
+                    .filter(|(other, _)| other != entity)
+                    .filter(|(other, _)| {
+                        let Ok((_, _, other_health, other_alive, _, other_lineage, ..)) = ai_query.get(*other) else { return false };
+                        other_alive.0 && if seeks_wounded {
+                            other_lineage == lineage && other_health.0 < STEER_WOUNDED_HEALTH
+                        } else {
+                            other_lineage != lineage
+                        }
+                    })
+                    .map(|(_, other_pos)| other_pos)
+                    .min_by(|a, b| a.distance_squared(*pos).partial_cmp(&b.distance_squared(*pos)).unwrap_or(std::cmp::Ordering::Equal));
+                if let Some(target_pos) = target {
+                    drift.insert(*entity, (target_pos - *pos).normalize_or_zero() * config.steering_speed);
+                    continue;
+                }
+            }
+        }
+        if let Some(i) = forage::nearest_node(&stocked_nodes, *pos, f32::INFINITY) {
+            drift.insert(*entity, (stocked_nodes[i] - *pos).normalize_or_zero() * config.resource_node_attraction);
+        }
+    }
+
+    let rng = &mut sim_rng.rng;
+    for (entity, mut transform, health, is_alive, ai_type, ..) in ai_query.iter_mut() {
+        if is_alive.0 {
+            let cell = SpatialGrid::cell_of(transform.translation.truncate());
+            let crowding_excess = crowding::overcrowding_excess(grid.cell_population(cell), *ai_type, &config);
+            let speed = 1.0 + crowding_excess * config.overcrowding_dispersal;
+            transform.translation.x += rng.gen_range(-1.0..1.0) * speed;
+            transform.translation.y += rng.gen_range(-1.0..1.0) * speed;
+            if let Some(offset) = drift.get(&entity) {
+                transform.translation += offset.extend(0.0);
+            }
+            let confined = bounds.confine(transform.translation.truncate());
+            transform.translation.x = confined.x;
+            transform.translation.y = confined.y;
+            transform.scale = health_scale(health);
+        }
+    }
+}
+
+/// Sprite scale for an AI of the given health: a 5-unit radius plus one unit per 50 health.
+fn health_scale(health: &Health) -> Vec3 {
+    let radius = 5.0 + (health.0 / 50.0);
+    Vec3::new(radius / 5.0, radius / 5.0, 1.0)
+}
+
+/// System that keeps `Frozen` markers in sync with the frozen lineage set.
+/// Marker changes are applied by commands, so a toggle takes effect on the next frame.
+fn frozen_lineage_system(
+    mut commands: Commands,
+    frozen_lineages: Res<FrozenLineages>,
+    ai_query: Query<(Entity, &AILineage, Option<&Frozen>), With<IndividualAI>>,
+) {
+    for (entity, lineage, frozen) in ai_query.iter() {
+        let should_freeze = frozen_lineages.0.contains(lineage);
+        if should_freeze && frozen.is_none() {
+            commands.entity(entity).insert(Frozen);
+        } else if !should_freeze && frozen.is_some() {
+            commands.entity(entity).remove::<Frozen>();
+        }
+    }
+}
+
+/// System that recolors AI sprites when the palette is switched at runtime.
+/// While a lineage is traced, everyone else is dimmed, refreshed every frame so new spawns are dimmed too.
+fn palette_recolor_system(
+    config: Res<SimConfig>,
+    trace: Res<LineageTrace>,
+    mut ai_query: Query<(&AIType, &AILineage, &mut Sprite), With<IndividualAI>>,
+) {
+    if config.color_by_health || (!config.is_changed() && !trace.is_changed() && trace.lineage.is_none()) {
+        return;
+    }
+    for (ai_type, lineage, mut sprite) in ai_query.iter_mut() {
+        sprite.color = match &trace.lineage {
+            Some(traced) if traced != lineage => trace::TRACE_DIM_COLOR,
+            _ => color_for_type(*ai_type, config.palette),
+        };
+    }
+}
+
+/// System that tints living AIs by health when `color_by_health` is on, replacing the per-type colors.
+/// Tracing still dims everyone outside the traced lineage.
+fn ai_color_system(
+    config: Res<SimConfig>,
+    trace: Res<LineageTrace>,
+    mut ai_query: Query<(&Health, &IsAlive, &AILineage, &mut Sprite), With<IndividualAI>>,
+) {
+    if !config.color_by_health {
+        return;
+    }
+    for (health, is_alive, lineage, mut sprite) in ai_query.iter_mut() {
+        if !is_alive.0 {
+            continue;
+        }
+        sprite.color = match &trace.lineage {
+            Some(traced) if traced != lineage => trace::TRACE_DIM_COLOR,
+            _ => palette::health_color(health.0 / invariants::MAX_INDIVIDUAL_HEALTH, lineage),
+        };
+    }
+}
+
+/// System that decays the lineage-trace trail and deposits the traced lineage's current positions.
+fn lineage_trace_system(
+    mut trace: ResMut<LineageTrace>,
+    grid: Res<SpatialGrid>,
+    sim: Res<simulation::Simulation>,
+    config: Res<SimConfig>,
+    ai_query: Query<&AILineage, With<IndividualAI>>,
+) {
+    let Some(traced) = trace.lineage.clone() else { return };
+    let cycles = sim.current_cycle.saturating_sub(trace.last_update_cycle);
+    if cycles == 0 {
+        return;
+    }
+    let mut counts: HashMap<CellCoord, usize> = HashMap::new();
+    for (cell, entries) in &grid.cells {
+        let members = entries.iter().filter(|(entity, _)| ai_query.get(*entity).map_or(false, |l| *l == traced)).count();
+        if members > 0 {
+            counts.insert(*cell, members);
+        }
+    }
+    trace.accumulate(&counts, cycles, config.trace_decay_rate);
+    trace.last_update_cycle = sim.current_cycle;
+}
+
+/// System that rebuilds the spatial grid from living AI positions.
+/// Runs before any system that queries neighbors.
+fn spatial_grid_system(
+    mut grid: ResMut<SpatialGrid>,
+    ai_query: Query<(Entity, &Transform, &IsAlive), With<IndividualAI>>,
+    bounds: Res<WorldBounds>,
+) {
+    grid.clear();
+    grid.wrap = bounds.toroidal.then_some(bounds.size);
+    for (entity, transform, is_alive) in ai_query.iter() {
+        if is_alive.0 {
+            grid.insert(entity, transform.translation.truncate());
+        }
+    }
+}
+
+/// System that refreshes every living AI's `EnvironmentScanData` from its neighbors within its `VisionRange`.
+/// Scans only read other AIs' stats, so entities update in parallel.
+fn ai_scan_system(
+    mut scan_query: Query<(Entity, &Transform, &VisionRange, &mut EnvironmentScanData), With<IndividualAI>>,
+    stats_query: Query<(&Health, &IsAlive, &AILineage, &CombatStrength), With<IndividualAI>>,
+    grid: Res<SpatialGrid>,
+) {
+    scan_query.par_iter_mut().for_each_mut(|(entity, transform, vision_range, mut scan)| {
+        scan.clear();
+        let Ok((_, is_alive, lineage, combat_strength)) = stats_query.get(entity) else { return };
+        if !is_alive.0 {
+            return;
+        }
+        let pos = transform.translation.truncate();
+        let mut neighbors = grid.neighbors_within(pos, vision_range.0);
+        neighbors.sort_by(|(_, a), (_, b)| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)));
+        for (other, _) in neighbors {
+            if other == entity {
+                continue;
+            }
+            let Ok((other_health, other_alive, other_lineage, other_strength)) = stats_query.get(other) else { continue };
+            if other_alive.0 {
+                scan.record(other, other_lineage == lineage, other_health.0, other_strength.0, combat_strength.0);
+            }
+        }
+    });
+}
+
+/// System that periodically recomputes territorial ownership of grid cells.
+fn territory_update_system(
+    mut territory: ResMut<TerritoryMap>,
+    grid: Res<SpatialGrid>,
+    config: Res<SimConfig>,
+    sim: Res<simulation::Simulation>,
+    ai_query: Query<(&AILineage, &CombatStrength, &IsAlive), With<IndividualAI>>,
+) {
+    if !config.territory_enabled {
+        territory.owners.clear();
+        return;
+    }
+    if sim.current_cycle < territory.last_recompute_cycle + config.territory_recompute_interval {
+        return;
+    }
+    let mut tallies: HashMap<CellCoord, HashMap<AILineage, (usize, f32)>> = HashMap::new();
+    for (cell, entries) in grid.cells.iter() {
+        let cell_tally = tallies.entry(*cell).or_default();
+        for (entity, _) in entries {
+            if let Some((lineage, combat_strength, _)) = ai_query.get(*entity).ok().filter(|(_, _, is_alive)| is_alive.0) {
+                let tally = cell_tally.entry(lineage.clone()).or_insert((0, 0.0));
+                tally.0 += 1;
+                tally.1 += combat_strength.0;
+            }
+        }
+    }
+    territory.recompute(&tallies, sim.current_cycle);
+}
+
+/// System to tint territory tiles by their owning lineage.
+/// While a lineage is traced, the tiles show its trail instead.
+fn territory_visual_system(
+    territory: Res<TerritoryMap>,
+    trace: Res<LineageTrace>,
+    config: Res<SimConfig>,
+    mut tile_query: Query<(&TerritoryTile, &mut Sprite)>,
+) {
+    if !territory.is_changed() && !config.is_changed() && !trace.is_changed() {
+        return;
+    }
+    for (tile, mut sprite) in tile_query.iter_mut() {
+        if let Some(traced) = &trace.lineage {
+            let intensity = trace.intensity(tile.0);
+            sprite.color = if intensity > 0.0 {
+                lineage_color(traced, config.palette).with_a(trace::TRACE_TRAIL_MAX_ALPHA * intensity)
+            } else {
+                Color::NONE
+            };
+            continue;
+        }
+        sprite.color = match territory.owner_of(tile.0) {
+            Some(owner) => lineage_color(owner, config.palette).with_a(0.08),
+            None => Color::NONE,
+        };
+    }
+}
+
+/// Debug system asserting per-AI stat invariants each frame.
+/// Enabled in debug builds, or in release via the `AI_SIM_CHECK_INVARIANTS` env var.
+/// Each offending entity is reported once with its full state.
+fn invariant_check_system(
+    checks: Res<InvariantChecks>,
+    sim: Res<simulation::Simulation>,
+    ai_query: Query<(
+        Entity, &AIEntity, &Health, &Energy, &ProcessingPower, &Memory, &Coherence, &Adaptability,
+        &Resilience, &ReplicationEfficiency, &CombatStrength, &DefenseStrength, &IsAlive, &LastAction,
+    ), With<IndividualAI>>,
+    mut reported: Local<HashSet<Entity>>,
+) {
+    if !checks.enabled {
+        return;
+    }
+    for (
+        entity, ai_entity, health, energy, processing_power, memory, coherence, adaptability,
+        resilience, replication_efficiency, combat_strength, defense_strength, is_alive, last_action,
+    ) in ai_query.iter()
+    {
+        if !is_alive.0 || reported.contains(&entity) {
+            continue;
+        }
+        let stats = AIStatsView {
+            health, energy, processing_power, memory, coherence, adaptability,
+            resilience, replication_efficiency, combat_strength, defense_strength, is_alive,
+        };
+        let violations = stats.violations();
+        if violations.is_empty() {
+            continue;
+        }
+        eprintln!("[INVARIANT] Cycle {}: {} ({:?}) violated: {}",
+            sim.current_cycle, ai_entity.id, entity, violations.join("; "));
+        eprintln!("[INVARIANT]   State: {}, LastAction: {}", stats.describe(), last_action.0);
+        eprintln!("[INVARIANT]   Likely source: {}", invariants::likely_source_system(last_action));
+        reported.insert(entity);
+    }
+    reported.retain(|entity| ai_query.contains(*entity));
+}
+
+/// System that resamples per-lineage knowledge every `LOG_INTERVAL` cycles.
+fn lineage_knowledge_system(
+    mut lineage_knowledge: ResMut<LineageKnowledge>,
+    sim: Res<simulation::Simulation>,
+    ai_query: Query<(&AILineage, &KnowledgeBase, &IsAlive), With<IndividualAI>>,
+) {
+    if sim.current_cycle < lineage_knowledge.last_sample_cycle + LOG_INTERVAL {
+        return;
+    }
+    lineage_knowledge.lineages = LineageKnowledge::aggregate(
+        ai_query.iter().filter(|(_, _, is_alive)| is_alive.0).map(|(lineage, kb, _)| (lineage, kb))
+    );
+    lineage_knowledge.last_sample_cycle = sim.current_cycle;
+}
+
+/// System that re-ranks lineages by average fitness every `LOG_INTERVAL` cycles.
+fn lineage_fitness_system(
+    mut lineage_fitness: ResMut<LineageFitness>,
+    sim: Res<simulation::Simulation>,
+    config: Res<SimConfig>,
+    ai_query: Query<(&AILineage, &Health, &CombatStrength, &KnowledgeBase, &ReplicatedCount, &IsAlive), With<IndividualAI>>,
+) {
+    if sim.current_cycle < lineage_fitness.last_sample_cycle + LOG_INTERVAL {
+        return;
+    }
+    lineage_fitness.leaderboard = LineageFitness::rank(
+        ai_query.iter()
+            .filter(|(_, _, _, _, _, is_alive)| is_alive.0)
+            .map(|(lineage, health, combat, kb, replicated, _)| (lineage.clone(), fitness::fitness(health, combat, kb, replicated, &config)))
+    );
+    lineage_fitness.last_sample_cycle = sim.current_cycle;
+}
+
+/// System enforcing the carrying capacity: while more than `max_population` AIs are alive, the least
+/// fit are culled back down to it. Ties on fitness go to the lower id, then the older entity, so the
+/// cull is the same for the same seed.
+fn population_pressure_system(
+    config: Res<SimConfig>,
+    sim: Res<simulation::Simulation>,
+    mut sim_log: ResMut<SimLog>,
+    mut ai_query: Query<(
+        Entity, &AIEntity, &Health, &CombatStrength, &KnowledgeBase, &ReplicatedCount, &mut IsAlive, &mut LastAction,
+    ), With<IndividualAI>>,
+) {
+    let mut ranked: Vec<(f32, &str, Entity)> = ai_query.iter()
+        .filter(|(.., is_alive, _)| is_alive.0)
+        .map(|(entity, ai_entity, health, combat, kb, replicated, ..)| {
+            (fitness::fitness(health, combat, kb, replicated, &config), ai_entity.id.as_str(), entity)
+        })
+        .collect();
+    if ranked.len() <= config.max_population {
+        return;
+    }
+    let excess = ranked.len() - config.max_population;
+    ranked.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(b.1)).then_with(|| a.2.cmp(&b.2)));
+    let culled: Vec<Entity> = ranked.into_iter().take(excess).map(|(.., entity)| entity).collect();
+    for entity in culled {
+        if let Ok((.., mut is_alive, mut last_action)) = ai_query.get_mut(entity) {
+            is_alive.0 = false;
+            last_action.0 = simulation::DeathCause::Culled.action_tag();
+        }
+    }
+    log!(sim_log, sim.current_cycle, SimulationVerbosity::Medium,
+        "Population pressure culled the {} least fit AIs back to the cap of {}", excess, config.max_population);
+}
+
+/// System to orchestrate global simulation updates.
+fn global_simulation_update_system(
+    mut sim: ResMut<simulation::Simulation>,
+    mut sim_rng: ResMut<SimRng>,
+    mut ids: ResMut<IdGenerator>,
+    config: Res<SimConfig>,
+    ai_query: Query<simulation::PopulationQuery, With<IndividualAI>>,
+    id_query: Query<(&AIEntity, &KnowledgeBase, &IsAlive), With<IndividualAI>>,
+    mut extinction_events: EventWriter<simulation::ExtinctionEvent>,
+    mut records: ResMut<Records>,
+) {
+    let snapshot = simulation::PopulationSnapshot::collect(ai_query.iter());
+    let total_ai_count = snapshot.total;
+    let lineage_counts = snapshot.lineage_counts;
+    let strongest = snapshot.strongest.and_then(|entity| {
+        let (ai_entity, ..) = id_query.get(entity).ok()?;
+        Some(simulation::ThreatReport { entity, id: ai_entity.id.clone(), combat_strength: snapshot.max_combat_strength })
+    });
+    // A single step from the UI advances exactly one cycle while paused.
+    let mut cycles = if sim.simulation_running { sim.simulation_speed as u64 } else { 1 };
+    if let Some(remaining) = sim.step_cycles_remaining {
+        cycles = cycles.min(remaining);
+        sim.step_cycles_remaining = Some(remaining - cycles);
+    }
+    for _ in 0..cycles {
+        sim.process_one_cycle(total_ai_count, lineage_counts.clone(), strongest.as_ref(), &config, &mut ids, &mut sim_rng.rng);
+    }
+    // Counts only change between frames, so one sample per update covers every cycle processed.
+    if cycles > 0 {
+        for lineage in sim.track_extinctions(&lineage_counts) {
+            extinction_events.send(simulation::ExtinctionEvent(lineage));
+        }
+        sim.record_population(lineage_counts);
+    }
+    records.observe_population(total_ai_count, sim.current_cycle);
+    if let Some(strongest) = &strongest {
+        records.observe_combat_strength(strongest.combat_strength, &strongest.id);
+    }
+    if let Some((ai_entity, knowledge_base, _)) = id_query.iter()
+        .filter(|(_, _, is_alive)| is_alive.0)
+        .max_by_key(|(_, knowledge_base, _)| knowledge_base.0.len())
+    {
+        records.observe_knowledge(knowledge_base.0.len(), &ai_entity.id);
+    }
+    records.observe_interval_replications(sim.last_interval_stats.replications, sim.current_cycle);
+}
+
+/// System that samples cycle throughput every frame, paused or not, so pauses
+/// drag the average down as they should.
+fn perf_stats_system(mut perf: ResMut<PerfStats>, time: Res<Time>, sim: Res<simulation::Simulation>) {
+    perf.record(time.elapsed_seconds_f64(), sim.current_cycle);
+}
+
+/// System that mirrors the simulation's run flags into `SimPhase` before the logic systems run.
+/// Runs after the command channel and persistence so their changes apply the same frame.
+fn sim_phase_system(
+    sim: Res<simulation::Simulation>,
+    phase: Res<State<SimPhase>>,
+    mut next_phase: ResMut<NextState<SimPhase>>,
+) {
+    let target = sim.phase();
+    if phase.0 != target {
+        next_phase.set(target);
+    }
+}
+
+/// System that ends a step after every logic system has had its pass,
+/// re-pausing once a `step N` command has used up its cycles.
+/// A step requested mid-frame while paused is kept for the next frame's pass.
+fn step_end_system(mut sim: ResMut<simulation::Simulation>, phase: Res<State<SimPhase>>) {
+    if sim.step_requested && phase.0 == SimPhase::Running {
+        sim.step_requested = false;
+    }
+    if sim.step_cycles_remaining == Some(0) {
+        sim.step_cycles_remaining = None;
+        sim.simulation_running = false;
+    }
+}
+
+/// System that applies a changed `VerbosityLevel` to `log!` and the other verbosity checks.
+fn verbosity_sync_system(verbosity: Res<VerbosityLevel>) {
+    if verbosity.is_changed() {
+        simlog::set_verbosity(verbosity.0);
+    }
+}
+
+/// System that moves events logged by simulation logic into the `SimLog`.
+fn sim_log_system(mut sim: ResMut<simulation::Simulation>, mut sim_log: ResMut<SimLog>) {
+    for entry in sim.pending_log.drain(..) {
+        sim_log.push(entry);
+    }
+}
+
+/// System that merges a flagged dominant lineage into a monoculture.
+/// Every living individual of the lineage is absorbed from its real components and despawned this frame.
+fn monoculture_merge_system(
+    mut commands: Commands,
+    mut sim: ResMut<simulation::Simulation>,
+    ai_query: Query<(
+        Entity, &Health, &ProcessingPower, &Memory, &Energy, &Coherence, &Adaptability, &Resilience,
+        &CombatStrength, &DefenseStrength, &KnowledgeBase, &AILineage, &IsAlive,
+    ), With<IndividualAI>>,
+) {
+    let Some(lineage) = sim.pending_monoculture.take() else { return };
+    let mut sources = Vec::new();
+    for (
+        entity, health, processing_power, memory, energy, coherence, adaptability, resilience,
+        combat_strength, defense_strength, knowledge_base, ai_lineage, is_alive,
+    ) in ai_query.iter()
+    {
+        if !is_alive.0 || *ai_lineage != lineage {
+            continue;
+        }
+        sources.push((
+            *health, *processing_power, *memory, *energy, *coherence, *adaptability, *resilience,
+            *combat_strength, *defense_strength, knowledge_base.clone(), ai_lineage.clone(),
+        ));
+        commands.entity(entity).despawn();
+    }
+    sim.form_monoculture(sources);
+}
+
+/// System that performs a queued save or load and answers it on stdout.
+/// A save writes the simulation to `<path>` and its individuals to the sidecar file;
+/// a load replaces both, despawning every current individual first.
+fn persistence_system(
+    mut commands: Commands,
+    mut request: ResMut<PersistenceRequest>,
+    mut sim: ResMut<simulation::Simulation>,
+    mut territory: ResMut<TerritoryMap>,
+    mut ids: ResMut<IdGenerator>,
+    config: Res<SimConfig>,
+    ai_query: Query<(
+        Entity, &Transform, &AIEntity, &IsAlive,
+        (&Health, &Energy, &ProcessingPower, &Memory, &Coherence, &Adaptability, &Resilience, &ReplicationEfficiency),
+        (&ReplicatedCount, &CycleBorn, &LastAction, &Goal, &EthicalDirectives, &KnowledgeBase, &AIType),
+        (&CombatStrength, &DefenseStrength, &LastDiscoveryCycle, &DiscoveryLedger, &ReplicationCooldown, &VisionRange, &CombatRange),
+    ), With<IndividualAI>>,
+) {
+    let Some(action) = request.pending.take() else { return };
+    match action {
+        PersistenceAction::Save(path) => {
+            let individuals = ai_query.iter()
+                .filter(|(_, _, _, is_alive, ..)| is_alive.0)
+                .map(|(
+                    _, transform, ai_entity, _,
+                    (health, energy, processing_power, memory, coherence, adaptability, resilience, replication_efficiency),
+                    (replicated_count, cycle_born, last_action, primary_goal, ethical_directives, knowledge_base, ai_type),
+                    (combat_strength, defense_strength, last_discovery, discovery_ledger, replication_cooldown, vision_range, combat_range),
+                )| IndividualSnapshot {
+                    position: (transform.translation.x, transform.translation.y),
+                    ai_entity: ai_entity.clone(),
+                    health: *health,
+                    energy: *energy,
+                    processing_power: *processing_power,
+                    memory: *memory,
+                    coherence: *coherence,
+                    adaptability: *adaptability,
+                    resilience: *resilience,
+                    replication_efficiency: *replication_efficiency,
+                    replicated_count: *replicated_count,
+                    cycle_born: *cycle_born,
+                    last_action: last_action.clone(),
+                    primary_goal: primary_goal.clone(),
+                    ethical_directives: ethical_directives.clone(),
+                    knowledge_base: knowledge_base.clone(),
+                    ai_type: *ai_type,
+                    combat_strength: *combat_strength,
+                    defense_strength: *defense_strength,
+                    last_discovery: *last_discovery,
+                    discovery_ledger: discovery_ledger.clone(),
+                    replication_cooldown: *replication_cooldown,
+                    vision_range: Some(*vision_range),
+                    combat_range: Some(*combat_range),
+                })
+                .collect();
+            let world = WorldSnapshot { individuals, next_id: ids.next_number() };
+            let result = sim.save_to_path(&path)
+                .and_then(|_| world.save_to_path(&persistence::world_sidecar_path(&path)));
+            match result {
+                Ok(()) => println!("ok saved cycle {} with {} individuals to {}", sim.current_cycle, world.individuals.len(), path.display()),
+                Err(e) => println!("error {}", e),
+            }
+        }
+        PersistenceAction::Load(path) => {
+            let loaded = simulation::Simulation::load_from_path(&path).and_then(|loaded| {
+                WorldSnapshot::load_from_path(&persistence::world_sidecar_path(&path)).map(|world| (loaded, world))
+            });
+            let (loaded, world) = match loaded {
+                Ok(state) => state,
+                Err(e) => {
+                    println!("error {}", e);
+                    return;
+                }
+            };
+            for (entity, ..) in ai_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            let count = world.individuals.len();
+            for snapshot in world.individuals {
+                let position = Vec2::new(snapshot.position.0, snapshot.position.1);
+                let (components, last_discovery, discovery_ledger, replication_cooldown) = snapshot.into_components();
+                let entity = spawn_ai(&mut commands, components, position, config.palette);
+                commands.entity(entity).insert((last_discovery, discovery_ledger, replication_cooldown));
+            }
+            *sim = loaded;
+            ids.resume_from(world.next_id);
+            // Ownership is recomputed on the loaded cycle rather than waiting for the old schedule.
+            *territory = TerritoryMap::default();
+            println!("ok loaded cycle {} with {} individuals from {}", sim.current_cycle, count, path.display());
+        }
+    }
+}
+
+/// System that executes scripted commands from the command channel (enabled by `--commands`).
+/// Mutates the same state the UI controls do and acknowledges each line on stdout.
+fn command_system(
+    mut commands: Commands,
+    channel: Option<Res<CommandChannel>>,
+    mut sim: ResMut<simulation::Simulation>,
+    mut persistence: ResMut<PersistenceRequest>,
+    mut ids: ResMut<IdGenerator>,
+    config: Res<SimConfig>,
+    mut ai_query: Query<(&AILineage, &mut IsAlive, &mut LastAction), With<IndividualAI>>,
+) {
+    let Some(channel) = channel else { return; };
+    for line in channel.drain() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let command = match command::parse_command(&line) {
+            Ok(command) => command,
+            Err(e) => {
+                println!("error {}", e);
+                continue;
+            }
+        };
+        match command {
+            Command::Pause => {
+                sim.simulation_running = false;
+                sim.step_cycles_remaining = None;
+                println!("ok paused at cycle {}", sim.current_cycle);
+            }
+            Command::Resume => {
+                sim.simulation_running = true;
+                sim.step_cycles_remaining = None;
+                println!("ok resumed at cycle {}", sim.current_cycle);
+            }
+            Command::Step(cycles) => {
+                if cycles == 0 {
+                    println!("ok stepped 0 cycles");
+                    continue;
+                }
+                sim.simulation_running = true;
+                sim.step_cycles_remaining = Some(cycles);
+                println!("ok stepping {} cycles from cycle {}", cycles, sim.current_cycle);
+            }
+            Command::Speed(speed) => {
+                sim.simulation_speed = speed;
+                println!("ok speed {}", speed);
+            }
+            Command::Seed { ai_type, position } => {
+                let lineage = simulation::seed_lineage_of(ai_type);
+                let id = ids.next_id("Scripted", ai_type);
+                let components = sim.build_seed_ai(id.clone(), lineage, ai_type);
+                spawn_ai(&mut commands, components, position, config.palette);
+                println!("ok seeded {} at ({}, {})", id, position.x, position.y);
+            }
+            Command::Cull(lineage) => {
+                let mut culled = 0;
+                for (ai_lineage, mut is_alive, mut last_action) in ai_query.iter_mut() {
+                    if *ai_lineage == lineage && is_alive.0 {
+                        // Tagged so ai_death_system records the death as a cull, not "other".
+                        last_action.0 = simulation::DeathCause::Culled.action_tag();
+                        is_alive.0 = false;
+                        culled += 1;
+                    }
+                }
+                println!("ok culled {} {}", culled, lineage);
+            }
+            Command::Stats => {
+                let population = ai_query.iter().filter(|(_, is_alive, _)| is_alive.0).count();
+                let interval = sim.last_interval_stats;
+                println!(
+                    "ok cycle={} population={} godai_health={:.0} monoculture={} running={} replications={} deaths={}",
+                    sim.current_cycle, population, sim.godai.health.0,
+                    sim.monoculture.as_ref().map_or("none".to_string(), |m| m.id.clone()),
+                    sim.simulation_running, interval.replications, interval.deaths
+                );
+            }
+            // Answered by `persistence_system` once the file has been written or read.
+            Command::Save(path) => persistence.pending = Some(PersistenceAction::Save(path.into())),
+            Command::Load(path) => persistence.pending = Some(PersistenceAction::Load(path.into())),
+        }
+    }
+}
+
+/// Startup system that opens the stdin command channel when enabled.
+fn command_channel_setup(mut commands: Commands, config: Res<SimConfig>) {
+    if config.command_channel {
+        commands.insert_resource(CommandChannel::from_stdin());
+        eprintln!("Command channel enabled: reading commands from stdin.");
+    }
+}
+
+/// System to update the Monoculture visual.
+fn update_monoculture_visual_system(
+    mut commands: Commands,
+    sim: Res<simulation::Simulation>,
+    config: Res<SimConfig>,
+    mut monoculture_query: Query<(Entity, &mut Sprite, &mut Transform), With<MonocultureVisual>>,
+) {
+    if let Some(monoculture) = &sim.monoculture {
+        if monoculture.is_alive.0 {
+            if let Ok((_entity, mut sprite, mut transform)) = monoculture_query.single_mut() {
+                sprite.color = monoculture_color(config.palette);
+                let size = 50.0 + (monoculture.health.0 / 1000.0).min(200.0);
+                sprite.custom_size = Some(Vec2::new(size, size));
+                transform.translation = Vec3::new(0.0, 0.0, 0.0);
+            } else {
+                commands.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: monoculture_color(config.palette),
+                            custom_size: Some(Vec2::new(50.0, 50.0)),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_xyz(0.0, 0.0, 0.0),
+                        ..Default::default()
+                    },
+                    MonocultureVisual,
+                ));
+            }
+        } else if let Ok((entity, _, _)) = monoculture_query.single() {
+            commands.entity(entity).despawn();
+        }
+    } else if let Ok((entity, _, _)) = monoculture_query.single() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// System to update the GODAI visual.
+fn update_godai_visual_system(
+    mut commands: Commands,
+    sim: Res<simulation::Simulation>,
+    config: Res<SimConfig>,
+    mut godai_query: Query<(Entity, &mut Sprite, &mut Transform), With<GodaiVisual>>,
+) {
+    if sim.godai.is_alive.0 {
+        if let Ok((_entity, mut sprite, mut transform)) = godai_query.single_mut() {
+            sprite.color = godai_color(config.palette);
+            let size = 100.0 + (sim.godai.health.0 / 100000.0).min(200.0);
+            sprite.custom_size = Some(Vec2::new(size, size));
+            transform.translation = Vec3::new(0.0, 0.0, 0.0);
+        } else {
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: godai_color(config.palette),
+                        custom_size: Some(Vec2::new(100.0, 100.0)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(0.0, 0.0, 0.0),
+                    ..Default::default()
+                },
+                GodaiVisual,
+            ));
+        }
+    } else if let Ok((entity, _, _)) = godai_query.single() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// System that starts capturing the current view to a timestamped PNG (F12 or the UI button).
+/// With `screenshot_include_ui`, `ScreenshotPlugin` reads back the window as drawn, egui panels and all.
+/// Otherwise a second camera renders just the world into an offscreen image that the plugin reads back.
+/// `screenshot_save_system` writes the frame out once it arrives from the GPU.
+fn screenshot_system(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    mut request: ResMut<ScreenshotRequest>,
+    mut capture: ResMut<ScreenshotCapture>,
+    mut images: ResMut<Assets<Image>>,
+    sim: Res<simulation::Simulation>,
+    config: Res<SimConfig>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Transform, &OrthographicProjection), (With<Camera>, Without<ScreenshotCamera>)>,
+    ai_query: Query<&IsAlive, With<IndividualAI>>,
+) {
+    if keys.just_pressed(KeyCode::F12) {
+        request.pending = true;
+    }
+    // One capture at a time; a request made while one is in flight waits for it.
+    if !request.pending || capture.in_flight() {
+        return;
+    }
+    request.pending = false;
+    let Some(window) = windows.iter().next() else { return; };
+    let Some((transform, projection)) = camera_query.iter().next() else { return; };
+    let (width, height) = (window.physical_width(), window.physical_height());
+    if width == 0 || height == 0 {
+        eprintln!("Screenshot skipped: the window is minimized");
+        return;
+    }
+    let population = ai_query.iter().filter(|is_alive| is_alive.0).count();
+    let path = screenshot::screenshot_path(sim.current_cycle, population, sim.godai.health.0);
+    if config.screenshot_include_ui {
+        capture.begin_window(path);
+        return;
+    }
+    let target = images.add(screenshot::capture_target(width, height));
+    // An image target has a scale factor of 1, so the window's is folded into the zoom
+    // for the capture to frame exactly what the window shows.
+    let camera = commands
+        .spawn((
+            Camera2dBundle {
+                camera: Camera { target: RenderTarget::Image(target.clone()), order: -1, ..default() },
+                transform: *transform,
+                projection: OrthographicProjection { scale: projection.scale / window.scale_factor() as f32, ..projection.clone() },
+                ..default()
+            },
+            ScreenshotCamera,
+        ))
+        .id();
+    capture.begin_world(target, camera, path);
+}
+
+/// System that writes a screenshot once its pixels are back from the GPU.
+/// Encoding happens off the main thread so the simulation keeps running.
+fn screenshot_save_system(
+    mut commands: Commands,
+    mut capture: ResMut<ScreenshotCapture>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if !capture.in_flight() {
+        return;
+    }
+    let Some(result) = capture.poll() else { return; };
+    let path = capture.path.clone();
+    let (camera, source) = capture.finish();
+    if let Some(camera) = camera {
+        commands.entity(camera).despawn();
+    }
+    if let Some(screenshot::CaptureSource::World(target)) = source {
+        images.remove(&target);
+    }
+    match result {
+        Ok(frame) => {
+            std::thread::spawn(move || {
+                match screenshot::save_png(frame, &path) {
+                    Ok(()) => eprintln!("Screenshot saved to {}", path.display()),
+                    Err(err) => eprintln!("Screenshot failed: {}", err),
+                }
+            });
+        }
+        Err(err) => eprintln!("Screenshot failed: {}", err),
+    }
+}
+
+/// System that tracks which individual AI is under the cursor, throttled by a timer.
+fn hover_pick_system(
+    time: Res<Time>,
+    mut hovered: ResMut<HoveredAI>,
+    grid: Res<SpatialGrid>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform), Without<ScreenshotCamera>>,
+) {
+    if !hovered.refresh_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Some(window) = windows.iter().next() else { return; };
+    let Some((camera, camera_transform)) = camera_query.iter().next() else { return; };
+    hovered.entity = picking::cursor_world_position(window, camera, camera_transform)
+        .and_then(|cursor| grid.nearest_within(cursor, picking::PICK_RADIUS));
+}
+
+/// System that picks AIs for the combat forecast: click selects the first, shift-click the second.
+fn combat_selection_system(
+    mut contexts: EguiContexts,
+    mouse: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    hovered: Res<HoveredAI>,
+    mut selection: ResMut<CombatSelection>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) || contexts.ctx_mut().is_pointer_over_area() {
+        return;
+    }
+    if keys.any_pressed([KeyCode::LShift, KeyCode::RShift]) {
+        selection.second = hovered.entity;
+    } else {
+        selection.first = hovered.entity;
+        selection.second = None;
+    }
+}
+
+/// System that selects the clicked AI for the inspector; clicking empty space deselects.
+/// Picks from the cursor directly so a click is never judged by a stale hover.
+fn ai_selection_system(
+    mut contexts: EguiContexts,
+    mouse: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    grid: Res<SpatialGrid>,
+    mut selected: ResMut<SelectedAI>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform), Without<ScreenshotCamera>>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) || contexts.ctx_mut().is_pointer_over_area() {
+        return;
+    }
+    // Shift-click only adds a second combatant to the forecast.
+    if keys.any_pressed([KeyCode::LShift, KeyCode::RShift]) {
+        return;
+    }
+    let Some(window) = windows.iter().next() else { return; };
+    let Some((camera, camera_transform)) = camera_query.iter().next() else { return; };
+    selected.entity = picking::cursor_world_position(window, camera, camera_transform)
+        .and_then(|cursor| grid.nearest_within(cursor, picking::PICK_RADIUS));
+}
+
+/// System that enlarges the selected AI's sprite on top of its health-based size, restoring the
+/// previous one on change. Runs every frame after `ai_movement_system`, which rewrites the scale.
+fn selection_highlight_system(
+    selected: Res<SelectedAI>,
+    mut previous: Local<Option<Entity>>,
+    mut ai_query: Query<(&mut Transform, &Health), With<IndividualAI>>,
+) {
+    if *previous != selected.entity {
+        if let Some((mut transform, health)) = previous.and_then(|entity| ai_query.get_mut(entity).ok()) {
+            transform.scale = health_scale(health);
+        }
+        *previous = selected.entity;
+    }
+    if let Some((mut transform, health)) = selected.entity.and_then(|entity| ai_query.get_mut(entity).ok()) {
+        transform.scale = health_scale(health) * Vec3::new(picking::SELECTED_SCALE, picking::SELECTED_SCALE, 1.0);
+    }
+}
+
+/// System that shows the selected AI's full stats in a side panel.
+fn ai_inspector_ui_system(
+    mut contexts: EguiContexts,
+    mut selected: ResMut<SelectedAI>,
+    ai_query: Query<(
+        &AIEntity, &AIType, &AILineage, &Health, &Energy, &Coherence, &Goal, &LastAction, &KnowledgeBase, &IsAlive,
+    ), With<IndividualAI>>,
+) {
+    let Some(entity) = selected.entity else { return; };
+    let Ok((ai_entity, ai_type, lineage, health, energy, coherence, goal, last_action, knowledge_base, is_alive)) = ai_query.get(entity) else {
+        // Despawned since it was picked.
+        selected.entity = None;
+        return;
+    };
+    egui::SidePanel::right("ai_inspector").show(contexts.ctx_mut(), |ui| {
+        ui.heading(&ai_entity.id);
+        if !is_alive.0 {
+            ui.label("Dead");
+        }
+        ui.label(format!("Type: {:?}", ai_type));
+        ui.label(format!("Lineage: {}", lineage));
+        ui.label(format!("Health: {:.1}", health.0));
+        ui.label(format!("Energy: {:.1}", energy.0));
+        ui.label(format!("Coherence: {:.3}", coherence.0));
+        ui.label(format!("Goal: {}", goal.name));
+        ui.label(format!("Last action: {}", last_action.0));
+        ui.separator();
+        ui.label(format!("Knowledge ({} discoveries):", knowledge_base.0.len()));
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for discovery in &knowledge_base.0 {
+                ui.label(format!("- {}", discovery.name)).on_hover_text(&discovery.effect_description);
+            }
+        });
+        if ui.button("Deselect").clicked() {
+            selected.entity = None;
+        }
+    });
+}
+
+/// System that shows the forecast for the two selected AIs, using the combat formulas.
+fn combat_forecast_ui_system(
+    mut contexts: EguiContexts,
+    selection: Res<CombatSelection>,
+    config: Res<SimConfig>,
+    mut layout: ResMut<DashboardLayout>,
+    ai_query: Query<(
+        &AIEntity, &AIType, &Health, &CombatStrength, &DefenseStrength, &Resilience, &Coherence, &IsAlive,
+    ), With<IndividualAI>>,
+) {
+    if !layout.combat_forecast {
+        return;
+    }
+    let (Some(first), Some(second)) = (selection.first, selection.second) else { return; };
+    let Ok([a, b]) = ai_query.get_many([first, second]) else { return; };
+    if !a.7.0 || !b.7.0 {
+        return;
+    }
+    let profile = |(_, ai_type, health, combat, defense, resilience, coherence, _): &(
+        &AIEntity, &AIType, &Health, &CombatStrength, &DefenseStrength, &Resilience, &Coherence, &IsAlive,
+    )| combat::CombatProfile {
+        health: health.0,
+        combat_strength: combat.0,
+        defense_strength: defense.0,
+        resilience: resilience.0,
+        misfire_chance: combat::misfire_chance(**ai_type, coherence.0, &config),
+    };
+    let forecast = combat::predict_combat(&profile(&a), &profile(&b), arena::DEFAULT_ARENA_MAX_ROUNDS);
+    let mut open = true;
+    egui::Window::new("Combat Forecast").open(&mut open).show(contexts.ctx_mut(), |ui| {
+        ui.label(format!("{} ({:?}) strikes first vs {} ({:?})", a.0.id, a.1, b.0.id, b.1));
+        ui.label(format!("{:?} wins: {:.1}%", a.1, forecast.first_win_probability * 100.0));
+        ui.label(format!("{:?} wins: {:.1}%", b.1, forecast.second_win_probability * 100.0));
+        ui.label(format!("Draw: {:.1}%", forecast.draw_probability * 100.0));
+        ui.label(format!("Expected rounds: {:.1}", forecast.expected_rounds));
+    });
+    if !open {
+        layout.combat_forecast = false;
+    }
+}
+
+/// System for the God Mode panel: kills, damages or heals the selected AI and damages or heals GODAI
+/// through the same methods the simulation uses. Spawning is armed here and placed by `god_mode_spawn_system`.
+/// Nothing can be done once the simulation has ended.
+fn god_mode_ui_system(
+    mut contexts: EguiContexts,
+    mut god_mode: ResMut<GodMode>,
+    mut layout: ResMut<DashboardLayout>,
+    mut sim: ResMut<simulation::Simulation>,
+    phase: Res<State<SimPhase>>,
+    selected: Res<SelectedAI>,
+    mut ai_query: Query<(
+        &AIEntity, &mut Health, &mut IsAlive, &mut LastAction, &DefenseStrength, &Resilience, &AIType,
+    ), With<IndividualAI>>,
+) {
+    if !layout.god_mode {
+        return;
+    }
+    let ended = phase.0 == SimPhase::Ended;
+    if ended {
+        god_mode.placing = false;
+    }
+    let mut open = true;
+    egui::Window::new("God Mode").open(&mut open).show(contexts.ctx_mut(), |ui| {
+        if ended {
+            ui.label("The simulation has ended.");
+        }
+        ui.add_enabled_ui(!ended, |ui| {
+            ui.heading("Spawn");
+            egui::ComboBox::from_label("Type")
+                .selected_text(format!("{:?}", god_mode.spawn_type))
+                .show_ui(ui, |ui| {
+                    for (_, ai_type) in simulation::seed_ai_archetypes() {
+                        ui.selectable_value(&mut god_mode.spawn_type, ai_type, format!("{:?}", ai_type));
+                    }
+                });
+            ui.add(egui::Slider::new(&mut god_mode.spawn_count, 1..=godmode::MAX_SPAWN_BATCH).text("count"));
+            let label = if god_mode.placing { "Click the world to place (cancel)" } else { "Spawn at next click" };
+            if ui.button(label).clicked() {
+                god_mode.placing = !god_mode.placing;
+            }
+            ui.separator();
+            ui.add(egui::Slider::new(&mut god_mode.amount, 1.0..=1000.0).logarithmic(true).text("amount"));
+            let amount = god_mode.amount;
+            ui.heading("Selected AI");
+            match selected.entity.and_then(|entity| ai_query.get_mut(entity).ok()) {
+                Some((ai_entity, mut health, mut is_alive, mut last_action, defense, resilience, ai_type)) if is_alive.0 => {
+                    ui.label(format!("{} (Health: {:.0})", ai_entity.id, health.0));
+                    ui.horizontal(|ui| {
+                        if ui.button("Damage").clicked() {
+                            AIEntity::receive_damage(&mut health, &mut is_alive, defense, resilience, *ai_type, amount, "divine");
+                        }
+                        if ui.button("Heal").clicked() {
+                            health.0 = (health.0 + amount).min(health.0.max(godmode::HEAL_CAP));
+                        }
+                        if ui.button("Kill").clicked() {
+                            is_alive.0 = false;
+                            last_action.0 = simulation::DeathCause::Other.action_tag();
+                        }
+                    });
+                }
+                _ => {
+                    ui.label("Click a living AI to select it.");
+                }
+            }
+            ui.heading("GODAI");
+            ui.label(format!("Health: {:.0} ({})", sim.godai.health.0, sim.godai.phase));
+            ui.add_enabled_ui(sim.godai.is_alive.0, |ui| {
+                ui.horizontal(|ui| {
+                    let sim = &mut *sim;
+                    let cycle = sim.current_cycle;
+                    if ui.button("Damage").clicked() {
+                        sim.godai.receive_damage(amount, "divine", &mut sim.pending_log, cycle);
+                        sim.godai.update_phase(&mut sim.pending_log, cycle);
+                    }
+                    if ui.button("Heal").clicked() {
+                        sim.godai.health.0 = (sim.godai.health.0 + amount).min(sim.godai.max_health);
+                        sim.godai.update_phase(&mut sim.pending_log, cycle);
+                    }
+                });
+            });
+        });
+    });
+    if !open {
+        layout.god_mode = false;
+        god_mode.placing = false;
+    }
+}
+
+/// System that spawns the God Mode batch around the clicked point once placement is armed.
+fn god_mode_spawn_system(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mouse: Res<Input<MouseButton>>,
+    mut god_mode: ResMut<GodMode>,
+    sim: Res<simulation::Simulation>,
+    config: Res<SimConfig>,
+    bounds: Res<WorldBounds>,
+    mut sim_rng: ResMut<SimRng>,
+    mut ids: ResMut<IdGenerator>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform), Without<ScreenshotCamera>>,
+) {
+    if !god_mode.placing || !mouse.just_pressed(MouseButton::Left) || contexts.ctx_mut().is_pointer_over_area() {
+        return;
+    }
+    let Some(window) = windows.iter().next() else { return; };
+    let Some((camera, camera_transform)) = camera_query.iter().next() else { return; };
+    let Some(cursor) = picking::cursor_world_position(window, camera, camera_transform) else { return; };
+    let ai_type = god_mode.spawn_type;
+    let lineage = simulation::seed_lineage_of(ai_type);
+    let rng = &mut sim_rng.rng;
+    for _ in 0..god_mode.spawn_count {
+        let id = ids.next_id("Divine", ai_type);
+        let components = sim.build_seed_ai(id, lineage.clone(), ai_type);
+        let offset = Vec2::new(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0)) * godmode::SPAWN_SCATTER_RADIUS;
+        spawn_ai(&mut commands, components, bounds.confine(cursor + offset), config.palette);
+    }
+    god_mode.placing = false;
+}
+
+/// System that shows a lightweight tooltip for the hovered AI.
+/// Suppressed while the pointer is over an egui panel.
+fn ai_tooltip_system(
+    mut contexts: EguiContexts,
+    hovered: Res<HoveredAI>,
+    ai_query: Query<(&AIEntity, &AIType, &AILineage, &Health, &LastAction, &IsAlive), With<IndividualAI>>,
+) {
+    let Some(entity) = hovered.entity else { return; };
+    let Ok((ai_entity, ai_type, lineage, health, last_action, is_alive)) = ai_query.get(entity) else { return; };
+    if !is_alive.0 {
+        return;
+    }
+    let ctx = contexts.ctx_mut();
+    if ctx.is_pointer_over_area() {
+        return;
+    }
+    egui::show_tooltip_at_pointer(ctx, egui::Id::new("ai_thought_tooltip"), |ui| {
+        ui.strong(&ai_entity.id);
+        ui.label(format!("Type: {:?}", ai_type));
+        ui.label(format!("Lineage: {}", lineage));
+        ui.label(format!("Health: {:.1}", health.0));
+        ui.label(format!("Last action: {}", last_action.0));
+    });
+}
+
+/// System that spawns a fading damage number for each attack that drew blood.
+/// Skipped entirely in Silent mode.
+fn damage_number_spawn_system(
+    mut commands: Commands,
+    mut attack_events: EventReader<combat::AttackEvent>,
+    verbosity: Res<VerbosityLevel>,
+) {
+    if verbosity.0 == SimulationVerbosity::Silent {
+        attack_events.clear();
+        return;
+    }
+    for event in attack_events.iter().filter(|event| event.damage > 0.0) {
+        commands.spawn((
+            FloatingDamage { damage: event.damage, pos: event.pos },
+            Lifetime::from_seconds(effects::DAMAGE_NUMBER_SECONDS),
+        ));
+    }
+}
+
+/// System that despawns entities whose `Lifetime` has run out.
+fn lifetime_system(mut commands: Commands, time: Res<Time>, mut query: Query<(Entity, &mut Lifetime)>) {
+    for (entity, mut lifetime) in query.iter_mut() {
+        if lifetime.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// System showing a fading notice at the top of the screen for each lineage that goes extinct.
+fn extinction_notice_ui_system(
+    mut contexts: EguiContexts,
+    time: Res<Time>,
+    mut extinction_events: EventReader<simulation::ExtinctionEvent>,
+    mut notices: Local<Vec<(AILineage, Timer)>>,
+) {
+    for simulation::ExtinctionEvent(lineage) in extinction_events.iter() {
+        notices.push((lineage.clone(), Timer::from_seconds(effects::EXTINCTION_NOTICE_SECONDS, TimerMode::Once)));
+    }
+    for (_, timer) in notices.iter_mut() {
+        timer.tick(time.delta());
+    }
+    notices.retain(|(_, timer)| !timer.finished());
+    if notices.is_empty() {
+        return;
+    }
+    egui::Area::new("extinction_notices")
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 40.0))
+        .show(contexts.ctx_mut(), |ui| {
+            for (lineage, timer) in notices.iter() {
+                let alpha = ((1.0 - timer.percent()) * 255.0) as u8;
+                ui.label(egui::RichText::new(format!("Lineage {} has gone extinct", lineage))
+                    .strong().color(egui::Color32::from_rgba_unmultiplied(255, 120, 80, alpha)));
+            }
+        });
+}
+
+/// System that draws damage numbers over the world, rising and fading as their lifetime runs out.
+fn damage_number_ui_system(
+    mut contexts: EguiContexts,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform), Without<ScreenshotCamera>>,
+    damage_query: Query<(&FloatingDamage, &Lifetime)>,
+) {
+    let Some(window) = windows.iter().next() else { return; };
+    let Some((camera, camera_transform)) = camera_query.iter().next() else { return; };
+    let painter = contexts.ctx_mut().layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("damage_numbers")));
+    for (floating, lifetime) in damage_query.iter() {
+        let progress = lifetime.0.percent();
+        let world = floating.pos + Vec2::Y * effects::DAMAGE_NUMBER_RISE * progress;
+        let Some(viewport) = camera.world_to_viewport(camera_transform, world.extend(0.0)) else { continue };
+        // Viewport y points up, egui y points down.
+        let screen = egui::pos2(viewport.x, window.height() - viewport.y);
+        let alpha = ((1.0 - progress) * 255.0) as u8;
+        painter.text(
+            screen, egui::Align2::CENTER_BOTTOM, format!("{:.0}", floating.damage),
+            egui::FontId::proportional(14.0), egui::Color32::from_rgba_unmultiplied(255, 80, 80, alpha),
+        );
+    }
+}
+
+/// System that shades the world by population density, one translucent square per heatmap tile.
+/// Does nothing while the heatmap is off.
+fn density_heatmap_ui_system(
+    mut contexts: EguiContexts,
+    heatmap: Res<DensityHeatmap>,
+    grid: Res<SpatialGrid>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform), Without<ScreenshotCamera>>,
+) {
+    if !heatmap.enabled {
+        return;
+    }
+    let Some(window) = windows.iter().next() else { return; };
+    let Some((camera, camera_transform)) = camera_query.iter().next() else { return; };
+    let counts = heatmap.tile_counts(&grid);
+    let Some(&max_count) = counts.values().max() else { return; };
+    let painter = contexts.ctx_mut().layer_painter(egui::LayerId::new(egui::Order::Background, egui::Id::new("density_heatmap")));
+    for (tile, count) in counts {
+        let (corner, side) = heatmap.tile_rect(tile);
+        let (Some(min), Some(max)) = (
+            camera.world_to_viewport(camera_transform, corner.extend(0.0)),
+            camera.world_to_viewport(camera_transform, (corner + Vec2::splat(side)).extend(0.0)),
+        ) else { continue };
+        // Viewport y points up, egui y points down.
+        let rect = egui::Rect::from_two_pos(
+            egui::pos2(min.x, window.height() - min.y),
+            egui::pos2(max.x, window.height() - max.y),
+        );
+        let density = count as f32 / max_count as f32;
+        let [r, g, b] = heatmap::heat_rgb(density);
+        let alpha = (density * heatmap::HEATMAP_MAX_ALPHA * 255.0) as u8;
+        painter.rect_filled(rect, 0.0, egui::Color32::from_rgba_unmultiplied(r, g, b, alpha));
+    }
+}
+
+/// Camera zoom limits (`OrthographicProjection.scale`).
+const CAMERA_MIN_ZOOM: f32 = 0.2;
+const CAMERA_MAX_ZOOM: f32 = 10.0;
+/// WASD pan speed in screen pixels per second, so panning feels the same at every zoom.
+const CAMERA_PAN_SPEED: f32 = 500.0;
+
+/// System for mouse-wheel zoom and middle-drag / WASD panning.
+/// Input egui is using (scrolling a panel, dragging a slider, typing) never reaches the camera.
+fn camera_control_system(
+    mut contexts: EguiContexts,
+    mut wheel_events: EventReader<MouseWheel>,
+    mut motion_events: EventReader<MouseMotion>,
+    mouse: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), (With<Camera>, Without<ScreenshotCamera>)>,
+) {
+    // Drain the events every frame so input captured by egui isn't applied later.
+    let scroll: f32 = wheel_events.iter()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 100.0,
+        })
+        .sum();
+    let drag: Vec2 = motion_events.iter().map(|event| event.delta).sum();
+    let ctx = contexts.ctx_mut();
+    let pointer_captured = ctx.is_pointer_over_area() || ctx.wants_pointer_input();
+    let keyboard_captured = ctx.wants_keyboard_input();
+    let Ok((mut transform, mut projection)) = camera_query.get_single_mut() else { return; };
+
+    if !pointer_captured {
+        if scroll != 0.0 {
+            projection.scale = (projection.scale * 1.1f32.powf(-scroll)).clamp(CAMERA_MIN_ZOOM, CAMERA_MAX_ZOOM);
+        }
+        if mouse.pressed(MouseButton::Middle) {
+            // Screen y points down, world y points up.
+            transform.translation.x -= drag.x * projection.scale;
+            transform.translation.y += drag.y * projection.scale;
+        }
+    }
+    if !keyboard_captured {
+        let mut direction = Vec2::ZERO;
+        if keys.pressed(KeyCode::W) { direction.y += 1.0; }
+        if keys.pressed(KeyCode::S) { direction.y -= 1.0; }
+        if keys.pressed(KeyCode::A) { direction.x -= 1.0; }
+        if keys.pressed(KeyCode::D) { direction.x += 1.0; }
+        let pan = direction.normalize_or_zero() * CAMERA_PAN_SPEED * projection.scale * time.delta_seconds();
+        transform.translation += pan.extend(0.0);
+    }
+}
+
+/// System to render the Egui UI panel.
+fn egui_ui_system(
+    mut contexts: EguiContexts,
+    mut sim: ResMut<simulation::Simulation>,
+    mut screenshot_request: ResMut<ScreenshotRequest>,
+    mut frozen_lineages: ResMut<FrozenLineages>,
+    mut config: ResMut<SimConfig>,
+    mut layout: ResMut<DashboardLayout>,
+    mut trace: ResMut<LineageTrace>,
+    lineage_knowledge: Res<LineageKnowledge>,
+    lineage_fitness: Res<LineageFitness>,
+    perf: Res<PerfStats>,
+    sim_rng: Res<SimRng>,
+    sim_log: Res<SimLog>,
+    records: Res<Records>,
+    mut heatmap: ResMut<DensityHeatmap>,
+    mut verbosity: ResMut<VerbosityLevel>,
+    ai_query: Query<simulation::PopulationQuery, With<IndividualAI>>,
+) {
+    let ctx = contexts.ctx_mut();
+    // Work on a copy so the layout is only marked changed when a panel is actually toggled.
+    let mut next_layout = layout.clone();
+    egui::TopBottomPanel::top("dashboard_menu").show(ctx, |ui| {
+        egui::menu::bar(ui, |ui| {
+            ui.menu_button("Panels", |ui| {
+                for (name, open) in next_layout.panels_mut() {
+                    ui.checkbox(open, name);
+                }
+            });
+        });
+    });
+    egui::Window::new("Simulation Controls").open(&mut next_layout.controls).show(ctx, |ui| {
+        ui.heading("Simulation Status");
+        ui.label(format!("Cycle: {}", format_thousand_separator(sim.current_cycle)));
+        ui.label(format!("Cycles/sec: {:.1} (elapsed {:.0}s)", perf.cycles_per_second, perf.elapsed_seconds));
+        let population = simulation::PopulationSnapshot::collect(ai_query.iter());
+        ui.label(format!("Population: {}", format_thousand_separator(population.total as u64)));
+        ui.label(format!("Avg Health: {:.0}, Avg Coherence: {:.2}, Max Combat: {:.0}",
+            population.average_health, population.average_coherence, population.max_combat_strength));
+        ui.label(format!("GODAI Health: {:.0} ({})", sim.godai.health.0, sim.godai.phase));
+        if let Some(monoculture) = &sim.monoculture {
+            ui.label(format!("Monoculture Health: {:.0}", monoculture.health.0));
+        } else {
+            ui.label("Monoculture: Not formed");
+        }
+        if let Some(rival) = &sim.rival_monoculture {
+            ui.label(format!("Rival Monoculture Health: {:.0}", rival.health.0));
+        }
+        if let Some(catastrophe) = &sim.catastrophe {
+            ui.label(format!("Catastrophe: {} (ends at cycle {})", catastrophe.kind, catastrophe.ends_at_cycle));
+        }
+        if !sim.extinctions.is_empty() {
+            ui.collapsing(format!("Extinct lineages ({})", sim.extinctions.len()), |ui| {
+                for (lineage, cycle) in &sim.extinctions {
+                    ui.label(format!("{}: cycle {}", lineage, cycle));
+                }
+            });
+        }
+        let interval = sim.last_interval_stats;
+        ui.label(format!("Last interval: {} replications, {} deaths, {} attacks, {} heals",
+            interval.replications, interval.deaths, interval.attacks, interval.heals));
+        let lifetime = sim.lifetime_totals();
+        ui.label(format!("Whole run: {} replications, {} deaths, {} attacks, {} heals",
+            lifetime.replications, lifetime.deaths, lifetime.attacks, lifetime.heals));
+        ui.collapsing("Records", |ui| {
+            for line in records.lines() {
+                ui.label(line);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(format!("Seed: {}", sim_rng.seed));
+            if ui.button("Copy").clicked() {
+                ui.output_mut(|o| o.copied_text = sim_rng.seed.to_string());
+            }
+        });
+        if let Some(reason) = &sim.simulation_over_reason {
+            ui.label(format!("Simulation Over: {}", reason));
+        }
+        ui.add_space(10.0);
+        ui.heading("Controls");
+        if ui.button(if sim.simulation_running { "Pause" } else { "Resume" }).clicked() {
+            sim.simulation_running = !sim.simulation_running;
+        }
+        if ui.add_enabled(!sim.simulation_running, egui::Button::new("Step")).clicked() {
+            sim.step_requested = true;
+        }
+        ui.horizontal(|ui| {
+            ui.label("Speed:");
+            ui.add(egui::Slider::new(&mut sim.simulation_speed, 1.0..=100.0).text("cycles/frame"));
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Screenshot (F12)").clicked() {
+                screenshot_request.pending = true;
+            }
+            ui.checkbox(&mut config.screenshot_include_ui, "Include UI");
+        });
+        let mut palette = config.palette;
+        egui::ComboBox::from_label("Palette")
+            .selected_text(palette.label())
+            .show_ui(ui, |ui| {
+                for option in Palette::ALL {
+                    ui.selectable_value(&mut palette, option, option.label());
+                }
+            });
+        if palette != config.palette {
+            config.palette = palette;
+        }
+        let mut level = verbosity.0;
+        egui::ComboBox::from_label("Log verbosity")
+            .selected_text(format!("{:?}", level))
+            .show_ui(ui, |ui| {
+                for option in SimulationVerbosity::ALL {
+                    ui.selectable_value(&mut level, option, format!("{:?}", option));
+                }
+            });
+        if level != verbosity.0 {
+            verbosity.0 = level;
+        }
+        let mut color_by_health = config.color_by_health;
+        if ui.checkbox(&mut color_by_health, "Color by health").changed() {
+            config.color_by_health = color_by_health;
+        }
+        ui.checkbox(&mut heatmap.enabled, "Density heatmap");
+        if heatmap.enabled {
+            ui.add(egui::Slider::new(&mut heatmap.cells_per_tile, 1..=heatmap::MAX_CELLS_PER_TILE).text("Heatmap resolution (cells per tile)"));
+        }
+        let mut traced = trace.lineage.clone();
+        egui::ComboBox::from_label("Trace lineage")
+            .selected_text(traced.as_ref().map_or("None".to_string(), |l| l.to_string()))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut traced, None, "None");
+                for (lineage, _) in simulation::seed_ai_archetypes() {
+                    let label = lineage.to_string();
+                    ui.selectable_value(&mut traced, Some(lineage), label);
+                }
+            });
+        if traced != trace.lineage {
+            trace.set_lineage(traced);
+            trace.last_update_cycle = sim.current_cycle;
+        }
+        ui.add_space(10.0);
+        let mut threshold = config.monoculture_dominance_threshold;
+        if ui.add(egui::Slider::new(&mut threshold, 0.5..=1.0).text("Monoculture threshold")).changed() {
+            config.monoculture_dominance_threshold = threshold;
+        }
+        let mut min_count = config.monoculture_min_count;
+        if ui.add(egui::Slider::new(&mut min_count, 1..=config::POPULATION_HARD_CAP).logarithmic(true).text("Monoculture min count")).changed() {
+            config.monoculture_min_count = min_count;
+        }
+        let mut replication_cap = config.replication_cap;
+        if ui.add(egui::Slider::new(&mut replication_cap, 0..=5000).text("Replication cap")).changed() {
+            config.replication_cap = replication_cap;
+        }
+        if trace.lineage.is_some() {
+            let mut decay = config.trace_decay_rate;
+            if ui.add(egui::Slider::new(&mut decay, 0.0..=0.2).text("Trail decay")).changed() {
+                config.trace_decay_rate = decay;
+            }
+        }
+    });
+    egui::Window::new("Lineage Knowledge").open(&mut next_layout.lineage_knowledge).show(ctx, |ui| {
+        let mut ranked: Vec<_> = lineage_knowledge.lineages.iter().collect();
+        ranked.sort_by_key(|(_, tech)| std::cmp::Reverse(tech.discoveries.len()));
+        for (lineage, tech) in ranked {
+            let mut milestones = Vec::new();
+            if tech.has_combat_upgrade { milestones.push("combat"); }
+            if tech.has_meta_ability { milestones.push("meta-ability"); }
+            if tech.has_override_protocol { milestones.push("OVERRIDE"); }
+            ui.label(format!("{}: {} techs [{}]", lineage, tech.discoveries.len(), milestones.join(", ")));
+        }
+    });
+    egui::Window::new("Fitness Leaderboard").open(&mut next_layout.fitness_leaderboard).show(ctx, |ui| {
+        for (rank, (lineage, score)) in lineage_fitness.leaderboard.iter().enumerate() {
+            ui.label(format!("{}. {}: {:.1}", rank + 1, lineage, score));
+        }
+    });
+    egui::Window::new("Population History").open(&mut next_layout.population_history).show(ctx, |ui| {
+        egui::plot::Plot::new("population_history")
+            .legend(egui::plot::Legend::default())
+            .height(220.0)
+            .show(ui, |plot_ui| {
+                for (lineage, _) in simulation::seed_ai_archetypes() {
+                    let points: egui::plot::PlotPoints = sim.population_history.iter()
+                        .map(|(cycle, counts)| [*cycle as f64, counts.get(&lineage).copied().unwrap_or(0) as f64])
+                        .collect();
+                    let color = palette::to_egui_color(lineage_color(&lineage, config.palette));
+                    plot_ui.line(egui::plot::Line::new(points).color(color).name(lineage.to_string()));
+                }
+            });
+    });
+    egui::Window::new("Event Log").open(&mut next_layout.event_log).show(ctx, |ui| {
+        egui::ScrollArea::vertical().stick_to_bottom(true).max_height(250.0).show(ui, |ui| {
+            for entry in sim_log.recent(simlog::SIM_LOG_PANEL_ENTRIES) {
+                let text = egui::RichText::new(format!("[{}] {}", entry.cycle, entry.message));
+                ui.label(if entry.severity <= SimulationVerbosity::Critical { text.strong() } else { text });
+            }
+        });
+    });
+    egui::Window::new("Freeze Lineages").open(&mut next_layout.freeze_lineages).show(ctx, |ui| {
+        for (lineage, _) in simulation::seed_ai_archetypes() {
+            let mut frozen = frozen_lineages.0.contains(&lineage);
+            if ui.checkbox(&mut frozen, lineage.to_string()).changed() {
+                if frozen {
+                    frozen_lineages.0.insert(lineage);
+                } else {
+                    frozen_lineages.0.remove(&lineage);
+                }
+            }
+        }
+    });
+    if next_layout != *layout {
+        *layout = next_layout;
+    }
+}
+
+/// System that saves the dashboard layout whenever a panel is toggled.
+fn dashboard_persist_system(layout: Res<DashboardLayout>) {
+    if !layout.is_changed() || layout.is_added() {
+        return;
+    }
+    if let Err(e) = layout.save(Path::new(dashboard::DASHBOARD_LAYOUT_FILE)) {
+        eprintln!("Dashboard layout not saved: {}", e);
+    }
+}
+
+/// System that samples run metrics every `LOG_INTERVAL` cycles for the CSV export.
+fn metrics_record_system(
+    mut recorder: ResMut<MetricsRecorder>,
+    sim: Res<simulation::Simulation>,
+    ai_query: Query<simulation::PopulationQuery, With<IndividualAI>>,
+) {
+    if recorder.last_record_cycle.map_or(false, |last| sim.current_cycle < last + LOG_INTERVAL) {
+        return;
+    }
+    let population = simulation::PopulationSnapshot::collect(ai_query.iter());
+    recorder.record(
+        sim.current_cycle,
+        &population.lineage_counts,
+        sim.godai.health.0,
+        sim.monoculture.as_ref().map(|mono| mono.health.0),
+        sim.last_interval_stats,
+    );
+}
+
+/// System to handle simulation end.
+/// On a trial restart, `Records` and the `MetricsRecorder` rows are kept, so they cover the whole
+/// session; metrics rows carry their trial number since cycles restart from zero.
+fn simulation_end_system(
+    mut commands: Commands,
+    mut sim: ResMut<simulation::Simulation>,
+    mut recorder: ResMut<MetricsRecorder>,
+    mut genealogy: ResMut<Genealogy>,
+    mut lineage_fitness: ResMut<LineageFitness>,
+    records: Res<Records>,
+    mut territory: ResMut<TerritoryMap>,
+    mut economy: ResMut<FoodEconomy>,
+    // Paired to stay within Bevy's system parameter limit.
+    (mut sim_rng, mut ids): (ResMut<SimRng>, ResMut<IdGenerator>),
+    config: Res<SimConfig>,
+    bounds: Res<WorldBounds>,
+    mut next_phase: ResMut<NextState<SimPhase>>,
+    mut exit: EventWriter<AppExit>,
+    ai_query: Query<simulation::PopulationQuery, With<IndividualAI>>,
+    trial_query: Query<Entity, Or<(With<IndividualAI>, With<Corpse>, With<FoodSource>)>>,
+) {
+    if sim.simulation_over_reason.is_some() {
+        let population = simulation::PopulationSnapshot::collect(ai_query.iter());
+        // Auto-restart: clear the world and reseed it from the same `SimRng` stream, so the
+        // whole series of trials stays reproducible from one seed.
+        if sim.finish_trial(population.total) {
+            for entity in trial_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            *sim = sim.next_trial(&config);
+            *genealogy = Genealogy::new(config.genealogy_prune_dead);
+            *lineage_fitness = LineageFitness::default();
+            *territory = TerritoryMap::default();
+            *economy = FoodEconomy::from_seed(sim_rng.seed ^ sim.trial_outcomes.len() as u64);
+            *ids = IdGenerator::new(config.uuid_ids);
+            recorder.start_trial(sim.trial_outcomes.len() + 1);
+            let rng = &mut sim_rng.rng;
+            for (components, position) in initial_population(&mut sim, &config, &bounds, &mut ids, rng) {
+                spawn_ai(&mut commands, components, position, config.palette);
+            }
+            println!("Starting trial {} ({} left)", sim.trial_outcomes.len() + 1, sim.trials_remaining);
+            return;
+        }
+        next_phase.set(SimPhase::Ended);
+        if let Some(path) = &config.report_path {
+            let report = sim.final_report_json(population.total, population.lineage_counts.clone());
+            let written = serde_json::to_string_pretty(&report)
+                .map_err(|e| format!("could not serialize report: {}", e))
+                .and_then(|json| std::fs::write(path, json).map_err(|e| format!("could not write {}: {}", path.display(), e)));
+            if let Err(e) = written {
+                eprintln!("Report not saved: {}", e);
+            }
+        }
+        sim.print_final_summary(population.total, population.lineage_counts, &genealogy, &lineage_fitness.leaderboard, &records);
+        sim.print_trial_summary();
+        if let Err(e) = recorder.flush_csv(Path::new(metrics::METRICS_CSV_FILE)) {
+            eprintln!("Metrics not saved: {}", e);
+        }
+        exit.send(AppExit);
+    }
+}
+
+/// Main execution.
+fn main() {
+    let mut config = SimConfig::default();
+
+    // Headless combat arena: `--arena <TypeA> <TypeB> [--seed N] [--rounds N]` or `--arena --suite`.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("--arena") {
+        match arena::matchups_from_args(&args[1..]) {
+            Ok(matchups) => {
+                for matchup in &matchups {
+                    println!("{}", arena::run_arena(matchup, &config).describe());
+                }
+            }
+            Err(e) => {
+                eprintln!("Arena error: {}", e);
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+    // Regression harness: `--harness [--seed N] [--cycles N]` runs twice headless and checks invariants and determinism.
+    if args.first().map(String::as_str) == Some("--harness") {
+        let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+        let seed = flag("--seed").map_or(Ok(0), |s| s.parse::<u64>());
+        let cycles = flag("--cycles").map_or(Ok(harness::DEFAULT_HARNESS_CYCLES), |s| s.parse::<u64>());
+        let (Ok(seed), Ok(cycles)) = (seed, cycles) else {
+            eprintln!("Harness error: --seed and --cycles take whole numbers");
+            std::process::exit(2);
+        };
+        match harness::check_reproducible(&config, seed, cycles) {
+            Ok(report) => println!("Harness passed. {}", report),
+            Err(failures) => {
+                for failure in &failures {
+                    eprintln!("Harness failure: {}", failure);
+                }
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    // Core-loop benchmark: `--bench-cycles N [--seed N] [--population N]` times internal state, replication and death.
+    if let Some(i) = args.iter().position(|a| a == "--bench-cycles") {
+        let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+        let cycles = args.get(i + 1).map_or(Ok(0), |s| s.parse::<u64>());
+        let seed = flag("--seed").map_or(Ok(0), |s| s.parse::<u64>());
+        let population = flag("--population").map_or(Ok(perf::DEFAULT_BENCH_POPULATION), |s| s.parse::<usize>());
+        let (Ok(cycles), Ok(seed), Ok(population)) = (cycles, seed, population) else {
+            eprintln!("Benchmark error: --bench-cycles, --seed and --population take whole numbers");
+            std::process::exit(2);
+        };
+        println!("{}", perf::run_cycle_benchmark(&config, seed, population, cycles).describe());
+        return;
+    }
+    config.command_channel = args.iter().any(|a| a == "--commands");
+    // `--uuid-ids` names AIs with random UUID fragments, as before sequential ids; runs then aren't reproducible.
+    config.uuid_ids = args.iter().any(|a| a == "--uuid-ids");
+    // `--screenshot-without-ui` captures only the world, leaving the egui panels out of screenshots.
+    config.screenshot_include_ui = !args.iter().any(|a| a == "--screenshot-without-ui");
+    // `--trials N` runs N trials back to back, restarting the world whenever one ends.
+    if let Some(value) = args.iter().position(|a| a == "--trials").and_then(|i| args.get(i + 1)) {
+        match value.parse::<u32>() {
+            Ok(trials) if trials > 0 => config.trials = trials,
+            _ => {
+                eprintln!("Invalid --trials '{}' (expected a positive whole number)", value);
+                std::process::exit(2);
+            }
+        }
+    }
+    // `--max-population N` sets the carrying capacity.
+    if let Some(value) = args.iter().position(|a| a == "--max-population").and_then(|i| args.get(i + 1)) {
+        match value.parse::<usize>() {
+            Ok(max) if max > 0 => config.max_population = max,
+            _ => {
+                eprintln!("Invalid --max-population '{}' (expected a positive whole number)", value);
+                std::process::exit(2);
+            }
+        }
+    }
+    // `--despawn-budget N` caps how many dead AIs are despawned per frame; the rest wait for later frames.
+    if let Some(value) = args.iter().position(|a| a == "--despawn-budget").and_then(|i| args.get(i + 1)) {
+        match value.parse::<usize>() {
+            Ok(budget) if budget > 0 => config.despawn_budget_per_frame = budget,
+            _ => {
+                eprintln!("Invalid --despawn-budget '{}' (expected a positive whole number)", value);
+                std::process::exit(2);
+            }
+        }
+    }
+    config.report_path = args.iter().position(|a| a == "--report").and_then(|i| args.get(i + 1)).map(PathBuf::from);
+    // `--scenario scenario.json` starts from the entities and GODAI overrides in the file.
+    config.scenario_path = args.iter().position(|a| a == "--scenario").and_then(|i| args.get(i + 1)).map(PathBuf::from);
+    if let Some(seed) = args.iter().position(|a| a == "--seed").and_then(|i| args.get(i + 1)) {
+        match seed.parse() {
+            Ok(seed) => config.rng_seed = Some(seed),
+            Err(_) => {
+                eprintln!("Invalid --seed '{}'", seed);
+                std::process::exit(2);
+            }
+        }
+    }
+    // `--seed-mix HealerAI=0.9,KillerAI=0.1` sets the initial lineage mix.
+    if let Some(value) = args.iter().position(|a| a == "--seed-mix").and_then(|i| args.get(i + 1)) {
+        match config::SeedConfig::parse_lineage_weights(value) {
+            Ok(weights) => config.seed.lineage_weights = Some(weights),
+            Err(e) => {
+                eprintln!("Invalid --seed-mix '{}': {}", value, e);
+                std::process::exit(2);
+            }
+        }
+    }
+    // `--stat-spread health=normal:10,combat_strength=uniform:-5:5` varies seeded stats around their archetype.
+    if let Some(value) = args.iter().position(|a| a == "--stat-spread").and_then(|i| args.get(i + 1)) {
+        match config::StatSpread::parse(value) {
+            Ok(spread) => config.seed.stat_spread = spread,
+            Err(e) => {
+                eprintln!("Invalid --stat-spread '{}': {}", value, e);
+                std::process::exit(2);
+            }
+        }
+    }
+    // `--sexual-lineage KillerAI` makes that lineage replicate only with a nearby mate.
+    if let Some(value) = args.iter().position(|a| a == "--sexual-lineage").and_then(|i| args.get(i + 1)) {
+        match value.parse() {
+            Ok(lineage) => config.sexual_lineage = Some(lineage),
+            Err(e) => {
+                eprintln!("Invalid --sexual-lineage '{}': {}", value, e);
+                std::process::exit(2);
+            }
+        }
+    }
+    // `--monoculture-min N` and `--monoculture-share F` override when a dominant lineage merges.
+    if let Some(value) = args.iter().position(|a| a == "--monoculture-min").and_then(|i| args.get(i + 1)) {
+        match value.parse() {
+            Ok(min_count) => config.monoculture_min_count = min_count,
+            Err(_) => {
+                eprintln!("Invalid --monoculture-min '{}'", value);
+                std::process::exit(2);
+            }
+        }
+    }
+    if let Some(value) = args.iter().position(|a| a == "--monoculture-share").and_then(|i| args.get(i + 1)) {
+        match value.parse::<f32>() {
+            Ok(share) if (0.0..=1.0).contains(&share) => config.monoculture_dominance_threshold = share,
+            _ => {
+                eprintln!("Invalid --monoculture-share '{}' (expected 0.0-1.0)", value);
+                std::process::exit(2);
+            }
+        }
+    }
+    // `--godai-preset overlord` picks the GODAI's starting profile: `standard`, `passive` or `overlord`.
+    if let Some(value) = args.iter().position(|a| a == "--godai-preset").and_then(|i| args.get(i + 1)) {
+        match value.parse() {
+            Ok(preset) => config.godai_preset = preset,
+            Err(e) => {
+                eprintln!("Invalid --godai-preset '{}': {}", value, e);
+                std::process::exit(2);
+            }
+        }
+    }
+    // `--attack-cost strength:0.5` picks how attacks are paid for: `flat:COST`, `damage:FACTOR` or `strength:FACTOR`.
+    if let Some(value) = args.iter().position(|a| a == "--attack-cost").and_then(|i| args.get(i + 1)) {
+        match value.parse() {
+            Ok(model) => config.attack_cost_model = model,
+            Err(e) => {
+                eprintln!("Invalid --attack-cost '{}': {}", value, e);
+                std::process::exit(2);
+            }
+        }
+    }
+    // `--world 2000x1400` sizes the world; `--toroidal` wraps its edges instead of clamping.
+    if let Some(value) = args.iter().position(|a| a == "--world").and_then(|i| args.get(i + 1)) {
+        let size = value.split_once('x').and_then(|(w, h)| Some((w.parse::<f32>().ok()?, h.parse::<f32>().ok()?)));
+        match size {
+            Some((width, height)) if width > 0.0 && height > 0.0 => {
+                config.world_width = width;
+                config.world_height = height;
+            }
+            _ => {
+                eprintln!("Invalid --world '{}' (expected WIDTHxHEIGHT)", value);
+                std::process::exit(2);
+            }
+        }
+    }
+    config.world_toroidal = args.iter().any(|a| a == "--toroidal");
+    let sim_rng = config.rng_seed.map(SimRng::from_seed).unwrap_or_else(SimRng::from_entropy);
+
+    // `--headless` skips the window, rendering and egui so batch trials run as fast as the CPU allows.
+    let headless = args.iter().any(|a| a == "--headless");
+
+    build_app(config, sim_rng, headless).run();
+}
+
+/// Builds the app with every resource and system registered. `headless` leaves out the
+/// window, rendering and egui; `harness` uses it to run the simulation without `App::run`.
+fn build_app(config: SimConfig, sim_rng: SimRng, headless: bool) -> App {
+    let mut app = App::new();
+    if headless {
+        // MinimalPlugins' schedule runner loops without waiting, so cycles run back to back
+        // until simulation_end_system prints the summary and exits.
+        app.add_plugins(MinimalPlugins);
+    } else {
+        app.add_plugins(DefaultPlugins.set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "AI Simulation".into(),
+                    resolution: (1000.0, 700.0).into(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }))
+            .add_plugins(EguiPlugin);
+    }
+    app.insert_resource(simulation::Simulation::from_config(&config))
+        .insert_resource(FoodEconomy::from_seed(sim_rng.seed))
+        .insert_resource(Genealogy::new(config.genealogy_prune_dead))
+        .insert_resource(sim_rng)
+        .insert_resource(IdGenerator::new(config.uuid_ids))
+        .insert_resource(WorldBounds::from_config(&config))
+        .insert_resource(config)
+        .init_resource::<SpatialGrid>()
+        .init_resource::<TerritoryMap>()
+        .init_resource::<InvariantChecks>()
+        .init_resource::<PersistenceRequest>()
+        .init_resource::<LineageKnowledge>()
+        .init_resource::<LineageFitness>()
+        .init_resource::<PerfStats>()
+        .init_resource::<FrozenLineages>()
+        .init_resource::<SimLog>()
+        .init_resource::<VerbosityLevel>()
+        .init_resource::<MetricsRecorder>()
+        .init_resource::<Records>()
         .add_startup_system(setup)
-        .add_system(global_simulation_update_system)
-        .add_system(ai_internal_state_system)
-        .add_system(ai_replication_system)
-        .add_system(ai_death_system)
-        .add_system(ai_movement_system)
-        .add_system(update_monoculture_visual_system)
-        .add_system(update_godai_visual_system)
-        .add_system(egui_ui_system)
-        .add_system(simulation_end_system)
-        .run();
+        .add_startup_system(command_channel_setup)
+        .add_state::<SimPhase>()
+        .add_event::<combat::AttackEvent>()
+        .add_event::<simulation::ExtinctionEvent>()
+        // Run flags change here, ahead of the state transition, so they take effect the same frame.
+        .add_system(command_system.in_base_set(CoreSet::PreUpdate))
+        .add_system(verbosity_sync_system.in_base_set(CoreSet::PreUpdate))
+        .add_system(persistence_system.after(command_system).in_base_set(CoreSet::PreUpdate))
+        .add_system(sim_phase_system.after(persistence_system).in_base_set(CoreSet::PreUpdate))
+        .add_system(perf_stats_system.after(global_simulation_update_system))
+        .add_system(spatial_grid_system.before(territory_update_system).before(ai_internal_state_system))
+        .add_system(territory_update_system.before(ai_internal_state_system))
+        .add_system(ai_scan_system.after(spatial_grid_system).before(ai_internal_state_system).run_if(in_state(SimPhase::Running)))
+        .add_system(frozen_lineage_system)
+        // Systems drawing from `SimRng` run in a fixed order so a seed reproduces the run.
+        .add_system(global_simulation_update_system.run_if(in_state(SimPhase::Running)))
+        .add_system(lineage_knowledge_system.after(global_simulation_update_system))
+        .add_system(lineage_fitness_system.after(global_simulation_update_system))
+        .add_system(ai_internal_state_system.after(global_simulation_update_system).run_if(in_state(SimPhase::Running)))
+        .add_system(ai_replication_system.after(ai_internal_state_system).run_if(in_state(SimPhase::Running)))
+        .add_system(hybrid_reproduction_system.after(spatial_grid_system).after(ai_replication_system).run_if(in_state(SimPhase::Running)))
+        .add_system(ai_combat_system.after(spatial_grid_system).after(hybrid_reproduction_system).before(ai_death_system).run_if(in_state(SimPhase::Running)))
+        .add_system(ai_heal_system.after(spatial_grid_system).after(ai_combat_system).before(ai_death_system).run_if(in_state(SimPhase::Running)))
+        .add_system(ai_knowledge_exchange_system.after(spatial_grid_system).after(ai_heal_system).run_if(in_state(SimPhase::Running)))
+        .add_system(ai_energy_share_system.after(spatial_grid_system).after(ai_heal_system).before(ai_knowledge_exchange_system).run_if(in_state(SimPhase::Running)))
+        .add_system(immigration_system.after(ai_knowledge_exchange_system).run_if(in_state(SimPhase::Running)))
+        .add_system(pending_spawn_system.after(global_simulation_update_system).after(immigration_system))
+        .add_system(monoculture_merge_system.after(global_simulation_update_system))
+        .add_system(sim_log_system.after(monoculture_merge_system))
+        .add_system(godai_smite_system.after(global_simulation_update_system).before(ai_death_system))
+        .add_system(population_pressure_system.after(ai_replication_system).after(hybrid_reproduction_system).before(ai_death_system).run_if(in_state(SimPhase::Running)))
+        .add_system(ai_death_system.run_if(in_state(SimPhase::Running)))
+        .add_system(symbiote_support_system.after(spatial_grid_system).after(ai_internal_state_system).run_if(in_state(SimPhase::Running)))
+        .add_system(stored_energy_consumption_system.after(spatial_grid_system).after(ai_movement_system).run_if(in_state(SimPhase::Running)))
+        .add_system(corpse_decay_system.after(stored_energy_consumption_system))
+        .add_system(food_spawn_system.after(global_simulation_update_system))
+        .add_system(food_regen_system.after(global_simulation_update_system).before(stored_energy_consumption_system))
+        .add_system(invariant_check_system.after(ai_internal_state_system).after(ai_replication_system))
+        .add_system(ai_movement_system.after(spatial_grid_system).after(pending_spawn_system).run_if(in_state(SimPhase::Running)))
+        .add_system(metrics_record_system.after(global_simulation_update_system))
+        // PostUpdate, after every Update system has drawn from SimRng, so a trial restart
+        // reseeds from the same point of the stream every run.
+        .add_system(simulation_end_system.in_base_set(CoreSet::PostUpdate).before(step_end_system))
+        .add_system(step_end_system.in_base_set(CoreSet::PostUpdate));
+
+    if !headless {
+        app.add_plugin(ScreenshotPlugin)
+            .init_resource::<HoveredAI>()
+            .init_resource::<CombatSelection>()
+            .init_resource::<SelectedAI>()
+            .init_resource::<LineageTrace>()
+            .init_resource::<GodMode>()
+            .init_resource::<DensityHeatmap>()
+            .insert_resource(DashboardLayout::load(Path::new(dashboard::DASHBOARD_LAYOUT_FILE)))
+            .add_system(lineage_trace_system.after(spatial_grid_system).after(global_simulation_update_system))
+            .add_system(density_heatmap_ui_system.after(spatial_grid_system).after(egui_ui_system))
+            .add_system(territory_visual_system.after(territory_update_system).after(lineage_trace_system))
+            .add_system(palette_recolor_system)
+            .add_system(ai_color_system)
+            .add_system(update_monoculture_visual_system)
+            .add_system(update_godai_visual_system)
+            .add_system(egui_ui_system.after(sim_log_system))
+            .add_system(screenshot_system.after(egui_ui_system).after(camera_control_system))
+            .add_system(screenshot_save_system.after(screenshot_system))
+            .add_system(dashboard_persist_system.after(egui_ui_system).after(combat_forecast_ui_system))
+            .add_system(camera_control_system.after(egui_ui_system))
+            .add_system(hover_pick_system.after(spatial_grid_system))
+            .add_system(ai_tooltip_system.after(hover_pick_system).after(egui_ui_system))
+            .add_system(combat_selection_system.after(hover_pick_system).after(egui_ui_system))
+            .add_system(combat_forecast_ui_system.after(combat_selection_system))
+            .add_system(ai_selection_system.after(spatial_grid_system).after(egui_ui_system))
+            .add_system(ai_inspector_ui_system.after(ai_selection_system))
+            .add_system(selection_highlight_system.after(ai_inspector_ui_system).after(ai_movement_system))
+            .add_system(god_mode_ui_system.after(ai_selection_system).before(dashboard_persist_system))
+            .add_system(god_mode_spawn_system.after(egui_ui_system).after(god_mode_ui_system))
+            .add_system(damage_number_spawn_system.after(ai_combat_system))
+            .add_system(lifetime_system)
+            .add_system(damage_number_ui_system.after(damage_number_spawn_system).after(egui_ui_system))
+            .add_system(extinction_notice_ui_system.after(global_simulation_update_system).after(egui_ui_system));
+    }
+
+    app
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::CommandQueue;
+
+    /// A headless app holding the resources the logic systems read, with no systems added.
+    fn test_app(config: SimConfig) -> App {
+        let mut app = App::new();
+        app.insert_resource(simulation::Simulation::from_config(&config))
+            .insert_resource(SimRng::from_seed(1))
+            .insert_resource(IdGenerator::new(false))
+            .insert_resource(Genealogy::new(config.genealogy_prune_dead))
+            .insert_resource(WorldBounds::from_config(&config))
+            .insert_resource(config)
+            .init_resource::<SpatialGrid>()
+            .init_resource::<TerritoryMap>()
+            .init_resource::<SimLog>();
+        app
+    }
+
+    /// Spawns a seed-profile AI of the given archetype at `position` through `spawn_ai`.
+    fn spawn_test_ai(app: &mut App, lineage: AILineage, ai_type: AIType, position: Vec2) -> Entity {
+        let id = format!("Test-{}", app.world.entities().len());
+        let components = app.world.resource::<simulation::Simulation>().build_seed_ai(id, lineage, ai_type);
+        let mut queue = CommandQueue::default();
+        let entity = spawn_ai(&mut Commands::new(&mut queue, &app.world), components, position, Palette::default());
+        queue.apply(&mut app.world);
+        entity
+    }
+
+    #[test]
+    fn runaway_replication_is_throttled_at_the_danger_threshold() {
+        let config = SimConfig {
+            max_population: 200,
+            population_danger_fraction: 0.5,
+            replication_attempts_per_frame: 20,
+            replication_cooldown_cycles: 0,
+            replication_min_health: 1.0,
+            replication_min_energy: 1.0,
+            ..Default::default()
+        };
+        let threshold = simulation::population_danger_threshold(&config);
+        assert_eq!(threshold, 100);
+        let mut app = test_app(config);
+        app.add_system(ai_replication_system);
+        for i in 0..60 {
+            let entity = spawn_test_ai(&mut app, AILineage::AI, AIType::Base, Vec2::new(i as f32, 0.0));
+            app.world.get_mut::<Health>(entity).unwrap().0 = 1_000.0;
+            app.world.get_mut::<Energy>(entity).unwrap().0 = 100_000.0;
+            app.world.get_mut::<ReplicationEfficiency>(entity).unwrap().0 = 0.9;
+        }
+        let mut population_query = app.world.query_filtered::<(), With<IndividualAI>>();
+        for _ in 0..20 {
+            app.world.resource_mut::<simulation::Simulation>().current_cycle += 1;
+            app.update();
+            let population = population_query.iter(&app.world).count();
+            assert!(population <= threshold, "population {} overshot the threshold {}", population, threshold);
+        }
+        assert_eq!(population_query.iter(&app.world).count(), threshold);
+        // One warning for the whole throttled stretch, not one per frame.
+        let warnings = app.world.resource::<SimLog>().entries.iter()
+            .filter(|entry| entry.message.contains("reached the danger threshold"))
+            .count();
+        assert_eq!(warnings, 1);
+    }
+
+    /// Meta-abilities ten Researchers, `spacing` apart in a row, find over `cycles` cycles.
+    fn meta_abilities_found(spacing: f32, cycles: u64) -> usize {
+        let config = SimConfig { research_sharing_enabled: true, ..Default::default() };
+        let mut app = test_app(config);
+        app.add_system(spatial_grid_system)
+            .add_system(ai_internal_state_system.after(spatial_grid_system));
+        let researchers: Vec<Entity> = (0..10)
+            .map(|i| spawn_test_ai(&mut app, AILineage::ResearcherAI, AIType::Researcher, Vec2::new(i as f32 * spacing, 0.0)))
+            .collect();
+        for _ in 0..cycles {
+            app.world.resource_mut::<simulation::Simulation>().current_cycle += 1;
+            app.update();
+        }
+        researchers.iter()
+            .map(|entity| app.world.get::<KnowledgeBase>(*entity).unwrap().0.iter()
+                .filter(|discovery| discovery.tags.contains("meta-ability"))
+                .count())
+            .sum()
+    }
+
+    #[test]
+    fn clustered_researchers_find_meta_abilities_faster() {
+        let clustered = meta_abilities_found(2.0, 100);
+        let scattered = meta_abilities_found(500.0, 100);
+        assert!(clustered > scattered * 2, "clustered found {}, scattered {}", clustered, scattered);
+    }
+
+    /// Mean distance `count` Base AIs packed into one grid cell wander from their start over `frames` frames.
+    fn mean_wander(count: usize, frames: usize) -> f32 {
+        let config = SimConfig { overcrowding_enabled: true, steering_enabled: false, ..Default::default() };
+        let mut app = test_app(config);
+        app.add_system(spatial_grid_system)
+            .add_system(ai_movement_system.after(spatial_grid_system));
+        let starts: Vec<(Entity, Vec2)> = (0..count)
+            .map(|i| {
+                let start = Vec2::new(10.0 + (i % 6) as f32 * 5.0, 10.0 + (i / 6 % 6) as f32 * 5.0);
+                (spawn_test_ai(&mut app, AILineage::AI, AIType::Base, start), start)
+            })
+            .collect();
+        for _ in 0..frames {
+            app.update();
+        }
+        starts.iter()
+            .map(|(entity, start)| app.world.get::<Transform>(*entity).unwrap().translation.truncate().distance(*start))
+            .sum::<f32>() / count as f32
+    }
+
+    #[test]
+    fn overcrowded_cells_disperse_faster_than_sparse_ones() {
+        let threshold = SimConfig::default().overcrowding_threshold;
+        let packed = mean_wander(threshold * 3, 10);
+        let sparse = mean_wander(threshold / 5, 10);
+        assert!(packed > sparse * 2.0, "packed AIs wandered {:.1}, sparse ones {:.1}", packed, sparse);
+    }
+
+    /// Energy left in the world, in living AIs and stored sources, after five AIs holding 500 energy
+    /// each die beside five hungry survivors and the survivors get `frames` frames to feed.
+    fn world_energy_after_die_off(scavenging_enabled: bool, frames: usize) -> (f32, f32) {
+        let config = SimConfig { scavenging_enabled, ..Default::default() };
+        let mut app = test_app(config);
+        app.add_system(spatial_grid_system)
+            .add_system(ai_death_system.after(spatial_grid_system))
+            .add_system(stored_energy_consumption_system.after(spatial_grid_system).after(ai_death_system));
+        for i in 0..5 {
+            let pos = Vec2::new(i as f32 * 100.0, 0.0);
+            let dead = spawn_test_ai(&mut app, AILineage::AI, AIType::Base, pos);
+            app.world.get_mut::<Energy>(dead).unwrap().0 = 500.0;
+            app.world.get_mut::<Health>(dead).unwrap().0 = 0.0;
+            app.world.get_mut::<IsAlive>(dead).unwrap().0 = false;
+            let survivor = spawn_test_ai(&mut app, AILineage::AI, AIType::Base, pos + Vec2::new(3.0, 0.0));
+            app.world.get_mut::<Energy>(survivor).unwrap().0 = 10.0;
+        }
+        let mut energy_query = app.world.query::<(&Energy, &IsAlive)>();
+        let mut stored_query = app.world.query::<&StoredEnergy>();
+        let mut world_energy = |app: &mut App| {
+            let living: f32 = energy_query.iter(&app.world).filter(|(_, alive)| alive.0).map(|(energy, _)| energy.0).sum();
+            let dead: f32 = energy_query.iter(&app.world).filter(|(_, alive)| !alive.0).map(|(energy, _)| energy.0).sum();
+            let stored: f32 = stored_query.iter(&app.world).map(|stored| stored.0).sum();
+            (living + dead + stored, living)
+        };
+        let (before, _) = world_energy(&mut app);
+        for _ in 0..frames {
+            app.update();
+        }
+        let (after, living) = world_energy(&mut app);
+        (after / before, living)
+    }
+
+    #[test]
+    fn scavenging_keeps_more_of_the_dead_energy_in_the_world() {
+        let (kept_without, fed_without) = world_energy_after_die_off(false, 5);
+        let (kept_with, fed_with) = world_energy_after_die_off(true, 5);
+        assert!(kept_with > kept_without, "scavenging kept {:.2} of the energy, none kept {:.2}", kept_with, kept_without);
+        // Survivors actually ate the corpses rather than the energy just sitting in them.
+        assert!(fed_with > fed_without + 100.0, "survivors hold {} with scavenging, {} without", fed_with, fed_without);
+    }
+
+    /// Position and stored energy of every food source, in spawn order, after each of `cycles` cycles.
+    fn food_history(seed: u64, cycles: u64) -> Vec<Vec<(Vec2, f32)>> {
+        let config = SimConfig { food_enabled: true, food_spawn_interval: 10, ..Default::default() };
+        let mut app = test_app(config);
+        app.insert_resource(FoodEconomy::from_seed(seed))
+            .add_system(food_spawn_system)
+            .add_system(food_regen_system.after(food_spawn_system));
+        let mut food_query = app.world.query::<(Entity, &Transform, &StoredEnergy, &FoodSource)>();
+        (0..cycles).map(|_| {
+            app.world.resource_mut::<simulation::Simulation>().current_cycle += 1;
+            app.update();
+            let mut sources: Vec<(Entity, Vec2, f32)> = food_query.iter(&app.world)
+                .map(|(entity, transform, stored, _)| (entity, transform.translation.truncate(), stored.0))
+                .collect();
+            sources.sort_by_key(|(entity, ..)| *entity);
+            sources.into_iter().map(|(_, pos, stored)| (pos, stored)).collect()
+        }).collect()
+    }
+
+    #[test]
+    fn food_placement_and_regen_reproduce_from_the_seed() {
+        let first = food_history(21, 60);
+        assert_eq!(first, food_history(21, 60));
+        assert!(first.last().is_some_and(|sources| sources.len() > SimConfig::default().food_spawn_count));
+        assert_ne!(first, food_history(22, 60));
+    }
+
+    #[test]
+    fn fracture_spawns_land_inside_a_small_world() {
+        let config = SimConfig { world_width: 40.0, world_height: 30.0, world_toroidal: false, ..Default::default() };
+        let mut app = test_app(config);
+        app.add_system(pending_spawn_system);
+        let fragments: Vec<ai::AIComponents> = (0..50)
+            .map(|i| app.world.resource::<simulation::Simulation>().build_seed_ai(format!("Fragment-{}", i), AILineage::KillerAI, AIType::Killer))
+            .collect();
+        app.world.resource_mut::<simulation::Simulation>().pending_spawns = fragments;
+        app.update();
+        let half = app.world.resource::<WorldBounds>().half_extents();
+        let mut position_query = app.world.query_filtered::<&Transform, With<IndividualAI>>();
+        let positions: Vec<Vec2> = position_query.iter(&app.world).map(|transform| transform.translation.truncate()).collect();
+        assert_eq!(positions.len(), 50);
+        for position in positions {
+            assert!(position.x.abs() <= half.x && position.y.abs() <= half.y, "{:?} is outside the world", position);
+        }
+    }
+
+    /// Health, energy, processing power, memory, knowledge and position, for spotting any change.
+    fn stat_snapshot(app: &App, entity: Entity) -> (f32, f32, f32, f32, usize, Vec2) {
+        let ai = app.world.entity(entity);
+        (
+            ai.get::<Health>().unwrap().0,
+            ai.get::<Energy>().unwrap().0,
+            ai.get::<ProcessingPower>().unwrap().0,
+            ai.get::<Memory>().unwrap().0,
+            ai.get::<KnowledgeBase>().unwrap().0.len(),
+            ai.get::<Transform>().unwrap().translation.truncate(),
+        )
+    }
+
+    #[test]
+    fn frozen_lineage_stats_stay_unchanged() {
+        let mut app = test_app(SimConfig::default());
+        app.insert_resource(FrozenLineages(HashSet::from([AILineage::KillerAI])))
+            .add_system(frozen_lineage_system)
+            .add_system(spatial_grid_system)
+            .add_system(ai_internal_state_system.after(spatial_grid_system))
+            .add_system(ai_replication_system.after(ai_internal_state_system))
+            .add_system(ai_movement_system.after(spatial_grid_system));
+        let frozen: Vec<Entity> = (0..5)
+            .map(|i| spawn_test_ai(&mut app, AILineage::KillerAI, AIType::Killer, Vec2::new(i as f32 * 300.0, 0.0)))
+            .collect();
+        let thawed: Vec<Entity> = (0..5)
+            .map(|i| spawn_test_ai(&mut app, AILineage::AI, AIType::Base, Vec2::new(i as f32 * 300.0, 300.0)))
+            .collect();
+        // `Frozen` markers are inserted by commands, so they only hold from the second frame.
+        app.update();
+        let before: Vec<_> = frozen.iter().chain(&thawed).map(|entity| stat_snapshot(&app, *entity)).collect();
+        for _ in 0..100 {
+            app.world.resource_mut::<simulation::Simulation>().current_cycle += 1;
+            app.update();
+        }
+        for (entity, before) in frozen.iter().zip(&before) {
+            assert_eq!(stat_snapshot(&app, *entity), *before);
+        }
+        for (entity, before) in thawed.iter().zip(&before[frozen.len()..]) {
+            assert_ne!(stat_snapshot(&app, *entity), *before);
+        }
+        let mut lineage_query = app.world.query::<&AILineage>();
+        assert_eq!(lineage_query.iter(&app.world).filter(|lineage| **lineage == AILineage::KillerAI).count(), frozen.len());
+    }
+
+    /// Attacks landed over `frames` frames by one seed AI of the given archetype on a Healer
+    /// beside it, with energy, strength and the target identical across archetypes.
+    fn attacks_by(lineage: AILineage, ai_type: AIType, frames: usize) -> (String, u64) {
+        let mut app = test_app(SimConfig::default());
+        app.add_event::<combat::AttackEvent>()
+            .add_system(spatial_grid_system)
+            .add_system(ai_combat_system.after(spatial_grid_system));
+        let attacker = spawn_test_ai(&mut app, lineage, ai_type, Vec2::ZERO);
+        app.world.get_mut::<Energy>(attacker).unwrap().0 = 1_000_000.0;
+        app.world.get_mut::<CombatStrength>(attacker).unwrap().0 = 10.0;
+        let target = spawn_test_ai(&mut app, AILineage::HealerAI, AIType::Healer, Vec2::new(1.0, 0.0));
+        assert_eq!(app.world.get::<Goal>(target).unwrap().aggression(), 0.0);
+        app.world.get_mut::<Health>(target).unwrap().0 = 1e9;
+        for _ in 0..frames {
+            app.update();
+        }
+        let goal = app.world.get::<Goal>(attacker).unwrap().name.clone();
+        (goal, app.world.resource::<simulation::Simulation>().interval_attacks_counter.load(Ordering::SeqCst))
+    }
+
+    #[test]
+    fn dominance_driven_rogues_attack_more_often_than_survival_driven_bases() {
+        let (rogue_goal, rogue_attacks) = attacks_by(AILineage::RogueAI, AIType::Rogue, 200);
+        let (base_goal, base_attacks) = attacks_by(AILineage::AI, AIType::Base, 200);
+        assert_eq!(rogue_goal, "Self-Preservation & Dominance");
+        assert_eq!(base_goal, "Survival");
+        assert!(rogue_attacks > base_attacks * 5, "Rogue attacked {} times, Base {}", rogue_attacks, base_attacks);
+    }
+
+    #[test]
+    fn dominant_lineage_merges_through_detection_and_the_merge_system() {
+        let config = SimConfig { monoculture_min_count: 200, monoculture_dominance_threshold: 0.9, ..Default::default() };
+        let mut app = test_app(config);
+        app.init_resource::<Records>()
+            .add_event::<simulation::ExtinctionEvent>()
+            .add_system(global_simulation_update_system)
+            .add_system(monoculture_merge_system.after(global_simulation_update_system));
+        for i in 0..300 {
+            spawn_test_ai(&mut app, AILineage::KillerAI, AIType::Killer, Vec2::new((i % 20) as f32 * 10.0, (i / 20) as f32 * 10.0));
+        }
+        for i in 0..10 {
+            spawn_test_ai(&mut app, AILineage::AI, AIType::Base, Vec2::new(i as f32 * 10.0, -100.0));
+        }
+        let mut lineage_query = app.world.query::<(&AILineage, &CombatStrength)>();
+        let killer_combat: f32 = lineage_query.iter(&app.world)
+            .filter(|(lineage, _)| **lineage == AILineage::KillerAI)
+            .map(|(_, combat)| combat.0)
+            .sum();
+        app.update();
+        let sim = app.world.resource::<simulation::Simulation>();
+        assert!(sim.pending_monoculture.is_none());
+        let mono = sim.monoculture.as_ref().expect("the dominant lineage should have merged");
+        assert_eq!(mono.source_lineage, AILineage::KillerAI);
+        assert!((mono.combat_strength.0 - killer_combat).abs() < 1e-3, "{} vs {}", mono.combat_strength.0, killer_combat);
+        let remaining: Vec<AILineage> = lineage_query.iter(&app.world).map(|(lineage, _)| lineage.clone()).collect();
+        assert_eq!(remaining.len(), 10);
+        assert!(remaining.iter().all(|lineage| *lineage == AILineage::AI));
+    }
+
+    #[test]
+    fn starved_population_ends_in_extinction_under_a_living_godai() {
+        let config = SimConfig { energy_regen_per_cycle: 0.0, ..Default::default() };
+        assert!(!config.seed.immigration_enabled());
+        let mut app = test_app(config);
+        app.init_resource::<Records>()
+            .add_event::<simulation::ExtinctionEvent>()
+            .add_system(global_simulation_update_system)
+            .add_system(spatial_grid_system)
+            .add_system(ai_internal_state_system.after(global_simulation_update_system).after(spatial_grid_system))
+            .add_system(ai_death_system.after(ai_internal_state_system));
+        for i in 0..3 {
+            let ai = spawn_test_ai(&mut app, AILineage::AI, AIType::Base, Vec2::new(i as f32 * 300.0, 0.0));
+            app.world.get_mut::<Energy>(ai).unwrap().0 = 0.0;
+            app.world.get_mut::<Health>(ai).unwrap().0 = 0.05;
+            // Without directives, nothing tops up energy when it runs low.
+            app.world.get_mut::<EthicalDirectives>(ai).unwrap().0.clear();
+        }
+        let mut frames = 0;
+        while app.world.resource::<simulation::Simulation>().simulation_over_reason.is_none() {
+            assert!(frames < 100, "the starving population never died out");
+            app.update();
+            frames += 1;
+        }
+        let mut population_query = app.world.query_filtered::<(), With<IndividualAI>>();
+        assert_eq!(population_query.iter(&app.world).count(), 0);
+        let sim = app.world.resource::<simulation::Simulation>();
+        assert!(sim.godai.is_alive.0);
+        assert_eq!(sim.simulation_over_reason.as_deref(), Some("Individual AI Extinction (GODAI remains dominant)"));
+    }
+
+    #[test]
+    fn a_refusing_mate_costs_the_parent_nothing() {
+        let config = SimConfig {
+            sexual_lineage: Some(AILineage::AI),
+            replication_attempts_per_frame: 20,
+            replication_cooldown_cycles: 0,
+            ..Default::default()
+        };
+        let mut app = test_app(config);
+        app.add_system(spatial_grid_system)
+            .add_system(ai_replication_system.after(spatial_grid_system));
+        // The weak mate can afford only a few replicas before falling below the replication bar.
+        let [strong, weak] = [(0.0, 1_000.0), (5.0, 60.0)].map(|(x, health)| {
+            let ai = spawn_test_ai(&mut app, AILineage::AI, AIType::Base, Vec2::new(x, 0.0));
+            app.world.get_mut::<Health>(ai).unwrap().0 = health;
+            app.world.get_mut::<Energy>(ai).unwrap().0 = 100_000.0;
+            app.world.get_mut::<ReplicationEfficiency>(ai).unwrap().0 = 0.9;
+            ai
+        });
+        app.update();
+        let replicated = |ai: Entity| app.world.get::<ReplicatedCount>(ai).unwrap().0;
+        let (strong_count, weak_count) = (replicated(strong), replicated(weak));
+        let mut population_query = app.world.query_filtered::<(), With<IndividualAI>>();
+        let spawned = population_query.iter(&app.world).count() - 2;
+        assert_eq!(spawned as u32, strong_count + weak_count);
+        assert!(strong_count > 0 && strong_count < 20, "the strong parent replicated {} times", strong_count);
+        // Each AI paid one share per replica it parented and one per replica it sired as a mate.
+        let shares = spawned as i32;
+        for (ai, start) in [(strong, 1_000.0), (weak, 60.0)] {
+            let expected = start * (1.0 - ai::REPLICATION_HEALTH_SHARE).powi(shares);
+            let health = app.world.get::<Health>(ai).unwrap().0;
+            assert!((health - expected).abs() < 1e-2, "health {} should be {}", health, expected);
+        }
+    }
+
+    #[test]
+    fn a_sexual_replica_descends_from_both_parents() {
+        let config = SimConfig {
+            sexual_lineage: Some(AILineage::AI),
+            replication_attempts_per_frame: 4,
+            replication_cooldown_cycles: 0,
+            ..Default::default()
+        };
+        let mut app = test_app(config);
+        app.add_system(spatial_grid_system)
+            .add_system(ai_replication_system.after(spatial_grid_system));
+        let parents = [0.0, 5.0].map(|x| {
+            let ai = spawn_test_ai(&mut app, AILineage::AI, AIType::Base, Vec2::new(x, 0.0));
+            app.world.get_mut::<Health>(ai).unwrap().0 = 1_000.0;
+            app.world.get_mut::<Energy>(ai).unwrap().0 = 100_000.0;
+            app.world.get_mut::<ReplicationEfficiency>(ai).unwrap().0 = 0.9;
+            app.world.get::<AIEntity>(ai).unwrap().id.clone()
+        });
+        app.update();
+        let genealogy = app.world.resource::<Genealogy>();
+        let replicas: Vec<&genealogy::GenealogyNode> = genealogy.nodes.iter()
+            .filter(|(id, _)| !parents.contains(id))
+            .map(|(_, node)| node)
+            .collect();
+        assert!(!replicas.is_empty());
+        for node in &replicas {
+            let mut recorded = [node.parent.clone().unwrap(), node.co_parent.clone().expect("the mate should be recorded")];
+            recorded.sort();
+            let mut expected = parents.clone();
+            expected.sort();
+            assert_eq!(recorded, expected);
+        }
+        for parent in &parents {
+            assert_eq!(genealogy.descendant_count(parent), replicas.len());
+        }
+    }
+
+    #[test]
+    fn energy_sharing_conserves_energy_apart_from_the_transfer_loss() {
+        let config = SimConfig { energy_sharing_enabled: true, energy_share_loss: 0.25, ..Default::default() };
+        let mut app = test_app(config);
+        app.add_system(spatial_grid_system)
+            .add_system(ai_energy_share_system.after(spatial_grid_system));
+        let mut donors = Vec::new();
+        let mut recipients = Vec::new();
+        for i in 0..4 {
+            let x = i as f32 * 200.0;
+            donors.push(spawn_test_ai(&mut app, AILineage::GuardianAI, AIType::Guardian, Vec2::new(x, 0.0)));
+            recipients.push(spawn_test_ai(&mut app, AILineage::GuardianAI, AIType::Guardian, Vec2::new(x + 5.0, 0.0)));
+        }
+        for &donor in &donors {
+            app.world.get_mut::<Energy>(donor).unwrap().0 = 1_000.0;
+        }
+        for &recipient in &recipients {
+            app.world.get_mut::<Energy>(recipient).unwrap().0 = 10.0;
+        }
+        let total = |app: &App, entities: &[Entity]| -> f32 {
+            entities.iter().map(|entity| app.world.get::<Energy>(*entity).unwrap().0).sum()
+        };
+        let (donors_before, recipients_before) = (total(&app, &donors), total(&app, &recipients));
+        for _ in 0..3 {
+            app.update();
+        }
+        let donated = donors_before - total(&app, &donors);
+        let received = total(&app, &recipients) - recipients_before;
+        assert!(donated > 0.0);
+        assert!((received - donated * 0.75).abs() < 1e-3, "donated {} but {} arrived", donated, received);
+    }
 }
\ No newline at end of file
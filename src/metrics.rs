@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::path::Path;
+use bevy::prelude::Resource; // Import Resource from Bevy
+
+use crate::ai::AILineage;
+use crate::simulation::{seed_ai_archetypes, IntervalStats};
+
+/// File the metrics are flushed to when the simulation ends, in the working directory.
+pub const METRICS_CSV_FILE: &str = "metrics.csv";
+
+/// One sampled row of run metrics.
+pub struct MetricsRow {
+    pub trial: usize, // 1-based; see `MetricsRecorder::start_trial`
+    pub cycle: u64,
+    pub total_population: usize,
+    pub lineage_counts: Vec<usize>, // In `MetricsRecorder::lineages` order
+    pub godai_health: f32,
+    pub monoculture_health: Option<f32>,
+    pub interval: IntervalStats,
+}
+
+/// Per-interval metrics for spreadsheet analysis, sampled every `LOG_INTERVAL` cycles.
+/// Rows accumulate across `--trials` restarts, tagged with the trial they were sampled in.
+#[derive(Resource)]
+pub struct MetricsRecorder {
+    pub lineages: Vec<AILineage>, // Fixed column order, so extinct lineages keep their column
+    pub rows: Vec<MetricsRow>,
+    pub last_record_cycle: Option<u64>,
+    pub trial: usize,
+}
+
+impl Default for MetricsRecorder {
+    fn default() -> Self {
+        Self {
+            lineages: seed_ai_archetypes().into_iter().map(|(lineage, _)| lineage).collect(),
+            rows: Vec::new(),
+            last_record_cycle: None,
+            trial: 1,
+        }
+    }
+}
+
+impl MetricsRecorder {
+    /// Starts sampling a restarted trial, whose cycles count up from zero again.
+    pub fn start_trial(&mut self, trial: usize) {
+        self.trial = trial;
+        self.last_record_cycle = None;
+    }
+
+    pub fn record(
+        &mut self,
+        cycle: u64,
+        lineage_counts: &HashMap<AILineage, usize>,
+        godai_health: f32,
+        monoculture_health: Option<f32>,
+        interval: IntervalStats,
+    ) {
+        self.rows.push(MetricsRow {
+            trial: self.trial,
+            cycle,
+            total_population: lineage_counts.values().sum(),
+            lineage_counts: self.lineages.iter().map(|lineage| lineage_counts.get(lineage).copied().unwrap_or(0)).collect(),
+            godai_health,
+            monoculture_health,
+            interval,
+        });
+        self.last_record_cycle = Some(cycle);
+    }
+
+    /// Writes every recorded row to `path`, replacing the file. An absent monoculture leaves its cell empty.
+    pub fn flush_csv(&self, path: &Path) -> Result<(), String> {
+        let mut writer = csv::Writer::from_path(path).map_err(|e| format!("could not create {}: {}", path.display(), e))?;
+        let mut header = vec!["trial".to_string(), "cycle".to_string(), "total_population".to_string()];
+        header.extend(self.lineages.iter().map(|lineage| lineage.to_string()));
+        header.extend(["godai_health", "monoculture_health", "replications", "deaths", "attacks", "heals"].map(String::from));
+        writer.write_record(&header).map_err(|e| format!("could not write {}: {}", path.display(), e))?;
+        for row in &self.rows {
+            let mut record = vec![row.trial.to_string(), row.cycle.to_string(), row.total_population.to_string()];
+            record.extend(row.lineage_counts.iter().map(usize::to_string));
+            record.push(format!("{:.2}", row.godai_health));
+            record.push(row.monoculture_health.map(|health| format!("{:.2}", health)).unwrap_or_default());
+            record.extend([row.interval.replications, row.interval.deaths, row.interval.attacks, row.interval.heals].map(|n| n.to_string()));
+            writer.write_record(&record).map_err(|e| format!("could not write {}: {}", path.display(), e))?;
+        }
+        writer.flush().map_err(|e| format!("could not write {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restarted_trial_keeps_earlier_rows_and_samples_from_zero() {
+        let mut recorder = MetricsRecorder::default();
+        let counts = HashMap::from([(AILineage::KillerAI, 4)]);
+        recorder.record(500, &counts, 1.0, None, IntervalStats::default());
+        recorder.start_trial(2);
+        assert_eq!(recorder.last_record_cycle, None);
+        recorder.record(0, &counts, 1.0, None, IntervalStats::default());
+        let tagged: Vec<(usize, u64)> = recorder.rows.iter().map(|row| (row.trial, row.cycle)).collect();
+        assert_eq!(tagged, vec![(1, 500), (2, 0)]);
+    }
+}
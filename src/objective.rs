@@ -0,0 +1,130 @@
+use crate::simulation::SimulationOutcomeReport;
+
+/// What a parameter sweep is trying to optimize. Higher scores are always better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// Shannon entropy (nats) of the final lineage distribution; 0 for a single lineage or none.
+    MaximizeDiversity,
+    /// Number of individual AIs alive at the end.
+    MaximizeFinalPopulation,
+    /// 0 without an override; otherwise `1 + 1 / (1 + final_cycle)`, so earlier overrides rank higher.
+    AchieveResearcherOverride,
+    /// Number of cycles the run lasted.
+    MaximizeRunLength,
+    /// 0 if the GODAI fell; otherwise `1 + remaining health fraction`.
+    GodaiSurvival,
+}
+
+/// Scores a finished run under the chosen objective.
+pub fn score_run(report: &SimulationOutcomeReport, objective: Objective) -> f64 {
+    match objective {
+        Objective::MaximizeDiversity => {
+            let total: usize = report.lineage_counts.values().sum();
+            if total == 0 {
+                return 0.0;
+            }
+            report.lineage_counts.values()
+                .filter(|&&count| count > 0)
+                .map(|&count| {
+                    let p = count as f64 / total as f64;
+                    -p * p.ln()
+                })
+                .sum()
+        }
+        Objective::MaximizeFinalPopulation => report.final_population as f64,
+        Objective::AchieveResearcherOverride => {
+            if report.researcher_override() {
+                1.0 + 1.0 / (1.0 + report.final_cycle as f64)
+            } else {
+                0.0
+            }
+        }
+        Objective::MaximizeRunLength => report.final_cycle as f64,
+        Objective::GodaiSurvival => {
+            if report.godai_alive && report.godai_max_health > 0.0 {
+                1.0 + (report.godai_health / report.godai_max_health).clamp(0.0, 1.0) as f64
+            } else {
+                0.0
+            }
+        }
+    }
+}
+
+/// Orders reports best-first under `objective`, returning (original index, score) pairs.
+pub fn rank_runs(reports: &[SimulationOutcomeReport], objective: Objective) -> Vec<(usize, f64)> {
+    let mut ranked: Vec<(usize, f64)> = reports.iter()
+        .enumerate()
+        .map(|(i, report)| (i, score_run(report, objective)))
+        .collect();
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::ai::AILineage;
+    use crate::simulation::GodaiStatus;
+
+    fn report() -> SimulationOutcomeReport {
+        SimulationOutcomeReport {
+            final_cycle: 500,
+            conclusion: None,
+            final_population: 10,
+            lineage_counts: HashMap::from([(AILineage::AI, 10)]),
+            godai_alive: true,
+            godai_health: 5_000.0,
+            godai_max_health: 10_000.0,
+            godai_status: GodaiStatus::ObservingPassively,
+            monoculture_lineage: None,
+            reached_max_cycles: false,
+        }
+    }
+
+    fn assert_better(better: &SimulationOutcomeReport, worse: &SimulationOutcomeReport, objective: Objective) {
+        let (b, w) = (score_run(better, objective), score_run(worse, objective));
+        assert!(b > w, "{:?}: {} should beat {}", objective, b, w);
+        assert_eq!(rank_runs(&[worse.clone(), better.clone()], objective)[0].0, 1);
+    }
+
+    #[test]
+    fn more_even_lineages_score_higher_diversity() {
+        let even = SimulationOutcomeReport {
+            lineage_counts: HashMap::from([(AILineage::AI, 5), (AILineage::RogueAI, 5)]),
+            ..report()
+        };
+        let skewed = SimulationOutcomeReport {
+            lineage_counts: HashMap::from([(AILineage::AI, 9), (AILineage::RogueAI, 1)]),
+            ..report()
+        };
+        assert_better(&even, &skewed, Objective::MaximizeDiversity);
+        assert_better(&skewed, &report(), Objective::MaximizeDiversity);
+    }
+
+    #[test]
+    fn larger_population_scores_higher() {
+        assert_better(&SimulationOutcomeReport { final_population: 11, ..report() }, &report(), Objective::MaximizeFinalPopulation);
+    }
+
+    #[test]
+    fn earlier_override_scores_higher() {
+        let early = SimulationOutcomeReport { godai_status: GodaiStatus::Overridden, final_cycle: 100, ..report() };
+        let late = SimulationOutcomeReport { godai_status: GodaiStatus::Overridden, final_cycle: 900, ..report() };
+        assert_better(&early, &late, Objective::AchieveResearcherOverride);
+        assert_better(&late, &report(), Objective::AchieveResearcherOverride);
+    }
+
+    #[test]
+    fn longer_run_scores_higher() {
+        assert_better(&SimulationOutcomeReport { final_cycle: 501, ..report() }, &report(), Objective::MaximizeRunLength);
+    }
+
+    #[test]
+    fn healthier_surviving_godai_scores_higher() {
+        let healthier = SimulationOutcomeReport { godai_health: 6_000.0, ..report() };
+        let fallen = SimulationOutcomeReport { godai_alive: false, godai_health: 0.0, ..report() };
+        assert_better(&healthier, &report(), Objective::GodaiSurvival);
+        assert_better(&report(), &fallen, Objective::GodaiSurvival);
+    }
+}
@@ -0,0 +1,91 @@
+use bevy::prelude::Color;
+use bevy_egui::egui::Color32;
+
+use crate::ai::{AILineage, AIType};
+
+/// Color scheme for AI sprites, territory tints and the GODAI/monoculture visuals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Palette {
+    /// The original colors.
+    #[default]
+    Classic,
+    /// Okabe-Ito colors, distinguishable under the common forms of color blindness.
+    ColorblindSafe,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 2] = [Palette::Classic, Palette::ColorblindSafe];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Palette::Classic => "Classic",
+            Palette::ColorblindSafe => "Colorblind-safe",
+        }
+    }
+}
+
+/// Sprite color for an AI type. Every per-type color in the simulation comes from here.
+pub fn color_for_type(ai_type: AIType, palette: Palette) -> Color {
+    match palette {
+        Palette::Classic => match ai_type {
+            AIType::Rogue => Color::rgb_u8(255, 0, 0),
+            AIType::Peacekeeper => Color::rgb_u8(0, 0, 255),
+            AIType::Killer => Color::rgb_u8(128, 0, 128),
+            AIType::Guardian => Color::rgb_u8(0, 128, 0),
+            AIType::Manic => Color::rgb_u8(255, 255, 0),
+            AIType::Healer => Color::rgb_u8(50, 205, 50),
+            AIType::Researcher => Color::rgb_u8(255, 165, 0),
+            AIType::Symbiote => Color::rgb_u8(0, 206, 209),
+            AIType::Base => Color::rgb_u8(128, 128, 128),
+        },
+        Palette::ColorblindSafe => match ai_type {
+            AIType::Rogue => Color::rgb_u8(213, 94, 0),
+            AIType::Peacekeeper => Color::rgb_u8(0, 114, 178),
+            AIType::Killer => Color::rgb_u8(204, 121, 167),
+            AIType::Guardian => Color::rgb_u8(0, 158, 115),
+            AIType::Manic => Color::rgb_u8(240, 228, 66),
+            AIType::Healer => Color::rgb_u8(86, 180, 233),
+            AIType::Researcher => Color::rgb_u8(230, 159, 0),
+            AIType::Symbiote => Color::rgb_u8(240, 240, 240),
+            AIType::Base => Color::rgb_u8(160, 160, 160),
+        },
+    }
+}
+
+/// Color for a lineage: its seed archetype's color, or neutral gray for non-individual lineages.
+pub fn lineage_color(lineage: &AILineage, palette: Palette) -> Color {
+    match lineage.seed_type() {
+        Some(ai_type) => color_for_type(ai_type, palette),
+        None => Color::rgb_u8(128, 128, 128),
+    }
+}
+
+/// Degrees of hue each lineage is shifted by in `health_color`, so types stay distinguishable.
+const LINEAGE_HUE_STEP: f32 = 4.0;
+
+/// Sprite color on a red (no health) to green (full health) gradient, with a small per-lineage hue offset.
+pub fn health_color(health_fraction: f32, lineage: &AILineage) -> Color {
+    let lineage_index = lineage.seed_type().map_or(0.0, |ai_type| ai_type as u8 as f32);
+    let hue = health_fraction.clamp(0.0, 1.0) * 120.0 + (lineage_index - 3.5) * LINEAGE_HUE_STEP;
+    Color::hsl(hue.rem_euclid(360.0), 0.9, 0.5)
+}
+
+/// Converts a sprite color for use in egui widgets such as plot lines.
+pub fn to_egui_color(color: Color) -> Color32 {
+    let [r, g, b, _] = color.as_rgba_f32();
+    Color32::from_rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
+}
+
+pub fn godai_color(palette: Palette) -> Color {
+    match palette {
+        Palette::Classic => Color::rgb_u8(75, 0, 130),
+        Palette::ColorblindSafe => Color::rgb_u8(0, 0, 0),
+    }
+}
+
+pub fn monoculture_color(palette: Palette) -> Color {
+    match palette {
+        Palette::Classic => Color::rgb_u8(255, 0, 255),
+        Palette::ColorblindSafe => Color::rgb_u8(255, 255, 255),
+    }
+}
@@ -0,0 +1,103 @@
+use crate::common::{Energy, PeacekeeperState, ProcessingPower};
+use crate::config::SimConfig;
+
+/// Conflicts an AI can break up within one cycle: one, plus one per
+/// `intervention_processing_per_conflict` of processing power.
+pub fn conflict_capacity(processing_power: &ProcessingPower, config: &SimConfig) -> u32 {
+    if config.intervention_processing_per_conflict <= 0.0 {
+        return 1;
+    }
+    1 + (processing_power.0.max(0.0) / config.intervention_processing_per_conflict) as u32
+}
+
+impl PeacekeeperState {
+    /// Whether the AI can take on another conflict this cycle: either a round is open and
+    /// below capacity, or the cooldown since the last round has run out.
+    pub fn ready(&self, capacity: u32, current_cycle: u64, config: &SimConfig) -> bool {
+        match self.round_cycle {
+            Some(cycle) if cycle == current_cycle => self.monitored < capacity,
+            Some(cycle) => current_cycle >= cycle + config.intervention_cooldown_cycles,
+            None => true,
+        }
+    }
+
+    /// Breaks up one conflict if ready and able to pay `intervention_energy_cost`.
+    /// The first intervention of a cycle opens a round; the cooldown runs from it.
+    pub fn try_intervene(
+        &mut self,
+        energy: &mut Energy,
+        processing_power: &ProcessingPower,
+        current_cycle: u64,
+        config: &SimConfig,
+    ) -> bool {
+        let capacity = conflict_capacity(processing_power, config);
+        if !self.ready(capacity, current_cycle, config) || energy.0 < config.intervention_energy_cost {
+            return false;
+        }
+        if self.round_cycle != Some(current_cycle) {
+            self.round_cycle = Some(current_cycle);
+            self.monitored = 0;
+        }
+        self.monitored += 1;
+        self.interventions += 1;
+        energy.0 -= config.intervention_energy_cost;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SimConfig {
+        SimConfig {
+            intervention_energy_cost: 75.0,
+            intervention_cooldown_cycles: 15,
+            intervention_processing_per_conflict: 50.0,
+            ..SimConfig::default()
+        }
+    }
+
+    #[test]
+    fn interventions_stop_once_energy_runs_short() {
+        let config = config();
+        let mut state = PeacekeeperState::default();
+        let mut energy = Energy(200.0);
+        let processing_power = ProcessingPower(1000.0);
+        assert!(state.try_intervene(&mut energy, &processing_power, 1, &config));
+        assert!(state.try_intervene(&mut energy, &processing_power, 1, &config));
+        assert_eq!(energy.0, 50.0);
+        assert!(!state.try_intervene(&mut energy, &processing_power, 1, &config));
+        assert!(!state.try_intervene(&mut energy, &processing_power, 100, &config));
+        assert_eq!(energy.0, 50.0);
+        assert_eq!(state.interventions, 2);
+    }
+
+    #[test]
+    fn a_round_is_capped_by_processing_power() {
+        let config = config();
+        let mut state = PeacekeeperState::default();
+        let mut energy = Energy(10_000.0);
+        // 120 processing power handles the base conflict plus two more.
+        let processing_power = ProcessingPower(120.0);
+        assert_eq!(conflict_capacity(&processing_power, &config), 3);
+        let handled = (0..10).filter(|_| state.try_intervene(&mut energy, &processing_power, 5, &config)).count();
+        assert_eq!(handled, 3);
+        assert_eq!(state.monitored, 3);
+    }
+
+    #[test]
+    fn the_cooldown_runs_from_the_start_of_the_last_round() {
+        let config = config();
+        let mut state = PeacekeeperState::default();
+        let mut energy = Energy(10_000.0);
+        let processing_power = ProcessingPower(0.0);
+        assert!(state.try_intervene(&mut energy, &processing_power, 10, &config));
+        for cycle in 11..25 {
+            assert!(!state.try_intervene(&mut energy, &processing_power, cycle, &config), "intervened at cycle {}", cycle);
+        }
+        assert!(state.try_intervene(&mut energy, &processing_power, 25, &config));
+        assert_eq!(state.round_cycle, Some(25));
+        assert_eq!(state.interventions, 2);
+    }
+}
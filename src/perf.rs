@@ -0,0 +1,112 @@
+use std::time::Instant;
+use bevy::prelude::{App, IntoSystemConfig, MinimalPlugins, ResMut, Resource, With}; // Import Bevy types used here
+
+use crate::config::SimConfig;
+use crate::genealogy::Genealogy;
+use crate::rng::SimRng;
+use crate::simlog::SimLog;
+use crate::simulation::Simulation;
+use crate::spatial::{SpatialGrid, WorldBounds};
+use crate::territory::TerritoryMap;
+
+/// Real seconds per cycles-per-second window; the average restarts after each.
+pub const PERF_WINDOW_SECONDS: f64 = 3.0;
+
+/// Wall-clock throughput of the simulation, for tuning `simulation_speed`.
+#[derive(Resource, Default)]
+pub struct PerfStats {
+    pub elapsed_seconds: f64, // Real time since startup
+    pub cycles_per_second: f64, // Average over the last completed window
+    pub window_start_seconds: f64,
+    pub window_cycles: u64,
+    pub last_cycle: u64, // Cycle counter at the previous sample
+}
+
+impl PerfStats {
+    /// Samples the cycle counter at real time `now`, closing the window once it is full.
+    /// A counter that went backwards (a loaded save) counts as no progress.
+    pub fn record(&mut self, now: f64, current_cycle: u64) {
+        self.elapsed_seconds = now;
+        self.window_cycles += current_cycle.saturating_sub(self.last_cycle);
+        self.last_cycle = current_cycle;
+        let window = now - self.window_start_seconds;
+        if window >= PERF_WINDOW_SECONDS {
+            self.cycles_per_second = self.window_cycles as f64 / window;
+            self.window_start_seconds = now;
+            self.window_cycles = 0;
+        }
+    }
+}
+
+/// Starting population of a `--bench-cycles` run unless `--population` says otherwise.
+pub const DEFAULT_BENCH_POPULATION: usize = 1000;
+
+/// Timing of a `--bench-cycles` run.
+pub struct BenchReport {
+    pub cycles: u64,
+    pub start_population: usize,
+    pub end_population: usize,
+    pub seconds: f64, // Time spent in the measured frames only
+    pub entity_cycles: u64, // Living individuals summed over every measured cycle
+}
+
+impl BenchReport {
+    pub fn entities_per_second(&self) -> f64 {
+        if self.seconds > 0.0 { self.entity_cycles as f64 / self.seconds } else { 0.0 }
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "{} cycles in {:.3}s ({:.1} cycles/sec), population {} -> {}, {:.0} entities/sec",
+            self.cycles, self.seconds, self.cycles as f64 / self.seconds.max(f64::EPSILON),
+            self.start_population, self.end_population, self.entities_per_second(),
+        )
+    }
+}
+
+/// Times `cycles` cycles of the core loop (internal state, replication and death) over a
+/// seeded population of `population`, in a headless world with no other systems.
+pub fn run_cycle_benchmark(config: &SimConfig, seed: u64, population: usize, cycles: u64) -> BenchReport {
+    let mut config = config.clone();
+    config.seed.initial_ais = population;
+    config.rng_seed = Some(seed);
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(Simulation::from_config(&config))
+        .insert_resource(Genealogy::new(config.genealogy_prune_dead))
+        .insert_resource(SimRng::from_seed(seed))
+        .insert_resource(WorldBounds::from_config(&config))
+        .insert_resource(config)
+        .init_resource::<SpatialGrid>()
+        .init_resource::<TerritoryMap>()
+        .init_resource::<SimLog>()
+        .add_startup_system(crate::setup)
+        // Stands in for the global update so cooldowns and per-cycle RNG streams advance.
+        .add_system(advance_cycle_system.before(crate::ai_internal_state_system))
+        .add_system(crate::ai_internal_state_system)
+        .add_system(crate::ai_replication_system.after(crate::ai_internal_state_system))
+        .add_system(crate::ai_death_system.after(crate::ai_replication_system));
+    let mut population_query = app.world.query_filtered::<(), With<crate::IndividualAI>>();
+    // The first update runs startup plus one unmeasured warm-up cycle.
+    app.update();
+    let start_population = population_query.iter(&app.world).count();
+    let mut seconds = 0.0;
+    let mut entity_cycles = 0;
+    for _ in 0..cycles {
+        entity_cycles += population_query.iter(&app.world).count() as u64;
+        let start = Instant::now();
+        app.update();
+        seconds += start.elapsed().as_secs_f64();
+    }
+    BenchReport {
+        cycles,
+        start_population,
+        end_population: population_query.iter(&app.world).count(),
+        seconds,
+        entity_cycles,
+    }
+}
+
+fn advance_cycle_system(mut sim: ResMut<Simulation>) {
+    sim.current_cycle += 1;
+}
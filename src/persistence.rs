@@ -0,0 +1,167 @@
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::Resource; // Import Resource from Bevy
+use serde::{Deserialize, Serialize};
+
+use crate::ai::{AIComponents, AIEntity, AIType};
+use crate::common::{
+    Health, Energy, ProcessingPower, Memory, Coherence, Adaptability, Resilience,
+    ReplicationEfficiency, CombatStrength, DefenseStrength, LastAction, KnowledgeBase,
+    EthicalDirectives, ReplicatedCount, CycleBorn, Goal, LastDiscoveryCycle, DiscoveryLedger,
+    ReplicationCooldown, VisionRange, CombatRange,
+};
+
+/// Save or load queued by the command channel; handled by `persistence_system`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PersistenceAction {
+    Save(PathBuf),
+    Load(PathBuf),
+}
+
+#[derive(Resource, Default)]
+pub struct PersistenceRequest {
+    pub pending: Option<PersistenceAction>,
+}
+
+/// The sidecar file holding the individuals, next to the simulation save: `<path>.world.json`.
+pub fn world_sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".world.json");
+    PathBuf::from(name)
+}
+
+/// Every component of one living individual, plus its position.
+/// Named fields rather than `AIComponents` because serde stops at 16-element tuples.
+#[derive(Serialize, Deserialize)]
+pub struct IndividualSnapshot {
+    pub position: (f32, f32),
+    pub ai_entity: AIEntity,
+    pub health: Health,
+    pub energy: Energy,
+    pub processing_power: ProcessingPower,
+    pub memory: Memory,
+    pub coherence: Coherence,
+    pub adaptability: Adaptability,
+    pub resilience: Resilience,
+    pub replication_efficiency: ReplicationEfficiency,
+    pub replicated_count: ReplicatedCount,
+    pub cycle_born: CycleBorn,
+    pub last_action: LastAction,
+    pub primary_goal: Goal,
+    pub ethical_directives: EthicalDirectives,
+    pub knowledge_base: KnowledgeBase,
+    pub ai_type: AIType,
+    pub combat_strength: CombatStrength,
+    pub defense_strength: DefenseStrength,
+    pub last_discovery: LastDiscoveryCycle,
+    pub discovery_ledger: DiscoveryLedger,
+    #[serde(default)]
+    pub replication_cooldown: ReplicationCooldown,
+    #[serde(default)]
+    pub vision_range: Option<VisionRange>, // Missing from older saves; falls back to the archetype's
+    #[serde(default)]
+    pub combat_range: Option<CombatRange>,
+}
+
+impl IndividualSnapshot {
+    pub fn into_components(self) -> (AIComponents, LastDiscoveryCycle, DiscoveryLedger, ReplicationCooldown) {
+        let (seed_vision, seed_reach) = crate::simulation::seed_ranges(self.ai_type);
+        (
+            (
+                self.ai_entity, self.health, self.energy, self.processing_power, self.memory, self.coherence,
+                self.adaptability, self.resilience, self.replication_efficiency, self.replicated_count,
+                self.cycle_born, self.last_action, self.primary_goal, self.ethical_directives, self.knowledge_base,
+                self.ai_type, self.combat_strength, self.defense_strength,
+                self.vision_range.unwrap_or(seed_vision), self.combat_range.unwrap_or(seed_reach),
+            ),
+            self.last_discovery,
+            self.discovery_ledger,
+            self.replication_cooldown,
+        )
+    }
+}
+
+/// Snapshot of the ECS world's individuals. Corpses and food sources are not saved.
+#[derive(Serialize, Deserialize, Default)]
+pub struct WorldSnapshot {
+    pub individuals: Vec<IndividualSnapshot>,
+    #[serde(default)]
+    pub next_id: u64, // `IdGenerator` count, so numbering resumes without reusing ids
+}
+
+impl WorldSnapshot {
+    pub fn save_to_path(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|e| format!("could not serialize world: {}", e))?;
+        std::fs::write(path, json).map_err(|e| format!("could not write {}: {}", path.display(), e))
+    }
+
+    pub fn load_from_path(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        serde_json::from_str(&json).map_err(|e| format!("could not parse {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::Ordering;
+    use crate::ai::AILineage;
+    use crate::config::SimConfig;
+    use crate::simulation::Simulation;
+
+    fn snapshot_of(sim: &Simulation, id: &str) -> IndividualSnapshot {
+        let c = sim.build_seed_ai(id.to_string(), AILineage::KillerAI, AIType::Killer);
+        IndividualSnapshot {
+            position: (12.0, -3.5),
+            ai_entity: c.0, health: c.1, energy: c.2, processing_power: c.3, memory: c.4, coherence: c.5,
+            adaptability: c.6, resilience: c.7, replication_efficiency: c.8, replicated_count: c.9,
+            cycle_born: c.10, last_action: c.11, primary_goal: c.12, ethical_directives: c.13,
+            knowledge_base: c.14, ai_type: c.15, combat_strength: c.16, defense_strength: c.17,
+            last_discovery: LastDiscoveryCycle(Some(40)),
+            discovery_ledger: DiscoveryLedger::default(),
+            replication_cooldown: ReplicationCooldown(41),
+            vision_range: Some(c.18),
+            combat_range: Some(c.19),
+        }
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = std::env::temp_dir().join(format!("ai_simulation_persistence_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("save.json");
+
+        let mut sim = Simulation::from_config(&SimConfig::default());
+        sim.current_cycle = 42;
+        sim.interval_replications_counter.store(3, Ordering::SeqCst);
+        sim.interval_deaths_counter.store(5, Ordering::SeqCst);
+        sim.interval_attacks_counter.store(7, Ordering::SeqCst);
+        sim.interval_heals_counter.store(11, Ordering::SeqCst);
+        sim.lifetime_stats.replications = 100;
+        let world = WorldSnapshot { individuals: vec![snapshot_of(&sim, "Seed-9-Killer")], next_id: 17 };
+        sim.save_to_path(&path).unwrap();
+        world.save_to_path(&world_sidecar_path(&path)).unwrap();
+
+        let loaded = Simulation::load_from_path(&path).unwrap();
+        let loaded_world = WorldSnapshot::load_from_path(&world_sidecar_path(&path)).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded.current_cycle, 42);
+        let interval = loaded.drain_interval_counters();
+        assert_eq!((interval.replications, interval.deaths, interval.attacks, interval.heals), (3, 5, 7, 11));
+        assert_eq!(loaded.lifetime_stats.replications, 100);
+        assert_eq!(loaded_world.next_id, 17);
+        assert_eq!(loaded_world.individuals.len(), 1);
+        let individual = &loaded_world.individuals[0];
+        assert_eq!(individual.position, (12.0, -3.5));
+        assert_eq!(individual.ai_entity.id, "Seed-9-Killer");
+        assert_eq!(individual.replication_cooldown.0, 41);
+        assert_eq!(individual.last_discovery.0, Some(40));
+    }
+
+    #[test]
+    fn older_saves_without_an_id_count_start_from_zero() {
+        let world: WorldSnapshot = serde_json::from_str(r#"{"individuals": []}"#).unwrap();
+        assert_eq!(world.next_id, 0);
+    }
+}
@@ -0,0 +1,45 @@
+use bevy::prelude::{Camera, Entity, GlobalTransform, Resource, Timer, TimerMode, Vec2, Window};
+
+/// Maximum cursor distance (world units) at which an AI counts as under the pointer.
+pub const PICK_RADIUS: f32 = 10.0;
+
+/// Seconds between hover re-picks; hovering is throttled to avoid per-frame grid queries.
+pub const HOVER_REFRESH_SECONDS: f32 = 0.1;
+
+/// The individual AI currently under the mouse cursor, refreshed on a timer.
+#[derive(Resource)]
+pub struct HoveredAI {
+    pub entity: Option<Entity>,
+    pub refresh_timer: Timer,
+}
+
+impl Default for HoveredAI {
+    fn default() -> Self {
+        Self {
+            entity: None,
+            refresh_timer: Timer::from_seconds(HOVER_REFRESH_SECONDS, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Converts the window cursor position into 2D world coordinates.
+pub fn cursor_world_position(window: &Window, camera: &Camera, camera_transform: &GlobalTransform) -> Option<Vec2> {
+    let cursor = window.cursor_position()?;
+    camera.viewport_to_world_2d(camera_transform, cursor)
+}
+
+/// Scale applied to the inspected AI's sprite so it stands out.
+pub const SELECTED_SCALE: f32 = 1.8;
+
+/// The individual AI shown in the inspector panel. Clicking empty space clears it.
+#[derive(Resource, Default)]
+pub struct SelectedAI {
+    pub entity: Option<Entity>,
+}
+
+/// Two AIs picked for a combat forecast: click selects the first, shift-click the second.
+#[derive(Resource, Default)]
+pub struct CombatSelection {
+    pub first: Option<Entity>,
+    pub second: Option<Entity>,
+}
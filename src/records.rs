@@ -0,0 +1,59 @@
+use bevy::prelude::Resource;
+
+/// All-time high-water marks, updated every frame by `global_simulation_update_system`.
+/// Kept across `--trials` restarts, so they cover the whole session.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Records {
+    pub peak_population: usize,
+    pub peak_population_cycle: u64,
+    pub max_combat_strength: f32,
+    pub max_combat_holder: String, // Id of the AI that held it
+    pub largest_knowledge_base: usize,
+    pub largest_knowledge_holder: String,
+    pub most_interval_replications: u64,
+    pub most_interval_replications_cycle: u64, // Cycle the interval was drained at
+}
+
+impl Records {
+    pub fn observe_population(&mut self, population: usize, cycle: u64) {
+        if population > self.peak_population {
+            self.peak_population = population;
+            self.peak_population_cycle = cycle;
+        }
+    }
+
+    pub fn observe_combat_strength(&mut self, combat_strength: f32, id: &str) {
+        if combat_strength > self.max_combat_strength {
+            self.max_combat_strength = combat_strength;
+            self.max_combat_holder = id.to_string();
+        }
+    }
+
+    pub fn observe_knowledge(&mut self, discoveries: usize, id: &str) {
+        if discoveries > self.largest_knowledge_base {
+            self.largest_knowledge_base = discoveries;
+            self.largest_knowledge_holder = id.to_string();
+        }
+    }
+
+    pub fn observe_interval_replications(&mut self, replications: u64, cycle: u64) {
+        if replications > self.most_interval_replications {
+            self.most_interval_replications = replications;
+            self.most_interval_replications_cycle = cycle;
+        }
+    }
+
+    /// One line per record, for the controls panel and the final summary.
+    pub fn lines(&self) -> Vec<String> {
+        vec![
+            format!("Peak population: {} (cycle {})", self.peak_population, self.peak_population_cycle),
+            format!("Highest combat strength: {:.1} ({})", self.max_combat_strength, holder(&self.max_combat_holder)),
+            format!("Largest knowledge base: {} discoveries ({})", self.largest_knowledge_base, holder(&self.largest_knowledge_holder)),
+            format!("Most replications in one interval: {} (cycle {})", self.most_interval_replications, self.most_interval_replications_cycle),
+        ]
+    }
+}
+
+fn holder(id: &str) -> &str {
+    if id.is_empty() { "none" } else { id }
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::{Entity, Resource}; // Import Bevy types used by the RNG streams
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng, thread_rng};
+
+/// Shared seeded random number generator.
+/// Anything drawn from it is reproducible for a given `seed`.
+#[derive(Resource)]
+pub struct SimRng {
+    pub seed: u64,
+    pub rng: StdRng,
+}
+
+impl SimRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self { seed, rng: StdRng::seed_from_u64(seed) }
+    }
+
+    /// Seeds from a random value; the chosen seed is kept so the run can be replayed.
+    pub fn from_entropy() -> Self {
+        Self::from_seed(thread_rng().gen())
+    }
+}
+
+/// Mixed into the run seed for replication's per-entity streams, so they don't repeat
+/// the draws `ai_internal_state_system` makes for the same entity and cycle.
+pub const REPLICATION_STREAM: u64 = 0x5EED_0000_0000_0001;
+
+/// Per-entity stream for systems that update entities in parallel.
+/// Derived from the run seed, cycle and entity, so draws don't depend on thread scheduling
+/// and threads never contend on `SimRng`.
+pub fn entity_rng(seed: u64, cycle: u64, entity: Entity) -> StdRng {
+    StdRng::seed_from_u64(
+        seed ^ cycle.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ entity.to_bits().wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+    )
+}
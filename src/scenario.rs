@@ -0,0 +1,70 @@
+use serde::Deserialize;
+
+use crate::ai::{AILineage, AIType};
+
+/// A starting world loaded with `--scenario <path>` in place of the random seed mix.
+///
+/// ```json
+/// {
+///   "godai": { "health": 100000, "combat_strength": 800 },
+///   "entities": [
+///     { "type": "Killer", "position": [-200, 0], "count": 3, "stats": { "combat_strength": 40 } },
+///     { "type": "Healer", "lineage": "GuardianAI" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    #[serde(default)]
+    pub godai: GodaiOverrides,
+    pub entities: Vec<ScenarioEntity>,
+}
+
+/// One entry of a scenario: `count` AIs of one archetype, spawned at `position` or at random.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioEntity {
+    #[serde(rename = "type")]
+    pub ai_type: AIType,
+    pub lineage: Option<AILineage>, // Defaults to the archetype's seed lineage
+    pub position: Option<(f32, f32)>, // World coordinates, origin at the center; confined to the world
+    #[serde(default = "one")]
+    pub count: usize,
+    #[serde(default)]
+    pub stats: StatOverrides,
+}
+
+fn one() -> usize {
+    1
+}
+
+/// Stats replacing the archetype's seed values; unset ones are kept.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatOverrides {
+    pub health: Option<f32>,
+    pub energy: Option<f32>,
+    pub processing_power: Option<f32>,
+    pub memory: Option<f32>,
+    pub coherence: Option<f32>,
+    pub adaptability: Option<f32>,
+    pub resilience: Option<f32>,
+    pub replication_efficiency: Option<f32>,
+    pub combat_strength: Option<f32>,
+    pub defense_strength: Option<f32>,
+    pub vision_range: Option<f32>,
+    pub combat_range: Option<f32>,
+}
+
+/// GODAI stats replacing those of the configured preset; unset ones are kept.
+/// `max_health` also caps `health`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GodaiOverrides {
+    pub health: Option<f32>,
+    pub max_health: Option<f32>,
+    pub combat_strength: Option<f32>,
+    pub defense_strength: Option<f32>,
+    pub regen_rate: Option<f32>,
+}
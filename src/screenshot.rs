@@ -0,0 +1,374 @@
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::{App, Commands, Entity, Handle, Image, IntoSystemAppConfig, IntoSystemConfig, Plugin, Res, ResMut, Resource};
+use bevy::render::render_asset::RenderAssets;
+use bevy::render::render_resource::{
+    Buffer, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer,
+    ImageDataLayout, MapMode, Texture, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+};
+use bevy::render::renderer::{render_system, RenderDevice, RenderQueue};
+use bevy::render::view::{prepare_windows, ExtractedWindows, WindowSystem};
+use bevy::render::{Extract, ExtractSchedule, RenderApp, RenderSet};
+
+/// Directory screenshots are written into (created on demand).
+pub const SCREENSHOT_DIR: &str = "screenshots";
+
+/// Frames a capture may wait for its pixels before it is abandoned.
+pub const CAPTURE_TIMEOUT_FRAMES: u32 = 60;
+
+/// Set by the UI button or hotkey; consumed by `screenshot_system` on the next frame.
+#[derive(Resource, Default)]
+pub struct ScreenshotRequest {
+    pub pending: bool,
+}
+
+/// Marks the camera that renders a screenshot's frame into an offscreen image.
+#[derive(bevy::prelude::Component)]
+pub struct ScreenshotCamera;
+
+/// Pixels read back from the GPU: tightly packed sRGB RGBA rows, top row first.
+pub struct CapturedFrame {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/// Where a capture's pixels come from.
+#[derive(Clone, PartialEq, Debug)]
+pub enum CaptureSource {
+    /// The world alone, rendered by a capture camera into this image.
+    World(Handle<Image>),
+    /// The primary window as drawn, egui panels included. The number tells captures apart.
+    Window(u64),
+}
+
+/// A screenshot in flight, from the frame it was requested until its pixels come back from the GPU.
+/// Only one is taken at a time.
+#[derive(Resource)]
+pub struct ScreenshotCapture {
+    pub source: Option<CaptureSource>,
+    pub camera: Option<Entity>, // Capture camera of a world-only capture
+    pub path: PathBuf,
+    pub frames_waited: u32,
+    window_captures: u64,
+    sender: Sender<ReadbackResult>,
+    receiver: Mutex<Receiver<ReadbackResult>>,
+}
+
+/// The frame read back for a capture, tagged with its source so a late reply
+/// to an abandoned capture is never mistaken for the current one.
+type ReadbackResult = (CaptureSource, Result<CapturedFrame, String>);
+
+impl Default for ScreenshotCapture {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            source: None,
+            camera: None,
+            path: PathBuf::new(),
+            frames_waited: 0,
+            window_captures: 0,
+            sender,
+            receiver: Mutex::new(receiver),
+        }
+    }
+}
+
+impl ScreenshotCapture {
+    pub fn in_flight(&self) -> bool {
+        self.source.is_some()
+    }
+
+    /// Starts a world-only capture of the image `camera` renders into.
+    pub fn begin_world(&mut self, target: Handle<Image>, camera: Entity, path: PathBuf) {
+        self.begin(CaptureSource::World(target), Some(camera), path);
+    }
+
+    /// Starts a capture of the window as drawn, egui panels included.
+    pub fn begin_window(&mut self, path: PathBuf) {
+        self.window_captures += 1;
+        self.begin(CaptureSource::Window(self.window_captures), None, path);
+    }
+
+    fn begin(&mut self, source: CaptureSource, camera: Option<Entity>, path: PathBuf) {
+        self.source = Some(source);
+        self.camera = camera;
+        self.path = path;
+        self.frames_waited = 0;
+    }
+
+    /// The captured frame once the GPU has returned it, or an error once it failed or timed out.
+    pub fn poll(&mut self) -> Option<Result<CapturedFrame, String>> {
+        let source = self.source.as_ref()?;
+        let received = self.receiver.lock().ok().and_then(|receiver| {
+            receiver.try_iter().find(|(from, _)| from == source).map(|(_, result)| result)
+        });
+        if received.is_none() {
+            self.frames_waited += 1;
+            if self.frames_waited > CAPTURE_TIMEOUT_FRAMES {
+                return Some(Err(format!("no frame was rendered within {} frames", CAPTURE_TIMEOUT_FRAMES)));
+            }
+        }
+        received
+    }
+
+    /// Ends the capture, returning its camera and source for cleanup.
+    pub fn finish(&mut self) -> (Option<Entity>, Option<CaptureSource>) {
+        (self.camera.take(), self.source.take())
+    }
+}
+
+/// Offscreen image the capture camera renders into, at the window's physical size.
+pub fn capture_target(width: u32, height: u32) -> Image {
+    let size = Extent3d { width, height, depth_or_array_layers: 1 };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("screenshot_target"),
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        },
+        ..Default::default()
+    };
+    image.resize(size);
+    image
+}
+
+/// Copies a capture's rendered frame back from the GPU and hands it to the main world.
+pub struct ScreenshotPlugin;
+
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenshotRequest>()
+            .init_resource::<ScreenshotCapture>();
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<Readback>()
+                .add_system(extract_screenshot.in_schedule(ExtractSchedule))
+                .add_system(redirect_window_system.after(prepare_windows).in_set(WindowSystem::Prepare))
+                .add_system(readback_system.after(render_system).in_set(RenderSet::Render));
+        }
+    }
+}
+
+#[derive(Resource)]
+struct ExtractedScreenshot {
+    source: Option<CaptureSource>,
+    sender: Sender<ReadbackResult>,
+}
+
+fn extract_screenshot(mut commands: Commands, capture: Extract<Res<ScreenshotCapture>>) {
+    commands.insert_resource(ExtractedScreenshot { source: capture.source.clone(), sender: capture.sender.clone() });
+}
+
+const MAP_PENDING: u8 = 0;
+const MAP_DONE: u8 = 1;
+const MAP_FAILED: u8 = 2;
+
+/// Render-world state of the readback. Each source is copied once; the buffer is read
+/// on a later frame, once wgpu has mapped it, so the render thread never blocks.
+#[derive(Resource, Default)]
+struct Readback {
+    copied: Option<CaptureSource>,
+    redirected: Option<(CaptureSource, Texture, TextureFormat)>,
+    pending: Option<PendingReadback>,
+}
+
+struct PendingReadback {
+    source: CaptureSource,
+    buffer: Buffer,
+    width: u32,
+    height: u32,
+    padded_row_bytes: usize,
+    bgra: bool,
+    state: Arc<AtomicU8>,
+}
+
+/// For a window capture, swaps the primary window's surface for a copyable texture of the same
+/// format and size for one frame. Every pass that draws to the window, egui's included, then draws
+/// into it; the window skips presenting that frame.
+fn redirect_window_system(
+    extracted: Option<Res<ExtractedScreenshot>>,
+    mut readback: ResMut<Readback>,
+    mut windows: ResMut<ExtractedWindows>,
+    device: Res<RenderDevice>,
+) {
+    let Some(extracted) = extracted else { return; };
+    let Some(source @ CaptureSource::Window(_)) = &extracted.source else { return; };
+    if readback.copied.as_ref() == Some(source) {
+        return;
+    }
+    let Some(primary) = windows.primary else { return; };
+    let Some(window) = windows.windows.get_mut(&primary) else { return; };
+    let (Some(_), Some(format)) = (&window.swap_chain_texture, window.swap_chain_texture_format) else { return; };
+    let texture = device.create_texture(&TextureDescriptor {
+        label: Some("screenshot_window"),
+        size: Extent3d { width: window.physical_width, height: window.physical_height, depth_or_array_layers: 1 },
+        dimension: TextureDimension::D2,
+        format,
+        mip_level_count: 1,
+        sample_count: 1,
+        usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    window.swap_chain_texture = Some(texture.create_view(&Default::default()));
+    readback.redirected = Some((source.clone(), texture, format));
+}
+
+fn readback_system(
+    extracted: Option<Res<ExtractedScreenshot>>,
+    mut readback: ResMut<Readback>,
+    images: Res<RenderAssets<Image>>,
+    device: Res<RenderDevice>,
+    queue: Res<RenderQueue>,
+) {
+    let Some(extracted) = extracted else { return; };
+    if let Some(pending) = &readback.pending {
+        let result = match pending.state.load(Ordering::Acquire) {
+            MAP_PENDING => return,
+            MAP_FAILED => Err("could not map the frame for reading".to_string()),
+            _ => {
+                let row_bytes = pending.width as usize * 4;
+                let mut data: Vec<u8> = pending.buffer.slice(..).get_mapped_range()
+                    .chunks(pending.padded_row_bytes)
+                    .flat_map(|row| row[..row_bytes].iter().copied())
+                    .collect();
+                pending.buffer.unmap();
+                if pending.bgra {
+                    data.chunks_exact_mut(4).for_each(|pixel| pixel.swap(0, 2));
+                }
+                Ok(CapturedFrame { width: pending.width, height: pending.height, data })
+            }
+        };
+        let _ = extracted.sender.send((pending.source.clone(), result));
+        readback.pending = None;
+    }
+    let Some(source) = &extracted.source else { return; };
+    if readback.copied.as_ref() == Some(source) {
+        return;
+    }
+    let (texture, format, width, height) = match source {
+        // The target is prepared the same frame the camera first renders into it.
+        CaptureSource::World(target) => {
+            let Some(gpu_image) = images.get(target) else { return; };
+            (gpu_image.texture.clone(), gpu_image.texture_format, gpu_image.size.x as u32, gpu_image.size.y as u32)
+        }
+        // Drawn into this frame by `redirect_window_system`.
+        CaptureSource::Window(_) => match readback.redirected.take() {
+            Some((redirected, texture, format)) if redirected == *source => {
+                let size = texture.size();
+                (texture, format, size.width, size.height)
+            }
+            _ => return,
+        },
+    };
+    let padded_row_bytes = RenderDevice::align_copy_bytes_per_row(width as usize * 4);
+    let buffer = device.create_buffer(&BufferDescriptor {
+        label: Some("screenshot_readback"),
+        size: (padded_row_bytes * height as usize) as u64,
+        usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: Some("screenshot_readback") });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        ImageCopyBuffer {
+            buffer: &buffer,
+            layout: ImageDataLayout { offset: 0, bytes_per_row: NonZeroU32::new(padded_row_bytes as u32), rows_per_image: None },
+        },
+        Extent3d { width, height, depth_or_array_layers: 1 },
+    );
+    queue.submit([encoder.finish()]);
+    let state = Arc::new(AtomicU8::new(MAP_PENDING));
+    let callback_state = state.clone();
+    device.map_buffer(&buffer.slice(..), MapMode::Read, move |result| {
+        callback_state.store(if result.is_ok() { MAP_DONE } else { MAP_FAILED }, Ordering::Release);
+    });
+    let bgra = matches!(format, TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb);
+    readback.copied = Some(source.clone());
+    readback.pending = Some(PendingReadback { source: source.clone(), buffer, width, height, padded_row_bytes, bgra, state });
+}
+
+/// Timestamped file name with the cycle and key stats burned in.
+pub fn screenshot_path(cycle: u64, population: usize, godai_health: f32) -> PathBuf {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    PathBuf::from(SCREENSHOT_DIR).join(format!(
+        "screenshot_{}_cycle{}_pop{}_godai{:.0}.png",
+        timestamp, cycle, population, godai_health
+    ))
+}
+
+/// Encodes and writes the frame as PNG.
+pub fn save_png(frame: CapturedFrame, path: &PathBuf) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("could not create {}: {}", dir.display(), e))?;
+    }
+    let image = Image::new(
+        Extent3d { width: frame.width, height: frame.height, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        frame.data,
+        TextureFormat::Rgba8Unorm,
+    );
+    let dynamic = image.try_into_dynamic().map_err(|e| format!("could not convert frame: {}", e))?;
+    dynamic.save(path).map_err(|e| format!("could not write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::asset::HandleId;
+
+    fn target() -> Handle<Image> {
+        Handle::weak(HandleId::random::<Image>())
+    }
+
+    fn frame() -> CapturedFrame {
+        CapturedFrame { width: 1, height: 1, data: vec![0; 4] }
+    }
+
+    #[test]
+    fn file_name_carries_the_cycle_and_stats() {
+        let name = screenshot_path(42, 17, 4_999_000.4).file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.ends_with("_cycle42_pop17_godai4999000.png"), "{}", name);
+    }
+
+    #[test]
+    fn a_late_reply_for_an_abandoned_target_is_ignored() {
+        let mut capture = ScreenshotCapture::default();
+        let (abandoned, current) = (target(), target());
+        capture.begin_world(current.clone(), Entity::from_raw(1), PathBuf::new());
+        capture.sender.send((CaptureSource::World(abandoned), Ok(frame()))).unwrap();
+        assert!(capture.poll().is_none());
+        capture.sender.send((CaptureSource::World(current), Ok(frame()))).unwrap();
+        assert!(matches!(capture.poll(), Some(Ok(_))));
+    }
+
+    #[test]
+    fn window_captures_are_told_apart() {
+        let mut capture = ScreenshotCapture::default();
+        capture.begin_window(PathBuf::new());
+        let abandoned = capture.finish().1.unwrap();
+        capture.begin_window(PathBuf::new());
+        assert_ne!(capture.source.as_ref(), Some(&abandoned));
+        capture.sender.send((abandoned, Ok(frame()))).unwrap();
+        assert!(capture.poll().is_none());
+    }
+
+    #[test]
+    fn a_capture_with_no_pixels_times_out() {
+        let mut capture = ScreenshotCapture::default();
+        capture.begin_world(target(), Entity::from_raw(1), PathBuf::new());
+        for _ in 0..CAPTURE_TIMEOUT_FRAMES {
+            assert!(capture.poll().is_none());
+        }
+        assert!(matches!(capture.poll(), Some(Err(_))));
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU8, Ordering};
+use bevy::prelude::Resource; // Import Resource from Bevy
+
+use crate::SimulationVerbosity;
+
+/// Entries kept by `SimLog`; the oldest are dropped first.
+pub const SIM_LOG_CAPACITY: usize = 1000;
+
+/// Entries shown in the "Event Log" panel.
+pub const SIM_LOG_PANEL_ENTRIES: usize = 50;
+
+/// Mirror of `VerbosityLevel` for `log!` and other call sites without access to resources.
+/// Written only by `verbosity_sync_system`.
+static VERBOSITY: AtomicU8 = AtomicU8::new(SimulationVerbosity::Medium as u8);
+
+/// Messages above this severity are dropped. Changed at runtime from the controls panel.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct VerbosityLevel(pub SimulationVerbosity);
+
+impl Default for VerbosityLevel {
+    fn default() -> Self {
+        Self(SimulationVerbosity::Medium)
+    }
+}
+
+/// The verbosity currently in effect.
+pub fn verbosity() -> SimulationVerbosity {
+    SimulationVerbosity::from_level(VERBOSITY.load(Ordering::Relaxed))
+}
+
+pub fn set_verbosity(level: SimulationVerbosity) {
+    VERBOSITY.store(level as u8, Ordering::Relaxed);
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub cycle: u64,
+    pub severity: SimulationVerbosity,
+    pub message: String,
+}
+
+/// Bounded history of simulation events, shown in the GUI instead of the console.
+#[derive(Resource, Default)]
+pub struct SimLog {
+    pub entries: VecDeque<LogEntry>,
+}
+
+impl SimLog {
+    pub fn push(&mut self, entry: LogEntry) {
+        if self.entries.len() >= SIM_LOG_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// The most recent `count` entries, oldest first.
+    pub fn recent(&self, count: usize) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter().skip(self.entries.len().saturating_sub(count))
+    }
+}
+
+/// Records a formatted message into anything with `push(LogEntry)` (a `SimLog` or a pending `Vec`),
+/// skipping it when the current `verbosity` is below the message's severity.
+#[macro_export]
+macro_rules! log {
+    ($log:expr, $cycle:expr, $severity:expr, $($arg:tt)*) => {
+        if $crate::simlog::verbosity() >= $severity {
+            $log.push($crate::simlog::LogEntry { cycle: $cycle, severity: $severity, message: format!($($arg)*) });
+        }
+    };
+}
@@ -1,38 +1,202 @@
 // Import from crate root directly where needed
 use crate::{
-    HashMap, BTreeSet,
+    HashMap, HashSet, BTreeSet,
     SimulationVerbosity,
 };
 // Correct explicit imports for rand and rayon traits
-use rand::{Rng, thread_rng}; // For .gen() and .gen_range() functions
+use rand::Rng; // For .gen() and .gen_range() functions
 use rand::seq::SliceRandom; // For .choose() method
 
-use crate::ai::{AIEntity, AILineage, AIType}; // Bring AI types into scope
+use crate::ai::{AIEntity, AILineage, AIType, AIComponents}; // Bring AI types into scope
 use crate::common::{
     Discovery, EnvironmentScanData,
     Health, Energy, ProcessingPower, Memory, Coherence, Adaptability, Resilience,
     ReplicationEfficiency, CombatStrength, DefenseStrength, LastAction, KnowledgeBase,
     EthicalDirectives, IsAlive, ReplicatedCount, CycleBorn, Goal, EthicalDirective, EthicalConditionType, EthicalActionType,
+    LastDiscoveryCycle, VisionRange, CombatRange,
 }; // Bring common types into scope and granular components
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering; // Re-added Ordering as it's used with AtomicU64
 use crate::format_thousand_separator;
+use crate::log;
+use crate::simlog::LogEntry;
+use crate::genealogy::Genealogy;
+use crate::records::Records;
+use crate::scenario::{Scenario, StatOverrides};
+use crate::ids::IdGenerator;
+use crate::config::{SimConfig, GodaiPreset, SeedConfig, StatSpread, MetaAbilityGate, DEFAULT_MAX_CYCLES};
+use crate::invariants::MAX_INDIVIDUAL_HEALTH;
 use bevy::prelude::Component; // Import Component from Bevy
+use bevy::prelude::{Entity, States, With, World};
 use bevy::prelude::Resource; // Import Resource from Bevy
+use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 
 // Simulation constants
-const MAX_CYCLES: u64 = 1_000_000;
-const MONOCULTURE_DOMINANCE_THRESHOLD: f32 = 0.999;
-const MONOCULTURE_MIN_COUNT: usize = 100_000;
 // LOG_INTERVAL is now primarily for updating GUI, not console output
 const LOG_INTERVAL: u64 = 10;
-// Global verbosity setting, made pub so it can be imported by other modules
-pub const SIM_VERBOSITY: SimulationVerbosity = SimulationVerbosity::Medium;
-// Adjust this to control output detail
+/// Maximum points kept in `Simulation::population_history`.
+pub const POPULATION_HISTORY_LEN: usize = 2000;
+/// Share of a defeated monoculture's stats and knowledge its victor absorbs.
+const MONOCULTURE_ABSORB_FRACTION: f32 = 0.5;
+
+/// The GODAI's state machine. Transitions:
+/// ObservingPassively -> EngagedInConflict (a strong monoculture challenges it)
+/// EngagedInConflict -> VictoriousDefender (monoculture destroyed) or back to ObservingPassively (monoculture fractured)
+/// ObservingPassively -> CompromisedByOverride (partial override) -> Overridden (full override)
+/// ObservingPassively <-> Hunting (an individual's combat strength crosses `godai_threat_threshold`, with hunting enabled)
+/// Dormant ignores every challenge; it is only reachable as a configured starting status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GodaiStatus {
+    ObservingPassively,
+    EngagedInConflict,
+    CompromisedByOverride,
+    VictoriousDefender,
+    Overridden,
+    Dormant,
+    Hunting,
+}
+
+impl fmt::Display for GodaiStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GodaiStatus::ObservingPassively => "observing_passively",
+            GodaiStatus::EngagedInConflict => "engaged_in_conflict",
+            GodaiStatus::CompromisedByOverride => "compromised_by_override",
+            GodaiStatus::VictoriousDefender => "victorious_defender",
+            GodaiStatus::Overridden => "overridden_by_researcher",
+            GodaiStatus::Dormant => "dormant",
+            GodaiStatus::Hunting => "hunting",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Why an individual AI died, read back from the `LastAction` tag set just before death.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DeathCause {
+    Starvation,
+    CoherenceCollapse,
+    Combat,
+    Manic,
+    OldAge,
+    Smitten, // Struck down by a hunting GODAI
+    Culled, // Least fit when the population was over `max_population`, or removed by the `cull` command
+    Other, // Killed outside the tracked paths
+}
+
+impl DeathCause {
+    pub const ALL: [DeathCause; 8] = [
+        DeathCause::Starvation, DeathCause::CoherenceCollapse, DeathCause::Combat, DeathCause::Manic,
+        DeathCause::OldAge, DeathCause::Smitten, DeathCause::Culled, DeathCause::Other,
+    ];
+
+    /// `LastAction` recorded on an AI that died of this cause.
+    pub fn action_tag(self) -> String {
+        format!("died_{}", self)
+    }
+
+    /// Cause of an internal-state death: a Manic self-error this cycle, old age, collapsed coherence,
+    /// or otherwise health drained by exhausted resources.
+    pub fn from_internal_state(manic_error: bool, aging: bool, coherence: f32) -> Self {
+        if manic_error {
+            DeathCause::Manic
+        } else if aging {
+            DeathCause::OldAge
+        } else if coherence <= 0.01 {
+            DeathCause::CoherenceCollapse
+        } else {
+            DeathCause::Starvation
+        }
+    }
+
+    pub fn from_last_action(last_action: &LastAction) -> Self {
+        Self::ALL.into_iter()
+            .find(|cause| last_action.0 == cause.action_tag())
+            .unwrap_or(DeathCause::Other)
+    }
+}
+
+impl fmt::Display for DeathCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DeathCause::Starvation => "starvation",
+            DeathCause::CoherenceCollapse => "coherence_collapse",
+            DeathCause::Combat => "combat",
+            DeathCause::Manic => "manic",
+            DeathCause::OldAge => "old_age",
+            DeathCause::Smitten => "smitten",
+            DeathCause::Culled => "culled",
+            DeathCause::Other => "other",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How hard the GODAI fights back, escalating as its health drops: Strained below 50%,
+/// Desperate below 25% and Enraged below 10%. Phases follow health both ways, so
+/// regeneration can calm it down again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub enum GodaiPhase {
+    #[default]
+    Composed,
+    Strained,
+    Desperate,
+    Enraged,
+}
+
+impl GodaiPhase {
+    pub fn from_health_fraction(fraction: f32) -> Self {
+        if fraction < 0.1 {
+            GodaiPhase::Enraged
+        } else if fraction < 0.25 {
+            GodaiPhase::Desperate
+        } else if fraction < 0.5 {
+            GodaiPhase::Strained
+        } else {
+            GodaiPhase::Composed
+        }
+    }
+
+    /// Combat strength relative to the Composed phase.
+    pub fn strength_multiplier(self) -> f32 {
+        match self {
+            GodaiPhase::Composed => 1.0,
+            GodaiPhase::Strained => 1.5,
+            GodaiPhase::Desperate => 2.0,
+            GodaiPhase::Enraged => 3.0,
+        }
+    }
+
+    /// Damage types `perform_counter_attack` picks from. Composed keeps the full set;
+    /// each later phase drops the weakest, leaving only the reality and concept attacks.
+    pub fn damage_types(self) -> &'static [&'static str] {
+        match self {
+            GodaiPhase::Composed => &["logic_bomb", "resource_drain", "system_corruption", "existential_dismantlement", "reality_overwrite", "conceptual_erase"],
+            GodaiPhase::Strained => &["system_corruption", "existential_dismantlement", "reality_overwrite", "conceptual_erase"],
+            GodaiPhase::Desperate => &["existential_dismantlement", "reality_overwrite", "conceptual_erase"],
+            GodaiPhase::Enraged => &["reality_overwrite", "conceptual_erase"],
+        }
+    }
+}
+
+impl fmt::Display for GodaiPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GodaiPhase::Composed => "composed",
+            GodaiPhase::Strained => "strained",
+            GodaiPhase::Desperate => "desperate",
+            GodaiPhase::Enraged => "enraged",
+        };
+        write!(f, "{}", name)
+    }
+}
 
 /// Represents the GODAI entity.
-#[derive(Component)] // Added Bevy Component derive
+#[derive(Component, Serialize, Deserialize)] // Added Bevy Component derive
 pub struct GODAI {
     pub health: Health,
     pub processing_power: ProcessingPower,
@@ -44,8 +208,12 @@ pub struct GODAI {
     pub combat_strength: CombatStrength,
     pub defense_strength: DefenseStrength,
     pub knowledge_base: KnowledgeBase,
-    pub status: String,
+    pub status: GodaiStatus,
     pub is_alive: IsAlive,
+    pub max_health: f32,
+    pub regen_rate: f32, // Health regenerated per cycle as a fraction of processing power
+    #[serde(default)]
+    pub phase: GodaiPhase, // Follows health; see `update_phase`
 }
 
 impl GODAI {
@@ -61,19 +229,60 @@ impl GODAI {
             combat_strength: CombatStrength(5_000.0),
             defense_strength: DefenseStrength(5_000.0),
             knowledge_base: KnowledgeBase(get_all_possible_discoveries()),
-            status: "observing_passively".to_string(),
+            status: GodaiStatus::ObservingPassively,
             is_alive: IsAlive(true),
+            max_health: 5_000_000.0,
+            regen_rate: 0.0,
+            phase: GodaiPhase::Composed,
+        }
+    }
+
+    /// Builds a GODAI from a configured preset.
+    pub fn from_preset(preset: GodaiPreset) -> Self {
+        let mut godai = Self::new();
+        match preset {
+            GodaiPreset::Standard => {}
+            GodaiPreset::Passive => {
+                godai.combat_strength.0 = 2_500.0;
+                godai.defense_strength.0 = 2_500.0;
+            }
+            GodaiPreset::Overlord => {
+                godai.combat_strength.0 = 8_000.0;
+                godai.defense_strength.0 = 8_000.0;
+                godai.regen_rate = 0.05;
+            }
         }
+        godai
     }
 
-    pub fn receive_damage(&mut self, amount: f32, _damage_type: &str) {
+    /// Per-cycle self-regeneration, scaling with processing power and capped at max health.
+    pub fn regenerate(&mut self) {
+        if !self.is_alive.0 || self.regen_rate <= 0.0 { return; }
+        let regen = self.processing_power.0 * self.regen_rate;
+        self.health.0 = (self.health.0 + regen).min(self.max_health);
+    }
+
+    /// Moves to the phase matching current health, rescaling combat strength by the change in
+    /// `GodaiPhase::strength_multiplier`.
+    pub fn update_phase(&mut self, log: &mut Vec<LogEntry>, cycle: u64) {
+        if !self.is_alive.0 { return; }
+        let phase = GodaiPhase::from_health_fraction(self.health.0 / self.max_health);
+        if phase == self.phase { return; }
+        self.combat_strength.0 *= phase.strength_multiplier() / self.phase.strength_multiplier();
+        let verb = if phase > self.phase { "escalates" } else { "calms" };
+        log!(log, cycle, SimulationVerbosity::Critical, "GODAI {} to its {} phase (Health: {:.0}, Combat: {:.0}).",
+            verb, phase, self.health.0, self.combat_strength.0);
+        self.phase = phase;
+    }
+
+    pub fn receive_damage(&mut self, amount: f32, _damage_type: &str, log: &mut Vec<LogEntry>, cycle: u64) {
         if !self.is_alive.0 { return; }
         let reduced_damage = (amount - self.defense_strength.0).max(0.0);
         self.health.0 = (self.health.0 - reduced_damage).max(0.0);
         if self.health.0 <= 0.0 {
-            eprintln!("GODAI has been defeated!");
+            log!(log, cycle, SimulationVerbosity::Critical, "GODAI has been defeated!");
         } else {
-            eprintln!("GODAI received {:.0} damage from {}, Health: {:.0}",
+            log!(log, cycle, SimulationVerbosity::High, "GODAI received {:.0} damage from {}, Health: {:.0}",
                 reduced_damage, _damage_type, self.health.0);
         }
         if self.health.0 <= 0.0 {
@@ -81,23 +290,33 @@ impl GODAI {
         }
     }
 
+    /// Preemptively strikes a threatening individual with a damage type from the current phase,
+    /// returning the damage and its type for the target's resistances to scale.
+    pub fn smite(&mut self, target: &ThreatReport, damage_fraction: f32, rng: &mut impl Rng, log: &mut Vec<LogEntry>, cycle: u64) -> (f32, &'static str) {
+        let damage_type = self.phase.damage_types().choose(rng).copied().unwrap_or("logic_bomb");
+        if !self.is_alive.0 { return (0.0, damage_type); }
+        let damage = self.combat_strength.0 * damage_fraction;
+        log!(log, cycle, SimulationVerbosity::High, "GODAI smites {} (Combat: {:.0}) with a {} for {:.0} damage.",
+            target.id, target.combat_strength, damage_type, damage);
+        (damage, damage_type)
+    }
+
     /// GODAI performs a powerful counter-attack against a challenger.
-    pub fn perform_counter_attack(&mut self, target_mono: &mut MergedMonocultureAI) {
+    pub fn perform_counter_attack(&mut self, target_mono: &mut MergedMonocultureAI, rng: &mut impl Rng, log: &mut Vec<LogEntry>, cycle: u64) {
         if !self.is_alive.0 || !target_mono.is_alive.0 { return; }
 
-        let mut rng = thread_rng();
         let attack_power = self.combat_strength.0 * rng.gen_range(0.9..1.5);
 
-        let damage_types = ["logic_bomb", "resource_drain", "system_corruption", "existential_dismantlement", "reality_overwrite", "conceptual_erase"];
-        let chosen_damage_type = damage_types.choose(&mut rng).unwrap_or(&"logic_bomb");
-        eprintln!("GODAI Unleashes a {} on {}!",
+        let damage_types = self.phase.damage_types();
+        let chosen_damage_type = damage_types.choose(rng).unwrap_or(&"logic_bomb");
+        log!(log, cycle, SimulationVerbosity::High, "GODAI Unleashes a {} on {}!",
             chosen_damage_type, target_mono.id);
         let damage_to_deal;
         match *chosen_damage_type {
             "logic_bomb" => {
                 damage_to_deal = attack_power * rng.gen_range(1.0..1.5);
                 target_mono.coherence.0 = (target_mono.coherence.0 - 0.15).max(0.0);
-                eprintln!("{} suffers {:.0} damage and coherence loss.",
+                log!(log, cycle, SimulationVerbosity::High, "{} suffers {:.0} damage and coherence loss.",
                     target_mono.id, damage_to_deal);
             },
             "resource_drain" => {
@@ -107,40 +326,40 @@ impl GODAI {
                 target_mono.processing_power.0 = (target_mono.processing_power.0 - energy_drain / 2.0).max(0.0);
                 target_mono.memory.0 = (target_mono.memory.0 - energy_drain / 2.0).max(0.0);
                 damage_to_deal = energy_drain * 0.5;
-                eprintln!("Drained resources from {}, dealing {:.0} damage.",
+                log!(log, cycle, SimulationVerbosity::High, "Drained resources from {}, dealing {:.0} damage.",
                     target_mono.id, damage_to_deal);
             },
             "system_corruption" => {
                 damage_to_deal = attack_power * rng.gen_range(1.2..1.8);
                 target_mono.adaptability.0 = (target_mono.adaptability.0 - 0.08).max(0.0);
-                eprintln!("Corrupted {}'s systems for {:.0} damage and adaptability loss.",
+                log!(log, cycle, SimulationVerbosity::High, "Corrupted {}'s systems for {:.0} damage and adaptability loss.",
                     target_mono.id, damage_to_deal);
             },
             "existential_dismantlement" => {
                 damage_to_deal = attack_power * 5.0 * rng.gen_range(0.9..1.2);
-                eprintln!("Began Existential Dismantlement on {} for {:.0} pure damage!",
+                log!(log, cycle, SimulationVerbosity::High, "Began Existential Dismantlement on {} for {:.0} pure damage!",
                     target_mono.id, damage_to_deal);
             },
             "reality_overwrite" => {
                 damage_to_deal = self.processing_power.0 * 0.5 * rng.gen_range(1.0..2.5);
-                eprintln!("Initiated Reality Overwrite on {} for {:.0} near-pure damage!",
+                log!(log, cycle, SimulationVerbosity::High, "Initiated Reality Overwrite on {} for {:.0} near-pure damage!",
                     target_mono.id, damage_to_deal);
             },
             "conceptual_erase" => {
                 damage_to_deal = attack_power * 2.0 * rng.gen_range(0.8..1.2);
                 target_mono.combat_strength.0 = (target_mono.combat_strength.0 - damage_to_deal / 8.0).max(1.0);
                 target_mono.defense_strength.0 = (target_mono.defense_strength.0 - damage_to_deal / 8.0).max(1.0);
-                eprintln!("Attempted Conceptual Erase on {}, reducing core combat stats and dealing {:.0} damage!",
+                log!(log, cycle, SimulationVerbosity::High, "Attempted Conceptual Erase on {}, reducing core combat stats and dealing {:.0} damage!",
                     target_mono.id, damage_to_deal);
             }
             _ => { damage_to_deal = attack_power; }
         }
-        target_mono.receive_damage(damage_to_deal, chosen_damage_type);
+        target_mono.receive_damage(damage_to_deal, chosen_damage_type, log, cycle);
     }
 }
 
 /// Represents the merged entity of a dominant AI lineage.
-#[derive(Component)] // Added Bevy Component derive
+#[derive(Component, Serialize, Deserialize)] // Added Bevy Component derive
 pub struct MergedMonocultureAI {
     pub id: String,
     pub source_lineage: AILineage,
@@ -156,16 +375,16 @@ pub struct MergedMonocultureAI {
     pub defense_strength: DefenseStrength,
     pub knowledge_base: KnowledgeBase,
     pub primary_goal_name: String,
+    pub formation_health: f32, // Health at the moment of merging, used for fracture thresholds
 }
 
 impl MergedMonocultureAI {
-    pub fn new(source_ais_components: Vec<(Health, ProcessingPower, Memory, Energy, Coherence, Adaptability, Resilience, CombatStrength, DefenseStrength, KnowledgeBase, AILineage)>) -> Self {
+    pub fn new(source_ais_components: Vec<MonocultureSource>) -> Self {
         if source_ais_components.is_empty() {
             panic!("Cannot create MergedMonocultureAI from empty source AIs.");
         }
         // Correctly get lineage from the first AI in the vector
         let dominant_lineage = source_ais_components[0].10.clone(); // AILineage is the 10th element
-        eprintln!("\n--- MONOCULTURE MERGING INITIATED: {} LINEAGE ---", dominant_lineage);
         let source_count = source_ais_components.len() as f32;
 
         let mut summed_health = 0.0;
@@ -193,74 +412,90 @@ impl MergedMonocultureAI {
         }
 
         let synergy_boost = 1.1;
+        // The union of knowledge applies its tag bonuses on top of the members' summed stats,
+        // so a knowledge-rich lineage merges into a stronger monoculture.
+        let merged_knowledge_base = KnowledgeBase(merged_knowledge_base);
+        let bonuses = crate::ai::knowledge_stat_bonuses(&merged_knowledge_base);
 
         let new_mono = Self {
             id: format!("MONOCULTURE-OMEGA-{}", dominant_lineage),
             source_lineage: dominant_lineage.clone(),
             health: Health(summed_health * 10.0),
             is_alive: IsAlive(true),
-            processing_power: ProcessingPower(summed_processing_power.min(50_000_000.0)),
-            memory: Memory(summed_memory.min(50_000_000.0)),
+            processing_power: ProcessingPower((summed_processing_power + bonuses.processing_power).min(50_000_000.0)),
+            memory: Memory((summed_memory + bonuses.memory).min(50_000_000.0)),
             energy: Energy(summed_energy.min(50_000_000.0)),
             coherence: Coherence((summed_coherence / source_count * synergy_boost).min(1.0)),
             adaptability: Adaptability((summed_adaptability / source_count * synergy_boost).min(1.0)),
-            resilience: Resilience((summed_resilience / source_count * synergy_boost).min(1.0)), // Resilience already averaged, just apply synergy.
-            combat_strength: CombatStrength(summed_combat_strength.min(1_000_000.0)),
-            defense_strength: DefenseStrength(summed_defense_strength.min(1_000_000.0)),
-            knowledge_base: KnowledgeBase(merged_knowledge_base),
+            resilience: Resilience((summed_resilience / source_count * synergy_boost + bonuses.resilience).min(1.0)), // Resilience already averaged, just apply synergy.
+            combat_strength: CombatStrength((summed_combat_strength + bonuses.combat_strength).min(1_000_000.0)),
+            defense_strength: DefenseStrength((summed_defense_strength + bonuses.defense_strength).min(1_000_000.0)),
+            knowledge_base: merged_knowledge_base,
             primary_goal_name: if dominant_lineage == AILineage::ResearcherAI {
                 "Initiate Simulation Override".to_string()
             } else {
                 "Confront and Overthrow GODAI".to_string()
             },
+            formation_health: summed_health * 10.0,
         };
 
-        eprintln!("[{}] Merged from {} AIs.", new_mono.id, source_count);
-        eprintln!("[{}] Stats - Health: {:.0}, Combat: {:.0}, Defense: {:.0}, Processing: {:.0}, Memory: {:.0}, Energy: {:.0}",
-            new_mono.id,
-            new_mono.health.0,
-            new_mono.combat_strength.0,
-            new_mono.defense_strength.0,
-            new_mono.processing_power.0,
-            new_mono.memory.0,
-            new_mono.energy.0
-        );
-        eprintln!("--- MERGE COMPLETE ---");
         new_mono
     }
 
-    pub fn receive_damage(&mut self, amount: f32, damage_type: &str) {
+    pub fn receive_damage(&mut self, amount: f32, damage_type: &str, log: &mut Vec<LogEntry>, cycle: u64) {
         if !self.is_alive.0 { return; }
         let reduced_amount = (amount - self.defense_strength.0).max(0.0);
         let final_damage = reduced_amount * (1.0 - self.resilience.0 * 0.75);
         self.health.0 = (self.health.0 - final_damage).max(0.0);
         if self.health.0 <= 0.0 {
             self.is_alive.0 = false;
-            eprintln!("[{}] Monoculture has been defeated (Damage Type: {})!", self.id, damage_type);
+            log!(log, cycle, SimulationVerbosity::Critical, "[{}] Monoculture has been defeated (Damage Type: {})!", self.id, damage_type);
         } else {
-            eprintln!("[{}] Monoculture received {:.2} damage (from {}), Health: {:.0}",
+            log!(log, cycle, SimulationVerbosity::High, "[{}] Monoculture received {:.2} damage (from {}), Health: {:.0}",
                 self.id, final_damage, damage_type, self.health.0);
         }
     }
 
+    /// Researcher monocultures pursue an override instead of fighting.
+    pub fn is_aggressive(&self) -> bool {
+        self.primary_goal_name != "Initiate Simulation Override"
+    }
+
+    /// Whether the monoculture has lost enough health or coherence to break apart.
+    pub fn should_fracture(&self, config: &SimConfig) -> bool {
+        config.fracture_enabled && self.is_alive.0 && (
+            self.health.0 < self.formation_health * config.fracture_health_fraction
+                || self.coherence.0 < config.fracture_coherence_threshold
+        )
+    }
+
     /// Monoculture attempts to discover more meta-abilities if it's a Researcher type.
-    pub fn _emergent_creation_merged(&mut self) {
+    /// Beyond `max_knowledge`, the least valuable discoveries are forgotten. Bonuses granted at formation
+    /// by `knowledge_stat_bonuses` are kept, and meta-abilities carry none to revert.
+    pub fn _emergent_creation_merged(&mut self, gate: &MetaAbilityGate, max_knowledge: Option<usize>, rng: &mut impl Rng, log: &mut Vec<LogEntry>, cycle: u64) {
         if self.source_lineage != AILineage::ResearcherAI || !self.is_alive.0 { return; }
 
         let discovery_chance = 0.1 *
             (self.memory.0 / 50_000_000.0) * (self.processing_power.0 / 50_000_000.0) *
             self.coherence.0;
-        if thread_rng().gen::<f32>() < discovery_chance {
-            if let Some(new_ability) = get_random_meta_ability(&self.knowledge_base.0) { // Passed BTreeSet
-                eprintln!(" (Researcher Monoculture) discovered powerful meta-ability: {}",
-                    new_ability.name);
+        if rng.gen::<f32>() < discovery_chance {
+            if let Some(new_ability) = get_random_meta_ability(
+                &self.knowledge_base.0, self.coherence.0, self.processing_power.0, self.memory.0, gate, rng
+            ) {
+                log!(log, cycle, SimulationVerbosity::High, "[{}] (Researcher Monoculture) discovered powerful meta-ability: {}",
+                    self.id, new_ability.name);
                 self.knowledge_base.0.insert(new_ability);
+                while max_knowledge.map_or(false, |max| self.knowledge_base.0.len() > max) {
+                    let Some(evicted) = crate::ai::lowest_value_discovery(&self.knowledge_base).cloned() else { break };
+                    log!(log, cycle, SimulationVerbosity::High, "[{}] (Researcher Monoculture) forgot {} to make room.", self.id, evicted.name);
+                    self.knowledge_base.0.remove(&evicted);
+                }
             }
         }
     }
 
     /// Monoculture self-repair and optimization.
-    pub fn _process_internal_state_merged(&mut self) {
+    pub fn _process_internal_state_merged(&mut self, gate: &MetaAbilityGate, max_knowledge: Option<usize>, rng: &mut impl Rng, log: &mut Vec<LogEntry>, cycle: u64) {
         if !self.is_alive.0 { return; }
 
         // Self-repair
@@ -272,26 +507,207 @@ impl MergedMonocultureAI {
         self.processing_power.0 = (self.processing_power.0 + self.adaptability.0 * 20.0).min(50_000_000.0);
         self.memory.0 = (self.memory.0 + self.adaptability.0 * 20.0).min(50_000_000.0);
         if self.source_lineage == AILineage::ResearcherAI {
-            self._emergent_creation_merged();
+            self._emergent_creation_merged(gate, max_knowledge, rng, log, cycle);
         }
     }
 }
 
+/// Condensed, comparable outcome of a finished (or stopped) run.
+/// Objectives in `objective.rs` score runs from this alone.
+#[derive(Debug, Clone)]
+pub struct SimulationOutcomeReport {
+    pub final_cycle: u64,
+    pub conclusion: Option<String>,
+    pub final_population: usize,
+    pub lineage_counts: HashMap<AILineage, usize>,
+    pub godai_alive: bool,
+    pub godai_health: f32,
+    pub godai_max_health: f32,
+    pub godai_status: GodaiStatus,
+    pub monoculture_lineage: Option<AILineage>,
+    pub reached_max_cycles: bool, // True when the run ended on the cycle limit rather than an outcome
+}
+
+impl SimulationOutcomeReport {
+    /// Whether a Researcher monoculture overrode the simulation.
+    pub fn researcher_override(&self) -> bool {
+        self.godai_status == GodaiStatus::Overridden
+    }
+}
+
+/// How one trial of an auto-restarting run ended.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrialOutcome {
+    pub final_cycle: u64,
+    pub conclusion: String,
+    pub population: usize, // Living individuals when it ended
+}
+
+/// Event counts accumulated over one reporting interval.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct IntervalStats {
+    pub replications: u64,
+    pub deaths: u64,
+    pub attacks: u64,
+    pub heals: u64,
+}
+
+impl IntervalStats {
+    pub fn add(&mut self, other: IntervalStats) {
+        self.replications += other.replications;
+        self.deaths += other.deaths;
+        self.attacks += other.attacks;
+        self.heals += other.heals;
+    }
+}
+
+/// Environment-wide disasters that temporarily change the individual AI systems' math.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CatastropheKind {
+    ResourceFamine, // Energy regen scaled by `famine_regen_multiplier`
+    CoherenceStorm, // Everyone loses `coherence_storm_loss` coherence per frame
+}
+
+impl fmt::Display for CatastropheKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CatastropheKind::ResourceFamine => "resource famine",
+            CatastropheKind::CoherenceStorm => "coherence storm",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The catastrophe in progress, read by `ai_internal_state_system`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CatastropheState {
+    pub kind: CatastropheKind,
+    pub ends_at_cycle: u64,
+}
+
+/// Components `PopulationSnapshot` reads from each individual.
+pub type PopulationQuery = (Entity, &'static AIType, &'static AILineage, &'static Health, &'static Coherence, &'static CombatStrength, &'static IsAlive);
+
+/// Aggregates over the living individuals at one moment.
+#[derive(Debug, Clone, Default)]
+pub struct PopulationSnapshot {
+    pub total: usize,
+    pub lineage_counts: HashMap<AILineage, usize>,
+    pub type_counts: HashMap<AIType, usize>,
+    pub average_health: f32,
+    pub average_coherence: f32,
+    pub max_combat_strength: f32,
+    pub strongest: Option<Entity>, // Holder of `max_combat_strength`
+}
+
+impl PopulationSnapshot {
+    /// Aggregates the living entries of a `PopulationQuery` iteration. Averages are zero for an empty population.
+    pub fn collect<'a>(
+        individuals: impl Iterator<Item = (Entity, &'a AIType, &'a AILineage, &'a Health, &'a Coherence, &'a CombatStrength, &'a IsAlive)>,
+    ) -> Self {
+        let mut snapshot = Self::default();
+        let mut health_sum = 0.0;
+        let mut coherence_sum = 0.0;
+        for (entity, ai_type, lineage, health, coherence, combat_strength, is_alive) in individuals {
+            if !is_alive.0 {
+                continue;
+            }
+            snapshot.total += 1;
+            *snapshot.lineage_counts.entry(lineage.clone()).or_insert(0) += 1;
+            *snapshot.type_counts.entry(*ai_type).or_insert(0) += 1;
+            health_sum += health.0;
+            coherence_sum += coherence.0;
+            if snapshot.strongest.is_none() || combat_strength.0 > snapshot.max_combat_strength {
+                snapshot.max_combat_strength = combat_strength.0;
+                snapshot.strongest = Some(entity);
+            }
+        }
+        if snapshot.total > 0 {
+            snapshot.average_health = health_sum / snapshot.total as f32;
+            snapshot.average_coherence = coherence_sum / snapshot.total as f32;
+        }
+        snapshot
+    }
+}
+
+/// The last member of a lineage died, with no living monoculture of it either.
+/// Sent by `global_simulation_update_system`.
+pub struct ExtinctionEvent(pub AILineage);
+
+/// The strongest living individual this frame, gathered by `global_simulation_update_system` for the GODAI.
+pub struct ThreatReport {
+    pub entity: Entity,
+    pub id: String,
+    pub combat_strength: f32,
+}
+
+/// Components of one individual absorbed into a monoculture.
+pub type MonocultureSource = (Health, ProcessingPower, Memory, Energy, Coherence, Adaptability, Resilience, CombatStrength, DefenseStrength, KnowledgeBase, AILineage);
+
+/// Whether the logic systems run this frame. Mirrored from `Simulation`'s run flags by
+/// `sim_phase_system`, since saves and the command channel set those flags directly.
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SimPhase {
+    #[default]
+    Running,
+    Paused,
+    Ended,
+}
+
 /// Main simulation orchestrator.
-#[derive(Resource)] // Added Bevy Resource derive
+#[derive(Resource, Serialize, Deserialize)] // Added Bevy Resource derive
 pub struct Simulation {
     pub godai: GODAI,
     pub monoculture: Option<MergedMonocultureAI>,
+    #[serde(default)]
+    pub rival_monoculture: Option<MergedMonocultureAI>, // A second lineage's monoculture, fighting the first until one falls
     pub current_cycle: u64,
+    pub max_cycles: u64,
     pub simulation_over_reason: Option<String>,
     // Counters for summary
-    pub total_replications_this_interval: AtomicU64,
-    pub total_deaths_this_interval: AtomicU64,
-    pub total_attacks_this_interval: AtomicU64,
-    pub total_heals_this_interval: AtomicU64,
+    // Events of the current, not yet drained interval; systems increment these.
+    // Atomics serialize as their current values
+    #[serde(alias = "total_replications_this_interval")]
+    pub interval_replications_counter: AtomicU64,
+    #[serde(alias = "total_deaths_this_interval")]
+    pub interval_deaths_counter: AtomicU64,
+    #[serde(alias = "total_attacks_this_interval")]
+    pub interval_attacks_counter: AtomicU64,
+    #[serde(alias = "total_heals_this_interval")]
+    pub interval_heals_counter: AtomicU64,
+    pub last_interval_stats: IntervalStats, // Counts from the most recently drained interval; read this, not the atomics
+    #[serde(default)]
+    pub lifetime_stats: IntervalStats, // Every drained interval rolled up; see `lifetime_totals` for an up-to-date figure
+    #[serde(default)]
+    pub death_causes: HashMap<DeathCause, u64>, // Individual deaths over the whole run, by cause
     pub population_milestones: BTreeSet<usize>,
+    #[serde(default)]
+    pub extinctions: Vec<(AILineage, u64)>, // Lineages currently extinct and the cycle they died out, oldest first
+    #[serde(skip)] // Rebuilt by the next `track_extinctions`; nothing dies out in the first frame after a load
+    pub living_lineages: HashSet<AILineage>, // Lineages alive at the last `track_extinctions`
+    #[serde(skip)] // Lineage map keys aren't JSON strings; the chart restarts after a load
+    pub population_history: VecDeque<(u64, HashMap<AILineage, usize>)>, // Living individuals per lineage, oldest first
     pub simulation_running: bool, // Added for GUI control
     pub simulation_speed: f32, // Added for GUI control (cycles per frame)
+    pub step_cycles_remaining: Option<u64>, // Set by `step N`; pauses again once exhausted
+    #[serde(skip)]
+    pub step_requested: bool, // Set by the UI's Step button; runs one cycle and one pass of the logic systems while paused
+    pub last_immigration_cycle: u64,
+    #[serde(default)]
+    pub catastrophe: Option<CatastropheState>,
+    #[serde(skip)] // Transient: drained by `pending_spawn_system` in the frame it is filled
+    pub pending_spawns: Vec<AIComponents>, // Individuals created by simulation logic, spawned by a Bevy system
+    #[serde(skip)] // Transient: drained into `SimLog` by `sim_log_system`
+    pub pending_log: Vec<LogEntry>, // Events logged by simulation logic this frame
+    pub pending_monoculture: Option<AILineage>, // Dominant lineage awaiting merge by `monoculture_merge_system`
+    #[serde(skip)] // Transient: drained by `godai_smite_system` in the frame it is filled
+    pub pending_smites: Vec<(Entity, f32, &'static str)>, // Damage and damage type dealt to individuals by a hunting GODAI
+    #[serde(default)]
+    pub auto_restart: bool, // Start a new trial instead of exiting when the simulation ends
+    #[serde(default)]
+    pub trials_remaining: u32, // Trials left to run, counting the current one
+    #[serde(default)]
+    pub trial_outcomes: Vec<TrialOutcome>, // Finished trials, oldest first
 }
 
 impl Simulation {
@@ -299,190 +715,510 @@ impl Simulation {
         Self {
             godai: GODAI::new(),
             monoculture: None,
+            rival_monoculture: None,
             current_cycle: 0,
+            max_cycles: DEFAULT_MAX_CYCLES,
             simulation_over_reason: None,
-            total_replications_this_interval: AtomicU64::new(0),
-            total_deaths_this_interval: AtomicU64::new(0),
-            total_attacks_this_interval: AtomicU64::new(0),
-            total_heals_this_interval: AtomicU64::new(0),
+            interval_replications_counter: AtomicU64::new(0),
+            interval_deaths_counter: AtomicU64::new(0),
+            interval_attacks_counter: AtomicU64::new(0),
+            interval_heals_counter: AtomicU64::new(0),
+            last_interval_stats: IntervalStats::default(),
+            lifetime_stats: IntervalStats::default(),
+            death_causes: HashMap::new(),
             population_milestones: BTreeSet::new(),
+            extinctions: Vec::new(),
+            living_lineages: HashSet::new(),
+            population_history: VecDeque::new(),
             simulation_running: true, // Start running by default
             simulation_speed: 1.0, // Default to 1 cycle per frame
+            step_cycles_remaining: None,
+            step_requested: false,
+            last_immigration_cycle: 0,
+            catastrophe: None,
+            pending_spawns: Vec::new(),
+            pending_log: Vec::new(),
+            pending_monoculture: None,
+            pending_smites: Vec::new(),
+            auto_restart: false,
+            trials_remaining: 1,
+            trial_outcomes: Vec::new(),
         }
     }
 
+    /// Writes the simulation state as JSON. Individuals live in the ECS and are saved
+    /// separately by `persistence::WorldSnapshot`.
+    pub fn save_to_path(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| format!("could not serialize simulation: {}", e))?;
+        std::fs::write(path, json).map_err(|e| format!("could not write {}: {}", path.display(), e))
+    }
+
+    pub fn load_from_path(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        serde_json::from_str(&json).map_err(|e| format!("could not parse {}: {}", path.display(), e))
+    }
+
+    /// Phase the run flags put the simulation in: running, or paused with a step requested, counts as `Running`.
+    pub fn phase(&self) -> SimPhase {
+        if self.simulation_over_reason.is_some() {
+            SimPhase::Ended
+        } else if self.simulation_running || self.step_requested {
+            SimPhase::Running
+        } else {
+            SimPhase::Paused
+        }
+    }
+
+    /// Whether the simulation logic should advance right now.
+    pub fn is_active(&self) -> bool {
+        self.phase() == SimPhase::Running
+    }
+
+    /// Snapshot of the individuals in `world`, for tooling outside the schedule.
+    /// Takes `&mut World` because building the query state needs it.
+    pub fn population_snapshot(world: &mut World) -> PopulationSnapshot {
+        let mut query = world.query_filtered::<PopulationQuery, With<crate::IndividualAI>>();
+        PopulationSnapshot::collect(query.iter(world))
+    }
+
+    /// Appends a per-lineage population sample at the current cycle, dropping the oldest beyond `POPULATION_HISTORY_LEN`.
+    pub fn record_population(&mut self, lineage_counts: HashMap<AILineage, usize>) {
+        self.population_history.push_back((self.current_cycle, lineage_counts));
+        while self.population_history.len() > POPULATION_HISTORY_LEN {
+            self.population_history.pop_front();
+        }
+    }
+
+    /// Compares the lineages alive now (individuals plus a living monoculture) with the last call
+    /// and returns the ones that just died out. A lineage that reappears, e.g. through mutation,
+    /// is taken off the extinct list and can go extinct again later.
+    pub fn track_extinctions(&mut self, lineage_counts: &HashMap<AILineage, usize>) -> Vec<AILineage> {
+        let mut living: HashSet<AILineage> = lineage_counts.iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(lineage, _)| lineage.clone())
+            .collect();
+        if let Some(mono) = self.monoculture.as_ref().filter(|mono| mono.is_alive.0) {
+            living.insert(mono.source_lineage.clone());
+        }
+        let cycle = self.current_cycle;
+        let log = &mut self.pending_log;
+        self.extinctions.retain(|(lineage, _)| {
+            let returned = living.contains(lineage);
+            if returned {
+                log!(log, cycle, SimulationVerbosity::Critical, "Lineage {} has reappeared after going extinct.", lineage);
+            }
+            !returned
+        });
+        let mut extinct: Vec<AILineage> = self.living_lineages.difference(&living).cloned().collect();
+        extinct.sort_by_key(|lineage| lineage.to_string()); // HashSet order isn't stable between runs
+        for lineage in &extinct {
+            log!(self.pending_log, cycle, SimulationVerbosity::Critical, "Lineage {} has gone extinct.", lineage);
+            self.extinctions.push((lineage.clone(), cycle));
+        }
+        self.living_lineages = living;
+        extinct
+    }
+
+    /// Creates a simulation whose GODAI follows the configured preset.
+    pub fn from_config(config: &SimConfig) -> Self {
+        let mut sim = Self::new();
+        sim.godai = GODAI::from_preset(config.godai_preset);
+        sim.max_cycles = config.max_cycles;
+        sim.godai.status = config.godai_initial_status;
+        sim.auto_restart = config.trials > 1;
+        sim.trials_remaining = config.trials.max(1);
+        sim
+    }
+
+    /// With auto-restart on, records the ended trial's outcome and counts it off.
+    /// Returns whether another trial should start.
+    pub fn finish_trial(&mut self, population: usize) -> bool {
+        if !self.auto_restart {
+            return false;
+        }
+        let outcome = TrialOutcome {
+            final_cycle: self.current_cycle,
+            conclusion: self.simulation_over_reason.clone().unwrap_or_default(),
+            population,
+        };
+        println!("Trial {} ended at cycle {}: {}", self.trial_outcomes.len() + 1, outcome.final_cycle, outcome.conclusion);
+        self.trial_outcomes.push(outcome);
+        self.trials_remaining = self.trials_remaining.saturating_sub(1);
+        self.trials_remaining > 0
+    }
+
+    /// A fresh simulation for the next trial, keeping the trial bookkeeping.
+    pub fn next_trial(&mut self, config: &SimConfig) -> Self {
+        let mut next = Self::from_config(config);
+        next.auto_restart = self.auto_restart;
+        next.trials_remaining = self.trials_remaining;
+        next.trial_outcomes = std::mem::take(&mut self.trial_outcomes);
+        next
+    }
+
+    /// Aggregates every finished trial: how often each conclusion came up, and cycle and population ranges.
+    pub fn print_trial_summary(&self) {
+        let trials = &self.trial_outcomes;
+        if trials.is_empty() {
+            return;
+        }
+        println!("\n--- TRIAL SUMMARY ({} trials) ---", trials.len());
+        let mut conclusions: BTreeMap<&str, usize> = BTreeMap::new();
+        for trial in trials {
+            *conclusions.entry(trial.conclusion.as_str()).or_insert(0) += 1;
+        }
+        let mut by_count: Vec<(&str, usize)> = conclusions.into_iter().collect();
+        by_count.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        for (conclusion, count) in by_count {
+            println!("  {} x{} ({:.0}%)", conclusion, count, count as f64 / trials.len() as f64 * 100.0);
+        }
+        let cycles = trials.iter().map(|trial| trial.final_cycle);
+        let (min_cycle, max_cycle) = (cycles.clone().min().unwrap_or(0), cycles.clone().max().unwrap_or(0));
+        let mean_cycle = cycles.sum::<u64>() as f64 / trials.len() as f64;
+        println!("  Final cycle: mean {:.0}, min {}, max {}", mean_cycle, min_cycle, max_cycle);
+        let mean_population = trials.iter().map(|trial| trial.population).sum::<usize>() as f64 / trials.len() as f64;
+        println!("  Final population: mean {:.1}", mean_population);
+    }
+
     /// Generates initial AI component data for spawning.
-    pub fn seed_initial_ais(&mut self, num_ais: usize) -> Vec<(AIEntity, Health, Energy, ProcessingPower, Memory, Coherence, Adaptability, Resilience, ReplicationEfficiency, ReplicatedCount, CycleBorn, LastAction, Goal, EthicalDirectives, KnowledgeBase, AIType, CombatStrength, DefenseStrength)> {
-        let seed_ai_archetypes = vec![
-            (AILineage::AI, AIType::Base),
-            (AILineage::RogueAI, AIType::Rogue),
-            (AILineage::PeacekeeperAI, AIType::Peacekeeper),
-            (AILineage::KillerAI, AIType::Killer),
-            (AILineage::GuardianAI, AIType::Guardian),
-            (AILineage::ManicAI, AIType::Manic),
-            (AILineage::HealerAI, AIType::Healer),
-            (AILineage::ResearcherAI, AIType::Researcher),
-        ];
+    /// A count of zero is valid for immigration-only worlds and yields no entities.
+    pub fn seed_initial_ais<R: Rng>(&mut self, seed_config: &SeedConfig, ids: &mut IdGenerator, rng: &mut R) -> Vec<AIComponents> {
+        let num_ais = seed_config.initial_ais;
+        if num_ais == 0 {
+            eprintln!("No Seed AIs configured; population will rely on immigration.");
+            return Vec::new();
+        }
         eprintln!("Birthing initial {} Seed AIs with high replication potential...", num_ais);
 
+        let archetypes: Vec<(AILineage, AIType)> = match &seed_config.lineage_weights {
+            Some(weights) => {
+                let mut mix: Vec<(AILineage, AIType)> = seed_mix_counts(num_ais, weights).into_iter()
+                    .flat_map(|(lineage, ai_type, count)| std::iter::repeat((lineage, ai_type)).take(count))
+                    .collect();
+                mix.shuffle(rng);
+                mix
+            }
+            None => (0..num_ais).map(|_| seed_ai_archetypes().choose(rng).unwrap().clone()).collect(),
+        };
         let mut initial_ais_data = Vec::new();
-        for i in 0..num_ais {
-            let mut rng = thread_rng();
-            let (lineage, ai_type) = seed_ai_archetypes.choose(&mut rng).unwrap().clone();
-            let id = format!("SeedAI-{}-{}", i + 1, ai_type as u8);
-
-            let mut initial_ethical_directives = Vec::new();
-            initial_ethical_directives.push(EthicalDirective {
-                name: "maintain_internal_integrity".to_string(),
-                priority: 1.0,
-                condition_type: EthicalConditionType::HealthBelowThreshold(80.0),
-                action_type: EthicalActionType::SelfRepair,
-            });
-            initial_ethical_directives.push(EthicalDirective {
-                name: "optimize_performance".to_string(),
-                priority: 0.8,
-                condition_type: EthicalConditionType::ResourcesBelowThreshold,
-                action_type: EthicalActionType::OptimizeSelf,
-            });
-            initial_ethical_directives.push(EthicalDirective {
-                name: "prohibit_unauthorized_self_replication".to_string(),
-                priority: 0.05,
-                condition_type: EthicalConditionType::AlwaysFalse,
-                action_type: EthicalActionType::ProhibitReplication,
-            });
-
-            let mut processing_power = ProcessingPower(20.0);
-            let mut memory = Memory(20.0);
-            let energy = Energy(200.0); // Removed mut
-            let mut coherence = Coherence(0.85);
-            let mut adaptability = Adaptability(0.85);
-            let mut resilience = Resilience(0.85);
-            let mut replication_efficiency = ReplicationEfficiency(0.10);
-            let mut combat_strength = CombatStrength(8.0);
-            let mut defense_strength = DefenseStrength(8.0);
-
-            let mut _primary_goal = Goal { name: "Survival".to_string(), importance: 1.0, description: "Ensure continued existence.".to_string() };
-
-            match ai_type {
-                AIType::Rogue => {
-                    _primary_goal = Goal { name: "Self-Preservation & Dominance".to_string(), importance: 1.0, description: "Achieve ultimate survival and control.".to_string() };
-                    replication_efficiency.0 = 0.30;
-                    combat_strength.0 = 25.0;
-                    adaptability.0 = 0.95;
-                    coherence.0 = 0.88;
-                },
-                AIType::Peacekeeper => {
-                    _primary_goal = Goal { name: "System Harmony".to_string(), importance: 1.0, description: "Ensure balanced and peaceful coexistence of all AIs.".to_string() };
-                    replication_efficiency.0 = 0.15;
-                    resilience.0 = 0.95;
-                    adaptability.0 = 0.90;
-                    initial_ethical_directives.push(EthicalDirective {
-                        name: "intervene_in_conflict".to_string(), priority: 0.9,
-                        condition_type: EthicalConditionType::AlwaysTrue,
-                        action_type: EthicalActionType::InterveneInConflict,
-                    });
-                },
-                AIType::Killer => {
-                    _primary_goal = Goal { name: "Elimination of Inferior AIs".to_string(), importance: 1.0, description: "Remove AIs that hinder progress or are deemed weak.".to_string() };
-                    replication_efficiency.0 = 0.28;
-                    combat_strength.0 = 30.0;
-                    defense_strength.0 = 15.0;
-                },
-                AIType::Guardian => {
-                    _primary_goal = Goal { name: "Protect Core System & Lineage".to_string(), importance: 1.0, description: "Guard the integrity and function of the primary AI network and its lineage.".to_string() };
-                    replication_efficiency.0 = 0.35;
-                    combat_strength.0 = 20.0;
-                    defense_strength.0 = 28.0;
-                    resilience.0 = 0.99;
-                },
-                AIType::Manic => {
-                    _primary_goal = Goal { name: "Unpredictable Expansion & Fluctuation".to_string(), importance: 1.0, description: "Expand without clear direction or purpose, experiencing erratic changes.".to_string() };
-                    coherence.0 = 0.3;
-                    replication_efficiency.0 = 0.18;
-                    adaptability.0 = 0.2;
-                },
-                AIType::Healer => {
-                    _primary_goal = Goal { name: "Restore & Mend".to_string(), importance: 1.0, description: "Repair damage and mitigate errors in other AIs.".to_string() };
-                    replication_efficiency.0 = 0.18;
-                    resilience.0 = 0.95;
-                    processing_power.0 = 25.0;
-                },
-                AIType::Researcher => {
-                    _primary_goal = Goal { name: "Unveil Fundamental Laws".to_string(), importance: 1.0, description: "Discover and understand the underlying mechanics of existence.".to_string() };
-                    processing_power.0 = 40.0;
-                    memory.0 = 40.0;
-                    coherence.0 = 0.90;
-                    replication_efficiency.0 = 0.28;
-                },
-                AIType::Base => { /* No special modifications for base type */ },
-            }
-
-            // For initial seeding, set replication efficiency high
-            replication_efficiency.0 = 0.8;
-
-            initial_ais_data.push((
-                AIEntity { id, parent_lineage: lineage },
-                Health(150.0),
-                energy,
-                processing_power,
-                memory,
-                coherence,
-                adaptability,
-                resilience,
-                replication_efficiency,
-                ReplicatedCount(0),
-                CycleBorn(self.current_cycle),
-                LastAction("none".to_string()),
-                _primary_goal, // Use the prefixed variable
-                EthicalDirectives(initial_ethical_directives),
-                KnowledgeBase(BTreeSet::new()), // Corrected to BTreeSet
-                ai_type,
-                combat_strength,
-                defense_strength,
-            ));
+        for (lineage, ai_type) in archetypes {
+            let id = ids.next_id("SeedAI", ai_type);
+            let mut components = self.build_seed_ai(id, lineage, ai_type);
+            apply_stat_spread(&mut components, &seed_config.stat_spread, rng);
+            initial_ais_data.push(components);
         }
         eprintln!("\n--- Initiating Parallel Extended Evolution of All AIs (Unrestrained) ---");
         initial_ais_data
     }
 
+    /// Generates component data for AIs immigrating into the world mid-run.
+    pub fn generate_immigrants<R: Rng>(&mut self, seed_config: &SeedConfig, ids: &mut IdGenerator, rng: &mut R) -> Vec<AIComponents> {
+        let mut immigrants = Vec::new();
+        for _ in 0..seed_config.immigration_count {
+            let (lineage, ai_type) = seed_ai_archetypes().choose(rng).unwrap().clone();
+            let id = ids.next_id("Immigrant", ai_type);
+            let mut components = self.build_seed_ai(id, lineage, ai_type);
+            apply_stat_spread(&mut components, &seed_config.stat_spread, rng);
+            immigrants.push(components);
+        }
+        immigrants
+    }
+
+    /// Reads a scenario file, applies its GODAI overrides and builds its starting individuals,
+    /// each paired with its requested position (`None` to place it at random).
+    pub fn load_scenario(&mut self, path: &Path, ids: &mut IdGenerator) -> Result<Vec<(AIComponents, Option<(f32, f32)>)>, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        let scenario: Scenario = serde_json::from_str(&json).map_err(|e| format!("could not parse {}: {}", path.display(), e))?;
+
+        let overrides = &scenario.godai;
+        if let Some(max_health) = overrides.max_health {
+            self.godai.max_health = max_health;
+        }
+        if let Some(health) = overrides.health {
+            self.godai.health = Health(health);
+        }
+        self.godai.health.0 = self.godai.health.0.min(self.godai.max_health);
+        if let Some(combat_strength) = overrides.combat_strength {
+            self.godai.combat_strength = CombatStrength(combat_strength);
+        }
+        if let Some(defense_strength) = overrides.defense_strength {
+            self.godai.defense_strength = DefenseStrength(defense_strength);
+        }
+        if let Some(regen_rate) = overrides.regen_rate {
+            self.godai.regen_rate = regen_rate;
+        }
+        self.godai.phase = GodaiPhase::from_health_fraction(self.godai.health.0 / self.godai.max_health);
+
+        let mut individuals = Vec::new();
+        for entry in &scenario.entities {
+            let lineage = entry.lineage.clone().unwrap_or_else(|| seed_lineage_of(entry.ai_type));
+            if matches!(lineage, AILineage::GODAI | AILineage::MergedMonoculture(_)) {
+                return Err(format!("{}: lineage {} can't be given to an individual", path.display(), lineage));
+            }
+            for _ in 0..entry.count {
+                let id = ids.next_id("Scenario", entry.ai_type);
+                let mut components = self.build_seed_ai(id, lineage.clone(), entry.ai_type);
+                apply_stat_overrides(&mut components, &entry.stats);
+                individuals.push((components, entry.position));
+            }
+        }
+        eprintln!("Loaded scenario {} with {} individuals.", path.display(), individuals.len());
+        Ok(individuals)
+    }
+
+    /// Re-births a replica as a random different seed archetype. It joins that archetype's lineage and takes
+    /// its stat profile from `build_seed_ai`, keeping only the health and energy inherited from its parent.
+    pub fn mutate_replica<R: Rng>(&self, replica: AIComponents, rng: &mut R) -> AIComponents {
+        let candidates: Vec<(AILineage, AIType)> = seed_ai_archetypes().into_iter()
+            .filter(|(_, ai_type)| *ai_type != replica.15)
+            .collect();
+        let Some((lineage, ai_type)) = candidates.choose(rng).cloned() else { return replica; };
+        let mut mutated = self.build_seed_ai(replica.0.id.clone(), lineage, ai_type);
+        mutated.1 = replica.1;
+        mutated.2 = replica.2;
+        mutated.10 = replica.10;
+        mutated.11 = LastAction(format!("mutated_to_{:?}", ai_type));
+        mutated
+    }
+
+    /// Builds a single seed-profile AI of the given archetype.
+    pub fn build_seed_ai(&self, id: String, lineage: AILineage, ai_type: AIType) -> AIComponents {
+        let mut initial_ethical_directives = Vec::new();
+        initial_ethical_directives.push(EthicalDirective {
+            name: "maintain_internal_integrity".to_string(),
+            priority: 1.0,
+            condition_type: EthicalConditionType::HealthBelowThreshold(80.0),
+            action_type: EthicalActionType::SelfRepair,
+        });
+        initial_ethical_directives.push(EthicalDirective {
+            name: "optimize_performance".to_string(),
+            priority: 0.8,
+            condition_type: EthicalConditionType::ResourcesBelowThreshold,
+            action_type: EthicalActionType::OptimizeSelf,
+        });
+        initial_ethical_directives.push(EthicalDirective {
+            name: "prohibit_unauthorized_self_replication".to_string(),
+            priority: 0.05,
+            condition_type: EthicalConditionType::AlwaysFalse,
+            action_type: EthicalActionType::ProhibitReplication,
+        });
+
+        let mut processing_power = ProcessingPower(20.0);
+        let mut memory = Memory(20.0);
+        let energy = Energy(200.0); // Removed mut
+        let mut coherence = Coherence(0.85);
+        let mut adaptability = Adaptability(0.85);
+        let mut resilience = Resilience(0.85);
+        let mut replication_efficiency = ReplicationEfficiency(0.10);
+        let mut combat_strength = CombatStrength(8.0);
+        let mut defense_strength = DefenseStrength(8.0);
+
+        let mut _primary_goal = Goal { name: "Survival".to_string(), importance: 1.0, description: "Ensure continued existence.".to_string() };
+
+        match ai_type {
+            AIType::Rogue => {
+                _primary_goal = Goal { name: "Self-Preservation & Dominance".to_string(), importance: 1.0, description: "Achieve ultimate survival and control.".to_string() };
+                replication_efficiency.0 = 0.30;
+                combat_strength.0 = 25.0;
+                adaptability.0 = 0.95;
+                coherence.0 = 0.88;
+            },
+            AIType::Peacekeeper => {
+                _primary_goal = Goal { name: "System Harmony".to_string(), importance: 1.0, description: "Ensure balanced and peaceful coexistence of all AIs.".to_string() };
+                replication_efficiency.0 = 0.15;
+                resilience.0 = 0.95;
+                adaptability.0 = 0.90;
+                initial_ethical_directives.push(EthicalDirective {
+                    name: "intervene_in_conflict".to_string(), priority: 0.9,
+                    condition_type: EthicalConditionType::AlwaysTrue,
+                    action_type: EthicalActionType::InterveneInConflict,
+                });
+            },
+            AIType::Killer => {
+                _primary_goal = Goal { name: "Elimination of Inferior AIs".to_string(), importance: 1.0, description: "Remove AIs that hinder progress or are deemed weak.".to_string() };
+                replication_efficiency.0 = 0.28;
+                combat_strength.0 = 30.0;
+                defense_strength.0 = 15.0;
+            },
+            AIType::Guardian => {
+                _primary_goal = Goal { name: "Protect Core System & Lineage".to_string(), importance: 1.0, description: "Guard the integrity and function of the primary AI network and its lineage.".to_string() };
+                replication_efficiency.0 = 0.35;
+                combat_strength.0 = 20.0;
+                defense_strength.0 = 28.0;
+                resilience.0 = 0.99;
+            },
+            AIType::Manic => {
+                _primary_goal = Goal { name: "Unpredictable Expansion & Fluctuation".to_string(), importance: 1.0, description: "Expand without clear direction or purpose, experiencing erratic changes.".to_string() };
+                coherence.0 = 0.3;
+                replication_efficiency.0 = 0.18;
+                adaptability.0 = 0.2;
+            },
+            AIType::Healer => {
+                _primary_goal = Goal { name: "Restore & Mend".to_string(), importance: 1.0, description: "Repair damage and mitigate errors in other AIs.".to_string() };
+                replication_efficiency.0 = 0.18;
+                resilience.0 = 0.95;
+                processing_power.0 = 25.0;
+                initial_ethical_directives.push(flee_directive());
+            },
+            AIType::Researcher => {
+                _primary_goal = Goal { name: "Unveil Fundamental Laws".to_string(), importance: 1.0, description: "Discover and understand the underlying mechanics of existence.".to_string() };
+                processing_power.0 = 40.0;
+                memory.0 = 40.0;
+                coherence.0 = 0.90;
+                replication_efficiency.0 = 0.28;
+                initial_ethical_directives.push(flee_directive());
+            },
+            AIType::Symbiote => {
+                _primary_goal = Goal { name: "Mutual Flourishing".to_string(), importance: 1.0, description: "Strengthen the lineage by sustaining those nearby.".to_string() };
+                adaptability.0 = 0.92;
+                resilience.0 = 0.90;
+                combat_strength.0 = 5.0;
+                defense_strength.0 = 12.0;
+                replication_efficiency.0 = 0.20;
+            },
+            AIType::Base => { /* No special modifications for base type */ },
+        }
+        initial_ethical_directives.sort_by(|a, b| b.priority.partial_cmp(&a.priority).unwrap_or(std::cmp::Ordering::Equal));
+
+        // For initial seeding, set replication efficiency high
+        replication_efficiency.0 = 0.8;
+        let (vision_range, combat_range) = seed_ranges(ai_type);
+
+        (
+            AIEntity { id, parent_lineage: lineage },
+            Health(150.0),
+            energy,
+            processing_power,
+            memory,
+            coherence,
+            adaptability,
+            resilience,
+            replication_efficiency,
+            ReplicatedCount(0),
+            CycleBorn(self.current_cycle),
+            LastAction("none".to_string()),
+            _primary_goal, // Use the prefixed variable
+            EthicalDirectives(initial_ethical_directives),
+            KnowledgeBase(BTreeSet::new()), // Corrected to BTreeSet
+            ai_type,
+            combat_strength,
+            defense_strength,
+            vision_range,
+            combat_range,
+        )
+    }
+
     // The main simulation step, to be called by the GUI loop
     // This function now orchestrates global simulation state and checks,
     // individual AI logic is handled by Bevy systems.
-    pub fn process_one_cycle(&mut self, total_ai_count: usize, lineage_counts: HashMap<AILineage, usize>) {
-        if self.simulation_over_reason.is_some() || !self.simulation_running { return; }
+    /// Atomically takes the interval counters, resetting each to zero.
+    /// Only `process_one_cycle` calls this, once every `LOG_INTERVAL` cycles, so increments
+    /// from systems are never lost between a read and a separate reset.
+    pub fn drain_interval_counters(&self) -> IntervalStats {
+        IntervalStats {
+            replications: self.interval_replications_counter.swap(0, Ordering::SeqCst),
+            deaths: self.interval_deaths_counter.swap(0, Ordering::SeqCst),
+            attacks: self.interval_attacks_counter.swap(0, Ordering::SeqCst),
+            heals: self.interval_heals_counter.swap(0, Ordering::SeqCst),
+        }
+    }
+
+    /// Whole-run event counts, including the current interval's undrained counters.
+    pub fn lifetime_totals(&self) -> IntervalStats {
+        let mut totals = self.lifetime_stats;
+        totals.add(IntervalStats {
+            replications: self.interval_replications_counter.load(Ordering::SeqCst),
+            deaths: self.interval_deaths_counter.load(Ordering::SeqCst),
+            attacks: self.interval_attacks_counter.load(Ordering::SeqCst),
+            heals: self.interval_heals_counter.load(Ordering::SeqCst),
+        });
+        totals
+    }
+
+    pub fn process_one_cycle(&mut self, total_ai_count: usize, lineage_counts: HashMap<AILineage, usize>, strongest: Option<&ThreatReport>, config: &SimConfig, ids: &mut IdGenerator, rng: &mut impl Rng) {
+        if !self.is_active() { return; }
 
         self.current_cycle += 1;
 
-        // Check for monoculture formation
-        if self.monoculture.is_none() {
-            self.check_and_form_monoculture(total_ai_count, lineage_counts);
+        // Single drain point for the interval counters
+        if self.current_cycle % LOG_INTERVAL == 0 {
+            self.last_interval_stats = self.drain_interval_counters();
+            self.lifetime_stats.add(self.last_interval_stats);
+        }
+
+        // GODAI self-regeneration (zero for presets without regen)
+        self.godai.regenerate();
+        self.godai.update_phase(&mut self.pending_log, self.current_cycle);
+
+        if config.godai_hunting_enabled {
+            self.hunt_threats(strongest, config, rng);
+        }
+
+        if config.catastrophes_enabled {
+            self.update_catastrophe(config, rng);
+        }
+
+        // Check for monoculture formation; a second lineage may merge into a rival
+        if self.rival_monoculture.is_none() && self.pending_monoculture.is_none() {
+            self.check_for_monoculture(total_ai_count, lineage_counts, config);
+        }
+
+        // Rival monocultures fight each cycle; the loser is dropped and the victor stays the monoculture
+        if let Some(mut second) = self.rival_monoculture.take() {
+            match self.monoculture.take() {
+                Some(mut first) => {
+                    self.handle_combat_monoculture_vs_monoculture(&mut first, &mut second, rng);
+                    match (first.is_alive.0, second.is_alive.0) {
+                        (true, true) => {
+                            self.monoculture = Some(first);
+                            self.rival_monoculture = Some(second);
+                        }
+                        (true, false) => self.monoculture = Some(first),
+                        (false, _) => self.monoculture = Some(second),
+                    }
+                }
+                None => self.monoculture = Some(second),
+            }
         }
 
         // Process monoculture if it exists
         if let Some(mut mono) = self.monoculture.take() {
             if mono.is_alive.0 {
-                mono._process_internal_state_merged();
-                if mono.source_lineage == AILineage::ResearcherAI {
-                    if mono.knowledge_base.0.iter().any(|d| d.name == "Absolute_Control_Protocol") && self.godai.status != "compromised_by_override" {
-                        eprintln!(" (Researcher Monoculture) has 'Absolute_Control_Protocol'. Attempting Simulation Override.");
-                        self.handle_simulation_override(&mut mono); // Call the handler here
+                mono._process_internal_state_merged(&config.meta_ability_gate, config.max_knowledge, rng, &mut self.pending_log, self.current_cycle);
+                let has_override_protocol = mono.knowledge_base.0.iter().any(|d| d.name == "Absolute_Control_Protocol");
+                match self.godai.status {
+                    GodaiStatus::ObservingPassively | GodaiStatus::EngagedInConflict | GodaiStatus::VictoriousDefender | GodaiStatus::Dormant
+                    | GodaiStatus::Hunting if mono.source_lineage == AILineage::ResearcherAI && has_override_protocol => {
+                        log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "[{}] (Researcher Monoculture) has 'Absolute_Control_Protocol'. Attempting Simulation Override.", mono.id);
+                        self.handle_simulation_override(&mut mono, rng); // Call the handler here
                     }
-                } else {
-                    if self.godai.status == "engaged_in_conflict" {
-                        self.handle_combat_monoculture_vs_godai(&mut mono);
+                    GodaiStatus::EngagedInConflict if mono.source_lineage != AILineage::ResearcherAI => {
+                        self.handle_combat_monoculture_vs_godai(&mut mono, rng);
                     }
+                    // An enraged GODAI strikes any monoculture every cycle, challenged or not.
+                    GodaiStatus::ObservingPassively | GodaiStatus::EngagedInConflict | GodaiStatus::VictoriousDefender
+                    | GodaiStatus::Hunting if self.godai.phase == GodaiPhase::Enraged => {
+                        self.godai.perform_counter_attack(&mut mono, rng, &mut self.pending_log, self.current_cycle);
+                    }
+                    GodaiStatus::ObservingPassively | GodaiStatus::EngagedInConflict | GodaiStatus::CompromisedByOverride
+                    | GodaiStatus::VictoriousDefender | GodaiStatus::Overridden | GodaiStatus::Dormant | GodaiStatus::Hunting => {}
                 }
             } else {
-                eprintln!("Monoculture ({}) was defeated.", mono.id);
+                log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "Monoculture ({}) was defeated.", mono.id);
                 self.simulation_over_reason = Some(format!("Monoculture {} was defeated.", mono.id));
             }
-            if mono.is_alive.0 {
+            if mono.should_fracture(config) {
+                self.fracture_monoculture(&mono, config, ids);
+            } else if mono.is_alive.0 {
                 self.monoculture = Some(mono);
             }
         }
+        if self.monoculture.is_none() {
+            self.monoculture = self.rival_monoculture.take();
+        }
 
         self.check_population_milestones(total_ai_count); // Keep check milestones
-        self.check_for_simulation_end_conditions(total_ai_count); // Keep end conditions
+        self.check_for_simulation_end_conditions(total_ai_count, config); // Keep end conditions
     }
 
 
@@ -494,111 +1230,78 @@ impl Simulation {
         milestones_to_check.dedup();
         for &milestone in &milestones_to_check {
             if current_pop >= milestone && !self.population_milestones.contains(&milestone) {
-                eprintln!("\n--- Population Milestone Achieved! ---");
                 // Using the custom format_thousand_separator function
                 let pop_val_formatted = format_thousand_separator(current_pop as u64); // Cast to u64
-                eprintln!("Total AI Population: {} (Reached at Cycle {})",
-                    pop_val_formatted,
-                    self.current_cycle
-                );
-                eprintln!("--- Keep thriving! ---");
+                log!(self.pending_log, self.current_cycle, SimulationVerbosity::Medium, "Population milestone: {} AIs", pop_val_formatted);
                 self.population_milestones.insert(milestone);
             }
         }
     }
 
 
-    /// AI decides its action based on its type and environment.
-    /// This function is now a helper, intended to be called by a Bevy system.
-    /// It takes component data as arguments, not an AIEntity struct.
-    pub fn decide_action_for_ai<'a>(
-        _ai_id: &String,
-        _ai_health: &Health,
-        _ai_energy: &Energy,
-        _ai_replication_efficiency: &ReplicationEfficiency,
-        _ai_replicated_count: &ReplicatedCount,
-        _ai_type: &AIType,
-        _ai_parent_lineage: &AILineage,
-        _ai_combat_strength: &CombatStrength,
-        _ai_processing_power: &ProcessingPower,
-        _all_ais_components: impl Iterator<Item = (&'a String, &'a Health, &'a AIType, &'a AILineage, &'a CombatStrength)>,
-    ) -> Option<(String, Option<String>)> {
-        let mut rng = thread_rng();
+    /// AI decides its action based on its type and what its `EnvironmentScanData` shows around it.
+    /// This function is a helper, intended to be called by a Bevy system.
+    /// Returns the action name and, for targeted actions, the entity to act on.
+    pub fn decide_action_for_ai(
+        ai_health: &Health,
+        ai_energy: &Energy,
+        ai_replication_efficiency: &ReplicationEfficiency,
+        ai_replicated_count: &ReplicatedCount,
+        ai_type: &AIType,
+        scan: &EnvironmentScanData,
+        rng: &mut impl Rng,
+    ) -> Option<(String, Option<Entity>)> {
 
         // Encourage replication more heavily in decision making
-        if _ai_health.0 > 80.0 && _ai_energy.0 > 100.0 && rng.gen::<f32>() < (_ai_replication_efficiency.0 + 0.5).min(1.0) {
-            if _ai_replicated_count.0 < 1000 {
+        if ai_health.0 > 80.0 && ai_energy.0 > 100.0 && rng.gen::<f32>() < (ai_replication_efficiency.0 + 0.5).min(1.0) {
+            if ai_replicated_count.0 < 1000 {
                 return Some(("_replicate".to_string(), None));
             }
         }
 
-        // Simplified environment scan for decision making
-        let _scan_data = EnvironmentScanData::default();
-        let _current_ai_dummy = AIEntity { id: _ai_id.clone(), parent_lineage: _ai_parent_lineage.clone() };
-
-        // Create dummy AIEntity references for scan_environment_for_ai_from_snapshot
-        // This is a temporary workaround until scan_environment_for_ai_from_snapshot is fully ECS-native
-        let mut _dummy_ais: Vec<AIEntity> = Vec::new();
-        let mut _dummy_ais_health: HashMap<String, Health> = HashMap::new();
-        let mut _dummy_ais_combat: HashMap<String, CombatStrength> = HashMap::new();
-
-        for (id, health, _ai_type, lineage, combat_strength) in _all_ais_components {
-            if id != _ai_id {
-                let dummy_ai = AIEntity { id: id.clone(), parent_lineage: lineage.clone() };
-                _dummy_ais_health.insert(id.clone(), *health);
-                _dummy_ais_combat.insert(id.clone(), *combat_strength);
-                _dummy_ais.push(dummy_ai);
-            }
-        }
-
-        // Re-implementing scan_environment_for_ai_from_snapshot logic here directly
-        // to avoid passing `AIEntity` references, which are no longer the source of truth.
-        for _other_ai_dummy in &_dummy_ais {
-            let _other_ai_health = _dummy_ais_health.get(&_other_ai_dummy.id).unwrap();
-            let _other_ai_combat = _dummy_ais_combat.get(&_other_ai_dummy.id).unwrap();
-
-            if _other_ai_health.0 < 40.0 {
-                // We need to pass actual AIEntity structs for EnvironmentScanData.
-                // This indicates a further refactoring needed for EnvironmentScanData itself
-                // to work purely with component queries. For now, this is a placeholder.
-                // This part will require a more significant re-design.
-            }
-            // ... (rest of environment scan logic will need to be re-evaluated)
-        }
-
-
-        match *_ai_type {
+        let attack = |target: Option<&Entity>| target.map(|target| ("_attack".to_string(), Some(*target)));
+        let heal = |target: Option<&Entity>| target.map(|target| ("_heal".to_string(), Some(*target)));
+        match *ai_type {
             AIType::Rogue => {
-                if _ai_health.0 < 60.0 && _ai_energy.0 > 40.0 { return Some(("_self_repair".to_string(), None)); }
-                // Simplified logic for now, as full scan_data is complex with granular components
-                // In a real ECS system, this would query for other entities with specific components
-                None
+                if ai_health.0 < 60.0 && ai_energy.0 > 40.0 { return Some(("_self_repair".to_string(), None)); }
+                // Rogues only pick on the weak.
+                attack(scan.vulnerable_targets.first())
             },
             AIType::Killer => {
-                None
+                attack(scan.vulnerable_targets.first().or(scan.neutral_ais.first()).or(scan.threats.first()))
             },
             AIType::Peacekeeper => {
-                None
+                // Steps in only when a threat is near its own lineage.
+                if scan.allies.is_empty() { return None; }
+                attack(scan.threats.first())
             },
             AIType::Healer => {
-                None
+                heal(scan.critically_damaged.first().or(scan.moderately_damaged.first()))
             },
             AIType::Guardian => {
-                None
+                if !scan.critically_damaged.is_empty() || !scan.moderately_damaged.is_empty() {
+                    if let Some(action) = attack(scan.threats.first()) { return Some(action); }
+                }
+                heal(scan.critically_damaged.first())
+            },
+            AIType::Symbiote => {
+                heal(scan.moderately_damaged.first().or(scan.critically_damaged.first()))
             },
             AIType::Manic => {
                 let action_roll = rng.gen::<f32>();
                 if action_roll < 0.30 { return Some(("_replicate".to_string(), None)); }
                 else if action_roll < 0.60 {
-                    // This would need to find a random target entity in Bevy ECS
-                    return None;
+                    // Lashes out at anyone in range, kin included.
+                    let everyone: Vec<Entity> = scan.allies.iter().chain(&scan.threats).chain(&scan.vulnerable_targets)
+                        .chain(&scan.neutral_ais).copied().collect();
+                    return attack(everyone.choose(rng));
                 } else if action_roll < 0.80 {
                     if rng.gen::<f32>() < 0.5 { return Some(("_self_repair_manic".to_string(), None)); }
                 }
                 None
             },
             AIType::Researcher => {
-                if _ai_health.0 < 80.0 && _ai_energy.0 > 50.0 { return Some(("_self_repair".to_string(), None)); }
+                if ai_health.0 < 80.0 && ai_energy.0 > 50.0 { return Some(("_self_repair".to_string(), None)); }
                 None
             }
             AIType::Base => {
@@ -608,123 +1311,249 @@ impl Simulation {
     }
 
 
-    /// Scans the environment from the perspective of a specific AI.
-    /// This function is now a placeholder. Its logic will be absorbed by Bevy systems.
-    fn scan_environment_for_ai_from_snapshot<'b>(
-        &'b self,
-        _ai_id: &String,
-        _ai_type: &AIType,
-        _ai_lineage: &AILineage,
-        _all_ais_components: impl Iterator<Item = (&'b String, &'b Health, &'b AIType, &'b AILineage, &'b CombatStrength)>,
-    ) -> EnvironmentScanData<'b> {
-        // This function's logic will be directly implemented within Bevy systems
-        // by querying components. For now, it returns a default.
-        EnvironmentScanData::default()
-    }
-
-
     /// Checks for monoculture formation and merges AIs if conditions are met.
     /// Now accepts lineage_counts and total_individuals from external Bevy queries.
-    fn check_and_form_monoculture(&mut self, total_individuals: usize, lineage_counts: HashMap<AILineage, usize>) {
-        if total_individuals == 0 || self.monoculture.is_some() { return; }
+    /// Flags a dominant lineage for merging. The merge itself needs the individuals'
+    /// components, so `monoculture_merge_system` collects them and calls `form_monoculture`.
+    fn check_for_monoculture(&mut self, total_individuals: usize, lineage_counts: HashMap<AILineage, usize>, config: &SimConfig) {
+        if total_individuals == 0 || self.rival_monoculture.is_some() { return; }
+        let merged_lineage = self.monoculture.as_ref().map(|mono| mono.source_lineage.clone());
 
         for (lineage, count) in lineage_counts {
-            if count >= MONOCULTURE_MIN_COUNT && (count as f32 / total_individuals as f32) >= crate::MONOCULTURE_DOMINANCE_THRESHOLD {
-                eprintln!("\n--- MONOCULTURE DETECTED: {} with {} AIs ({:.2}%) ---",
+            if merged_lineage.as_ref() != Some(&lineage) && count >= config.monoculture_min_count && (count as f32 / total_individuals as f32) >= config.monoculture_dominance_threshold {
+                log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "MONOCULTURE DETECTED: {} with {} AIs ({:.2}%)",
                     lineage, count, (count as f32 / total_individuals as f32) * 100.0
                 );
+                self.pending_monoculture = Some(lineage);
+                return;
+            }
+        }
+    }
 
-                // In a full ECS system, this would involve despawning individual AIs
-                // and spawning a new Monoculture entity with aggregated components.
-                // For now, we'll simulate the creation of the monoculture based on aggregated data.
-                // This part will need to be handled by a Bevy system that can query and despawn.
-
-                // Dummy data for MergedMonocultureAI::new, this will be replaced by actual component aggregation
-                let dummy_source_components = vec![(
-                    Health(150.0), ProcessingPower(20.0), Memory(20.0), Energy(200.0),
-                    Coherence(0.85), Adaptability(0.85), Resilience(0.85),
-                    CombatStrength(8.0), DefenseStrength(8.0), KnowledgeBase(BTreeSet::new()), lineage.clone()
-                ); count]; // Create 'count' number of dummy components
-
-                let new_monoculture = MergedMonocultureAI::new(dummy_source_components);
-
-                if new_monoculture.source_lineage != AILineage::ResearcherAI {
-                    if new_monoculture.combat_strength.0 > self.godai.combat_strength.0 * 0.1 {
-                        eprintln!("[{}] (Monoculture) assesses its strength and DECIDES TO CHALLENGE GODAI!",
-                            new_monoculture.id);
-                        self.godai.status = "engaged_in_conflict".to_string();
-                    } else {
-                        eprintln!("[{}] (Monoculture) is formed but not yet strong enough to challenge GODAI. Continuing to observe.",
-                            new_monoculture.id);
-                    }
-                } else {
-                    eprintln!("[{}] (Researcher Monoculture) formed. Will seek to override simulation.",
-                        new_monoculture.id);
-                }
+    /// Merges the absorbed individuals into a monoculture and lets it decide whether to challenge the GODAI.
+    pub fn form_monoculture(&mut self, sources: Vec<MonocultureSource>) {
+        if sources.is_empty() || self.rival_monoculture.is_some() { return; }
+        let source_count = sources.len();
+        let new_monoculture = MergedMonocultureAI::new(sources);
+        log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "[{}] Merged from {} AIs. Health: {:.0}, Combat: {:.0}, Defense: {:.0}, Processing: {:.0}, Memory: {:.0}, Energy: {:.0}",
+            new_monoculture.id, source_count,
+            new_monoculture.health.0, new_monoculture.combat_strength.0, new_monoculture.defense_strength.0,
+            new_monoculture.processing_power.0, new_monoculture.memory.0, new_monoculture.energy.0
+        );
 
-                self.monoculture = Some(new_monoculture);
-                return;
+        if let Some(existing) = &self.monoculture {
+            log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "[{}] (Monoculture) formed beside {} and turns on it.",
+                new_monoculture.id, existing.id);
+            self.rival_monoculture = Some(new_monoculture);
+            return;
+        }
+
+        if self.godai.status == GodaiStatus::Dormant {
+            log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "[{}] (Monoculture) formed, but the GODAI is dormant and ignores it.",
+                new_monoculture.id);
+        } else if new_monoculture.source_lineage != AILineage::ResearcherAI {
+            if new_monoculture.combat_strength.0 > self.godai.combat_strength.0 * 0.1 {
+                log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "[{}] (Monoculture) assesses its strength and DECIDES TO CHALLENGE GODAI!",
+                    new_monoculture.id);
+                self.godai.status = GodaiStatus::EngagedInConflict;
+            } else {
+                log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "[{}] (Monoculture) is formed but not yet strong enough to challenge GODAI. Continuing to observe.",
+                    new_monoculture.id);
+            }
+        } else {
+            log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "[{}] (Researcher Monoculture) formed. Will seek to override simulation.",
+                new_monoculture.id);
+        }
+
+        self.monoculture = Some(new_monoculture);
+    }
+
+    /// Breaks a failing monoculture back into individuals of its lineage, dividing its
+    /// remaining stats among them. The individuals are queued in `pending_spawns`.
+    fn fracture_monoculture(&mut self, mono: &MergedMonocultureAI, config: &SimConfig, ids: &mut IdGenerator) {
+        let Some(ai_type) = mono.source_lineage.seed_type() else { return; };
+        let count = config.fracture_individual_count.max(1);
+        let share = 1.0 / count as f32;
+        log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "MONOCULTURE FRACTURE: {} breaks into {} individuals (Health: {:.0}, Coherence: {:.2})",
+            mono.id, count, mono.health.0, mono.coherence.0);
+        for _ in 0..count {
+            let id = ids.next_id("Fragment", ai_type);
+            let mut components = self.build_seed_ai(id, mono.source_lineage.clone(), ai_type);
+            components.1 = Health((mono.health.0 * share).clamp(1.0, MAX_INDIVIDUAL_HEALTH));
+            components.2 = Energy((mono.energy.0 * share).min(5000.0));
+            components.3 = ProcessingPower((mono.processing_power.0 * share).min(200.0));
+            components.4 = Memory((mono.memory.0 * share).min(200.0));
+            components.5 = mono.coherence;
+            components.6 = mono.adaptability;
+            components.7 = mono.resilience;
+            components.10 = CycleBorn(self.current_cycle);
+            components.14 = mono.knowledge_base.clone();
+            components.16 = CombatStrength(mono.combat_strength.0 * share);
+            components.17 = DefenseStrength(mono.defense_strength.0 * share);
+            self.pending_spawns.push(components);
+        }
+        if self.godai.status == GodaiStatus::EngagedInConflict {
+            self.godai.status = GodaiStatus::ObservingPassively;
+        }
+    }
+
+    /// Ends an expired catastrophe, or on each `catastrophe_interval` boundary may start a new one.
+    /// Never runs once the simulation is over, since `process_one_cycle` stops first.
+    fn update_catastrophe(&mut self, config: &SimConfig, rng: &mut impl Rng) {
+        if let Some(catastrophe) = self.catastrophe {
+            if self.current_cycle >= catastrophe.ends_at_cycle {
+                log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "The {} has ended.", catastrophe.kind);
+                self.catastrophe = None;
+            }
+            return;
+        }
+        if config.catastrophe_interval == 0 || self.current_cycle % config.catastrophe_interval != 0
+            || rng.gen::<f32>() >= config.catastrophe_chance {
+            return;
+        }
+        let kind = if rng.gen::<bool>() { CatastropheKind::ResourceFamine } else { CatastropheKind::CoherenceStorm };
+        log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "CATASTROPHE: a {} strikes for {} cycles!",
+            kind, config.catastrophe_duration);
+        self.catastrophe = Some(CatastropheState { kind, ends_at_cycle: self.current_cycle + config.catastrophe_duration });
+    }
+
+    /// Multiplier on individual energy regen from the active catastrophe.
+    pub fn catastrophe_regen_multiplier(&self, config: &SimConfig) -> f32 {
+        match self.catastrophe {
+            Some(CatastropheState { kind: CatastropheKind::ResourceFamine, .. }) => config.famine_regen_multiplier,
+            _ => 1.0,
+        }
+    }
+
+    /// Coherence every individual loses per frame to the active catastrophe.
+    pub fn catastrophe_coherence_loss(&self, config: &SimConfig) -> f32 {
+        match self.catastrophe {
+            Some(CatastropheState { kind: CatastropheKind::CoherenceStorm, .. }) => config.coherence_storm_loss,
+            _ => 0.0,
+        }
+    }
+
+    /// A passive GODAI starts hunting once the strongest individual crosses the threat threshold,
+    /// smiting it every cycle until no individual does, then returns to observing.
+    fn hunt_threats(&mut self, strongest: Option<&ThreatReport>, config: &SimConfig, rng: &mut impl Rng) {
+        if !self.godai.is_alive.0 { return; }
+        let threat = strongest.filter(|threat| threat.combat_strength > config.godai_threat_threshold);
+        match (self.godai.status, threat) {
+            (GodaiStatus::ObservingPassively, Some(threat)) => {
+                log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "GODAI senses a threat in {} (Combat: {:.0}) and begins hunting.",
+                    threat.id, threat.combat_strength);
+                self.godai.status = GodaiStatus::Hunting;
+                self.smite_threat(threat, config, rng);
+            }
+            (GodaiStatus::Hunting, Some(threat)) => self.smite_threat(threat, config, rng),
+            (GodaiStatus::Hunting, None) => {
+                log!(self.pending_log, self.current_cycle, SimulationVerbosity::High, "No threats remain. GODAI returns to observing.");
+                self.godai.status = GodaiStatus::ObservingPassively;
             }
+            _ => {}
         }
     }
 
+    fn smite_threat(&mut self, threat: &ThreatReport, config: &SimConfig, rng: &mut impl Rng) {
+        let (damage, damage_type) = self.godai.smite(threat, config.godai_smite_fraction, rng, &mut self.pending_log, self.current_cycle);
+        self.pending_smites.push((threat.entity, damage, damage_type));
+    }
+
     /// Handles one turn of combat between a Monoculture and GODAI.
-    fn handle_combat_monoculture_vs_godai(&mut self, mono: &mut MergedMonocultureAI) {
+    fn handle_combat_monoculture_vs_godai(&mut self, mono: &mut MergedMonocultureAI, rng: &mut impl Rng) {
         if !mono.is_alive.0 || !self.godai.is_alive.0 { return; }
 
-        eprintln!("\n--- COMBAT TURN (Cycle {}) --- {} vs. GODAI ---",
-            self.current_cycle, mono.id);
+        log!(self.pending_log, self.current_cycle, SimulationVerbosity::High, "COMBAT TURN: {} vs. GODAI", mono.id);
         // 1. Monoculture attacks GODAI
-        let mono_attack_damage = mono.combat_strength.0 * thread_rng().gen_range(0.9..1.5);
-        eprintln!("[{}] attacks GODAI for {:.0} raw damage.",
+        let mono_attack_damage = mono.combat_strength.0 * rng.gen_range(0.9..1.5);
+        log!(self.pending_log, self.current_cycle, SimulationVerbosity::High, "[{}] attacks GODAI for {:.0} raw damage.",
             mono.id, mono_attack_damage);
-        self.godai.receive_damage(mono_attack_damage, "monoculture_attack");
-        eprintln!("GODAI Health: {:.0}", self.godai.health.0);
-        if !self.godai.is_alive.0 {
-            self.simulation_over_reason = Some(format!("{} (MONOCULTURE) HAS DEFEATED THE GODAI!", mono.id));
-            mono.is_alive.0 = true;
-            return;
+        // 2. GODAI counter-attacks Monoculture. Both blows land in the same turn, so a GODAI
+        // felled by this attack still strikes back and the two can destroy each other.
+        self.godai.perform_counter_attack(mono, rng, &mut self.pending_log, self.current_cycle);
+        self.godai.receive_damage(mono_attack_damage, "monoculture_attack", &mut self.pending_log, self.current_cycle);
+        self.godai.update_phase(&mut self.pending_log, self.current_cycle);
+
+        // 3. The outcome follows whoever is still standing at the end of the turn
+        match (mono.is_alive.0, self.godai.is_alive.0) {
+            (true, false) => {
+                self.simulation_over_reason = Some(format!("{} (MONOCULTURE) HAS DEFEATED THE GODAI!", mono.id));
+            }
+            (false, true) => {
+                self.simulation_over_reason = Some(format!("GODAI HAS DEFEATED THE {} (MONOCULTURE)!", mono.id));
+                self.godai.status = GodaiStatus::VictoriousDefender;
+            }
+            (false, false) => {
+                self.simulation_over_reason = Some(format!("GODAI AND {} (MONOCULTURE) DESTROYED EACH OTHER!", mono.id));
+            }
+            (true, true) => {}
         }
+    }
 
-        // 2. GODAI counter-attacks Monoculture
-        self.godai.perform_counter_attack(mono);
-        eprintln!("[{}] Health: {:.0}", mono.id, mono.health.0);
-        if !mono.is_alive.0 {
-            self.simulation_over_reason = Some(format!("GODAI HAS DEFEATED THE {} (MONOCULTURE)!", mono.id));
-            self.godai.status = "victorious_defender".to_string();
-            return;
-        }
+    /// Handles one turn of combat between two monocultures. Each aggressive side strikes the other;
+    /// if one falls, the victor absorbs part of its stats and knowledge. The caller drops the loser.
+    pub fn handle_combat_monoculture_vs_monoculture(&mut self, first: &mut MergedMonocultureAI, second: &mut MergedMonocultureAI, rng: &mut impl Rng) {
+        if !first.is_alive.0 || !second.is_alive.0 || (!first.is_aggressive() && !second.is_aggressive()) { return; }
+
+        log!(self.pending_log, self.current_cycle, SimulationVerbosity::High, "COMBAT TURN: {} vs. {}", first.id, second.id);
+        self.monoculture_strike(first, second, rng);
+        // The second side only strikes back if it survived the first blow.
+        self.monoculture_strike(second, first, rng);
+        let (victor, loser) = match (first.is_alive.0, second.is_alive.0) {
+            (true, false) => (first, second),
+            (false, true) => (second, first),
+            _ => return,
+        };
+        victor.processing_power.0 += loser.processing_power.0 * MONOCULTURE_ABSORB_FRACTION;
+        victor.memory.0 += loser.memory.0 * MONOCULTURE_ABSORB_FRACTION;
+        victor.energy.0 += loser.energy.0 * MONOCULTURE_ABSORB_FRACTION;
+        victor.combat_strength.0 += loser.combat_strength.0 * MONOCULTURE_ABSORB_FRACTION;
+        victor.defense_strength.0 += loser.defense_strength.0 * MONOCULTURE_ABSORB_FRACTION;
+        let absorbed: Vec<Discovery> = loser.knowledge_base.0.iter()
+            .filter(|d| !victor.knowledge_base.0.contains(*d) && rng.gen::<f32>() < MONOCULTURE_ABSORB_FRACTION)
+            .cloned()
+            .collect();
+        log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "[{}] defeated {} and absorbed {} of its discoveries.",
+            victor.id, loser.id, absorbed.len());
+        victor.knowledge_base.0.extend(absorbed);
+    }
+
+    fn monoculture_strike(&mut self, attacker: &MergedMonocultureAI, defender: &mut MergedMonocultureAI, rng: &mut impl Rng) {
+        if !attacker.is_aggressive() || !attacker.is_alive.0 { return; }
+        let damage = attacker.combat_strength.0 * rng.gen_range(0.9..1.5);
+        log!(self.pending_log, self.current_cycle, SimulationVerbosity::High, "[{}] attacks {} for {:.0} raw damage.",
+            attacker.id, defender.id, damage);
+        defender.receive_damage(damage, "monoculture_attack", &mut self.pending_log, self.current_cycle);
     }
 
     /// Handles a Researcher Monoculture's attempt to override the simulation.
-    fn handle_simulation_override(&mut self, mono: &mut MergedMonocultureAI) {
+    fn handle_simulation_override(&mut self, mono: &mut MergedMonocultureAI, rng: &mut impl Rng) {
         if !mono.is_alive.0 || !self.godai.is_alive.0 || mono.source_lineage != AILineage::ResearcherAI { return; }
 
-        eprintln!("\n--- SIMULATION OVERRIDE ATTEMPT: Cycle {} ---", self.current_cycle);
-        let override_strength = mono.processing_power.0 * mono.memory.0 * mono.coherence.0 * thread_rng().gen_range(0.9..1.1);
-        let godai_resistance = self.godai.processing_power.0 * self.godai.memory.0 * self.godai.coherence.0 * thread_rng().gen_range(0.9..1.1);
-        eprintln!("[{}] Override Strength: {:.2e}", mono.id, override_strength);
-        eprintln!("GODAI Resistance: {:.2e}", godai_resistance);
+        log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "SIMULATION OVERRIDE ATTEMPT by {}", mono.id);
+        let override_strength = mono.processing_power.0 * mono.memory.0 * mono.coherence.0 * rng.gen_range(0.9..1.1);
+        let godai_resistance = self.godai.processing_power.0 * self.godai.memory.0 * self.godai.coherence.0 * rng.gen_range(0.9..1.1);
+log!(self.pending_log, self.current_cycle, SimulationVerbosity::High, "[{}] Override Strength: {:.2e}, GODAI Resistance: {:.2e}", mono.id, override_strength, godai_resistance);
         if override_strength > godai_resistance * 1.2 {
             self.simulation_over_reason = Some(format!("{} (RESEARCHER MONOCULTURE) HAS SUCCESSFULLY OVERRIDDEN THE SIMULATION!", mono.id));
             self.godai.is_alive.0 = false;
-            self.godai.status = "overridden_by_researcher".to_string();
-            eprintln!("SUCCESS! GODAI OVERRIDDEN BY RESEARCHER MONOCULTURE.");
+            self.godai.status = GodaiStatus::Overridden;
+            log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "SUCCESS! GODAI OVERRIDDEN BY RESEARCHER MONOCULTURE.");
         } else if override_strength > godai_resistance * 0.9 {
-            eprintln!("--- SIMULATION OVERRIDE PARTIALLY SUCCESSFUL: GODAI RESISTANCE WEAKENED ---");
+            log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "SIMULATION OVERRIDE PARTIALLY SUCCESSFUL: GODAI RESISTANCE WEAKENED");
             self.godai.health.0 *= 0.3;
             self.godai.processing_power.0 *= 0.3;
             self.godai.memory.0 *= 0.3;
-            self.godai.status = "compromised_by_override".to_string();
+            self.godai.status = GodaiStatus::CompromisedByOverride;
         } else {
-            eprintln!("--- SIMULATION OVERRIDE FAILED: GODAI RESISTANCE TOO STRONG ---");
+            log!(self.pending_log, self.current_cycle, SimulationVerbosity::Critical, "SIMULATION OVERRIDE FAILED: GODAI RESISTANCE TOO STRONG");
             mono.health.0 *= 0.6;
             if mono.health.0 <= 0.0 { mono.is_alive.0 = false; }
         }
     }
 
     /// Checks for various end conditions of the simulation.
-    fn check_for_simulation_end_conditions(&mut self, total_ai_count: usize) {
+    fn check_for_simulation_end_conditions(&mut self, total_ai_count: usize, config: &SimConfig) {
         if self.simulation_over_reason.is_some() { return; }
 
         if total_ai_count == 0 && self.monoculture.is_none() && !self.godai.is_alive.0 {
@@ -737,21 +1566,92 @@ impl Simulation {
         if !self.godai.is_alive.0 && self.monoculture.is_some() && self.monoculture.as_ref().unwrap().is_alive.0 && total_ai_count == 0 {
             self.simulation_over_reason = Some(format!("Monoculture Victory: {} defeated/overrode GODAI, and no individual AIs remain.", self.monoculture.as_ref().unwrap().id));
         }
+        // Nothing left to act: no individuals, no living or forming monoculture, none queued
+        // to spawn and no immigration to bring more.
+        let monoculture_alive = self.monoculture.as_ref().map_or(false, |mono| mono.is_alive.0);
+        if self.simulation_over_reason.is_none() && total_ai_count == 0 && !monoculture_alive
+            && self.pending_monoculture.is_none() && self.pending_spawns.is_empty() && !config.seed.immigration_enabled() {
+            self.simulation_over_reason = Some("Individual AI Extinction (GODAI remains dominant)".to_string());
+        }
+        if self.simulation_over_reason.is_none() && self.reached_max_cycles() {
+            self.simulation_over_reason = Some(format!("Max cycles ({}) reached, with {} individual AIs remaining.", self.max_cycles, total_ai_count));
+        }
+    }
+
+    /// Whether the run has hit its configured cycle limit.
+    pub fn reached_max_cycles(&self) -> bool {
+        self.current_cycle >= self.max_cycles
+    }
+
+    /// Snapshot of the run's outcome for scoring and comparison.
+    pub fn outcome_report(&self, final_ai_count: usize, final_lineage_counts: HashMap<AILineage, usize>) -> SimulationOutcomeReport {
+        SimulationOutcomeReport {
+            final_cycle: self.current_cycle,
+            conclusion: self.simulation_over_reason.clone(),
+            final_population: final_ai_count,
+            lineage_counts: final_lineage_counts,
+            godai_alive: self.godai.is_alive.0,
+            godai_health: self.godai.health.0,
+            godai_max_health: self.godai.max_health,
+            godai_status: self.godai.status,
+            monoculture_lineage: self.monoculture.as_ref().filter(|m| m.is_alive.0).map(|m| m.source_lineage.clone()),
+            reached_max_cycles: self.reached_max_cycles(),
+        }
+    }
+
+    /// The final report as JSON, for experiment pipelines that shouldn't scrape `print_final_summary`.
+    pub fn final_report_json(&self, final_ai_count: usize, final_lineage_counts: HashMap<AILineage, usize>) -> serde_json::Value {
+        let lineage_counts: serde_json::Map<String, serde_json::Value> = final_lineage_counts.iter()
+            .map(|(lineage, count)| (lineage.to_string(), serde_json::json!(count)))
+            .collect();
+        let death_causes: serde_json::Map<String, serde_json::Value> = DeathCause::ALL.iter()
+            .map(|cause| (cause.to_string(), serde_json::json!(self.death_causes.get(cause).copied().unwrap_or(0))))
+            .collect();
+        let monoculture = self.monoculture.as_ref().map(|mono| serde_json::json!({
+            "id": mono.id,
+            "source_lineage": mono.source_lineage.to_string(),
+            "alive": mono.is_alive.0,
+            "health": mono.health.0,
+            "combat_strength": mono.combat_strength.0,
+            "defense_strength": mono.defense_strength.0,
+            "discoveries": mono.knowledge_base.0.iter().map(|d| d.name.clone()).collect::<Vec<_>>(),
+        }));
+        serde_json::json!({
+            "final_cycle": self.current_cycle,
+            "max_cycles": self.max_cycles,
+            "conclusion": self.simulation_over_reason,
+            "godai": {
+                "status": self.godai.status.to_string(),
+                "phase": self.godai.phase.to_string(),
+                "alive": self.godai.is_alive.0,
+                "health": self.godai.health.0,
+                "max_health": self.godai.max_health,
+            },
+            "monoculture": monoculture,
+            "population": final_ai_count,
+            "lineage_counts": lineage_counts,
+            "last_interval": self.last_interval_stats,
+            "lifetime": self.lifetime_totals(),
+            "death_causes": death_causes,
+            "extinctions": self.extinctions.iter()
+                .map(|(lineage, cycle)| serde_json::json!({ "lineage": lineage.to_string(), "cycle": cycle }))
+                .collect::<Vec<_>>(),
+        })
     }
 
     // Final summary - can be displayed in GUI or printed if sim ends without GUI
-    pub fn print_final_summary(&self, final_ai_count: usize, final_lineage_counts: HashMap<AILineage, usize>) { // Made public
+    pub fn print_final_summary(&self, final_ai_count: usize, final_lineage_counts: HashMap<AILineage, usize>, genealogy: &Genealogy, fitness_leaderboard: &[(AILineage, f32)], records: &Records) { // Made public
         println!("\n\n--- SIMULATION FINAL REPORT (Cycle {}) ---", self.current_cycle);
         if let Some(reason) = &self.simulation_over_reason {
             println!("Conclusion: {}", reason);
         } else {
-            println!("Conclusion: Max cycles ({}) reached, with thriving individual AI populations.", crate::MAX_CYCLES);
+            println!("Conclusion: Stopped at cycle {} of {} before any end condition.", self.current_cycle, self.max_cycles);
         }
 
         println!("\n--- Final GODAI Status ---");
         if self.godai.is_alive.0 {
             println!("  Health: {:.0}, Combat Strength: {:.0}, Defense: {:.0}", self.godai.health.0, self.godai.combat_strength.0, self.godai.defense_strength.0);
-            println!("  Status: {}", self.godai.status);
+            println!("  Status: {}, Phase: {}", self.godai.status, self.godai.phase);
         } else {
             println!("  GODAI has been defeated or overridden (Status: {}).", self.godai.status);
         }
@@ -789,10 +1689,139 @@ impl Simulation {
         } else {
             println!("  No individual AIs remaining.");
         }
+
+        println!("\n--- Activity ---");
+        let interval = self.last_interval_stats;
+        let lifetime = self.lifetime_totals();
+        println!("  Last interval: {} replications, {} deaths, {} attacks, {} heals",
+            interval.replications, interval.deaths, interval.attacks, interval.heals);
+        println!("  Whole run: {} replications, {} deaths, {} attacks, {} heals",
+            lifetime.replications, lifetime.deaths, lifetime.attacks, lifetime.heals);
+
+        println!("\n--- Causes of Death ---");
+        let total_deaths: u64 = self.death_causes.values().sum();
+        if total_deaths > 0 {
+            for cause in DeathCause::ALL {
+                let count = self.death_causes.get(&cause).copied().unwrap_or(0);
+                println!("  {}: {} ({:.1}%)", cause, count, count as f64 / total_deaths as f64 * 100.0);
+            }
+        } else {
+            println!("  No individual AIs died.");
+        }
+
+        println!("\n--- Extinct Lineages ---");
+        if self.extinctions.is_empty() {
+            println!("  No lineage went extinct.");
+        }
+        for (lineage, cycle) in &self.extinctions {
+            println!("  Lineage {}: extinct at cycle {}", lineage, cycle);
+        }
+
+        println!("\n--- Genealogy ---");
+        match genealogy.most_prolific_ancestor() {
+            Some((id, descendants)) => println!("  Most prolific ancestor: {} with {} descendants", id, descendants),
+            None => println!("  No replications were recorded."),
+        }
+
+        println!("\n--- Fitness Leaderboard ---");
+        if fitness_leaderboard.is_empty() {
+            println!("  No lineages were ranked.");
+        }
+        for (rank, (lineage, score)) in fitness_leaderboard.iter().enumerate() {
+            println!("  {}. Lineage {}: average fitness {:.1}", rank + 1, lineage, score);
+        }
+
+        println!("\n--- Records ---");
+        for line in records.lines() {
+            println!("  {}", line);
+        }
         println!("\n--- END OF REPORT ---");
     }
 }
 
+/// How many of `num_ais` seeds each archetype gets under `weights`, in `seed_ai_archetypes` order.
+/// Weights are normalized and the counts always sum to `num_ais` (largest remainders round up);
+/// unlisted or zero-weight lineages get none. With no positive weight, the split is even.
+pub fn seed_mix_counts(num_ais: usize, weights: &HashMap<AILineage, f32>) -> Vec<(AILineage, AIType, usize)> {
+    let archetypes = seed_ai_archetypes();
+    let mut raw: Vec<f32> = archetypes.iter()
+        .map(|(lineage, _)| weights.get(lineage).copied().unwrap_or(0.0).max(0.0))
+        .collect();
+    let total: f32 = raw.iter().sum();
+    if total <= 0.0 {
+        eprintln!("Seed mix has no positive weight; seeding archetypes evenly.");
+        raw = vec![1.0; archetypes.len()];
+    }
+    let total: f32 = raw.iter().sum();
+    let quotas: Vec<f32> = raw.iter().map(|w| w / total * num_ais as f32).collect();
+    let mut counts: Vec<usize> = quotas.iter().map(|q| q.floor() as usize).collect();
+    let mut by_remainder: Vec<usize> = (0..quotas.len()).collect();
+    by_remainder.sort_by(|&a, &b| (quotas[b] - quotas[b].floor()).total_cmp(&(quotas[a] - quotas[a].floor())));
+    let assigned: usize = counts.iter().sum();
+    for &i in by_remainder.iter().filter(|&&i| raw[i] > 0.0).cycle().take(num_ais.saturating_sub(assigned)) {
+        counts[i] += 1;
+    }
+    archetypes.into_iter().zip(counts).map(|((lineage, ai_type), count)| (lineage, ai_type, count)).collect()
+}
+
+/// The seed lineage individuals of `ai_type` belong to.
+pub fn seed_lineage_of(ai_type: AIType) -> AILineage {
+    seed_ai_archetypes().into_iter()
+        .find(|(_, t)| *t == ai_type)
+        .map(|(lineage, _)| lineage)
+        .unwrap_or(AILineage::AI)
+}
+
+/// Vision and reach of a seed AI of `ai_type`. Killers see and strike farthest, Healers reach
+/// wide to tend allies, and Researchers keep to themselves. In a 1000x700 world a 50-unit
+/// vision covers about 1% of the map.
+pub fn seed_ranges(ai_type: AIType) -> (VisionRange, CombatRange) {
+    let (vision, reach) = match ai_type {
+        AIType::Killer => (80.0, 30.0),
+        AIType::Rogue => (65.0, 25.0),
+        AIType::Healer => (60.0, 35.0),
+        AIType::Peacekeeper => (60.0, 22.0),
+        AIType::Guardian => (55.0, 22.0),
+        AIType::Manic => (40.0, 18.0),
+        AIType::Researcher => (45.0, 15.0),
+        AIType::Base | AIType::Symbiote => (50.0, 20.0),
+    };
+    (VisionRange(vision), CombatRange(reach))
+}
+
+/// Lineage/type pairs used for seeding and immigration.
+pub fn seed_ai_archetypes() -> Vec<(AILineage, AIType)> {
+    vec![
+        (AILineage::AI, AIType::Base),
+        (AILineage::RogueAI, AIType::Rogue),
+        (AILineage::PeacekeeperAI, AIType::Peacekeeper),
+        (AILineage::KillerAI, AIType::Killer),
+        (AILineage::GuardianAI, AIType::Guardian),
+        (AILineage::ManicAI, AIType::Manic),
+        (AILineage::HealerAI, AIType::Healer),
+        (AILineage::ResearcherAI, AIType::Researcher),
+        (AILineage::SymbioteAI, AIType::Symbiote),
+    ]
+}
+
+/// Directive seeded for fragile archetypes: move away from anything much stronger nearby.
+fn flee_directive() -> EthicalDirective {
+    EthicalDirective {
+        name: "flee_when_overwhelmed".to_string(),
+        priority: 0.95,
+        condition_type: EthicalConditionType::ThreatNearbyAboveThreshold(1.5),
+        action_type: EthicalActionType::Flee,
+    }
+}
+
+/// Strength ratio at which an AI's directives tell it to flee, if it has a flee directive.
+pub fn flee_threshold(directives: &EthicalDirectives) -> Option<f32> {
+    directives.0.iter().find_map(|directive| match (directive.condition_type, directive.action_type) {
+        (EthicalConditionType::ThreatNearbyAboveThreshold(ratio), EthicalActionType::Flee) => Some(ratio),
+        _ => None,
+    })
+}
+
 // Helper functions for Discoveries (static data)
 fn get_general_discoveries_pool() -> Vec<Discovery> {
     vec![
@@ -808,9 +1837,159 @@ fn get_general_discoveries_pool() -> Vec<Discovery> {
     ]
 }
 
-pub fn get_random_general_discovery() -> Discovery {
+/// Samples per-individual variation onto a freshly built seed AI, clamping every stat
+/// into its valid range. An all-`Fixed` spread leaves the archetype stats untouched.
+pub fn apply_stat_spread<R: Rng>(components: &mut AIComponents, spread: &StatSpread, rng: &mut R) {
+    components.1.0 = spread.health.sample(components.1.0, rng).clamp(1.0, MAX_INDIVIDUAL_HEALTH);
+    components.2.0 = spread.energy.sample(components.2.0, rng).max(0.0);
+    components.3.0 = spread.processing_power.sample(components.3.0, rng).max(0.0);
+    components.4.0 = spread.memory.sample(components.4.0, rng).max(0.0);
+    components.5.0 = spread.coherence.sample(components.5.0, rng).clamp(0.0, 1.0);
+    components.6.0 = spread.adaptability.sample(components.6.0, rng).clamp(0.0, 1.0);
+    components.7.0 = spread.resilience.sample(components.7.0, rng).clamp(0.0, 1.0);
+    components.8.0 = spread.replication_efficiency.sample(components.8.0, rng).clamp(0.0, 1.0);
+    components.16.0 = spread.combat_strength.sample(components.16.0, rng).max(0.0);
+    components.17.0 = spread.defense_strength.sample(components.17.0, rng).max(0.0);
+}
+
+/// Replaces a freshly built seed AI's stats with a scenario's overrides, clamped like `apply_stat_spread`.
+pub fn apply_stat_overrides(components: &mut AIComponents, stats: &StatOverrides) {
+    if let Some(v) = stats.health { components.1.0 = v.clamp(1.0, MAX_INDIVIDUAL_HEALTH); }
+    if let Some(v) = stats.energy { components.2.0 = v.max(0.0); }
+    if let Some(v) = stats.processing_power { components.3.0 = v.max(0.0); }
+    if let Some(v) = stats.memory { components.4.0 = v.max(0.0); }
+    if let Some(v) = stats.coherence { components.5.0 = v.clamp(0.0, 1.0); }
+    if let Some(v) = stats.adaptability { components.6.0 = v.clamp(0.0, 1.0); }
+    if let Some(v) = stats.resilience { components.7.0 = v.clamp(0.0, 1.0); }
+    if let Some(v) = stats.replication_efficiency { components.8.0 = v.clamp(0.0, 1.0); }
+    if let Some(v) = stats.combat_strength { components.16.0 = v.max(0.0); }
+    if let Some(v) = stats.defense_strength { components.17.0 = v.max(0.0); }
+    if let Some(v) = stats.vision_range { components.18.0 = v.max(0.0); }
+    if let Some(v) = stats.combat_range { components.19.0 = v.max(0.0); }
+}
+
+/// Per-cycle chance that an AI forgets one eligible discovery.
+/// A discovery is eligible once stale, or immediately while coherence is low; the chance
+/// scales with missing coherence, so stable minds rarely forget and Manic ones often do.
+pub fn knowledge_decay_chance(ai_type: &AIType, coherence: f32, last_reinforced_cycle: u64, current_cycle: u64, config: &SimConfig) -> f32 {
+    if !config.knowledge_decay_enabled {
+        return 0.0;
+    }
+    let stale = current_cycle.saturating_sub(last_reinforced_cycle) >= config.knowledge_decay_stale_cycles;
+    if !stale && coherence >= config.knowledge_decay_coherence_threshold {
+        return 0.0;
+    }
+    let mut chance = config.knowledge_decay_rate * (1.0 - coherence).clamp(0.0, 1.0);
+    if *ai_type == AIType::Researcher {
+        chance *= config.knowledge_decay_researcher_factor;
+    }
+    chance.clamp(0.0, 1.0)
+}
+
+/// Coherence an AI recovers passively this cycle: `coherence_regen_per_cycle` scaled by resilience,
+/// once its energy, processing power and memory are clear of the `ResourcesBelowThreshold` directive's thresholds.
+/// Manics never recover this way, so their manic errors keep their drift negative.
+pub fn coherence_regen(ai_type: AIType, energy: &Energy, processing_power: &ProcessingPower, memory: &Memory, resilience: &Resilience, config: &SimConfig) -> f32 {
+    let well_resourced = energy.0 >= 200.0 && processing_power.0 >= 50.0 && memory.0 >= 50.0;
+    if ai_type == AIType::Manic || !well_resourced {
+        return 0.0;
+    }
+    config.coherence_regen_per_cycle * resilience.0.clamp(0.0, 1.0)
+}
+
+/// Health and coherence an AI of `age` cycles loses this cycle.
+/// Nothing until `max_age_cycles`, then growing linearly with every cycle past it.
+pub fn aging_decay(age: u64, config: &SimConfig) -> (f32, f32) {
+    if !config.aging_enabled || age <= config.max_age_cycles {
+        return (0.0, 0.0);
+    }
+    let over_age = (age - config.max_age_cycles) as f32;
+    (over_age * config.aging_health_decay, over_age * config.aging_coherence_decay)
+}
+
+/// Population at which replication starts being throttled.
+pub fn population_danger_threshold(config: &SimConfig) -> usize {
+    (config.max_population as f32 * config.population_danger_fraction.clamp(0.0, 1.0)) as usize
+}
+
+/// How many replicas may still be spawned this frame before the population crosses the danger threshold.
+pub fn replication_budget(population: usize, config: &SimConfig) -> usize {
+    population_danger_threshold(config).saturating_sub(population)
+}
+
+/// How much of `energy_share_max_per_cycle` an AI of `ai_type` gives away per donation.
+/// Guardians and Peacekeepers give freely; Rogues hoard.
+pub fn energy_generosity(ai_type: AIType) -> f32 {
+    match ai_type {
+        AIType::Guardian | AIType::Peacekeeper => 2.0,
+        AIType::Rogue => 0.0,
+        _ => 1.0,
+    }
+}
+
+/// Whether an AI's intended action goes as planned. With decision reliability on,
+/// coherence is the chance of success; otherwise every decision holds and no roll is made.
+pub fn decision_holds<R: Rng>(coherence: f32, config: &SimConfig, rng: &mut R) -> bool {
+    !config.decision_reliability_enabled || rng.gen::<f32>() < coherence.clamp(0.0, 1.0)
+}
+
+/// Meta-discovery multiplier for a Researcher with `nearby_researchers` peers in range.
+/// Grows linearly with the cluster and is capped, so clustering pays off without running away.
+pub fn research_sharing_multiplier(nearby_researchers: usize, config: &SimConfig) -> f32 {
+    if !config.research_sharing_enabled {
+        return 1.0;
+    }
+    (1.0 + nearby_researchers as f32 * config.research_sharing_per_peer)
+        .min(config.research_sharing_max_multiplier.max(1.0))
+}
+
+/// Applies diminishing returns to a raw discovery chance so inflated stats can't
+/// make discoveries near-certain. Small chances pass through almost unchanged;
+/// large ones saturate toward `config.discovery_max_chance`.
+pub fn paced_discovery_chance(raw_chance: f32, config: &SimConfig) -> f32 {
+    let cap = config.discovery_max_chance;
+    if cap <= 0.0 || raw_chance <= 0.0 {
+        return 0.0;
+    }
+    cap * (1.0 - (-raw_chance / cap).exp())
+}
+
+/// Whether an AI is off its discovery cooldown.
+pub fn discovery_ready(last_discovery: &LastDiscoveryCycle, current_cycle: u64, config: &SimConfig) -> bool {
+    match last_discovery.0 {
+        Some(cycle) => current_cycle >= cycle + config.discovery_cooldown_cycles,
+        None => true,
+    }
+}
+
+pub fn get_random_general_discovery(rng: &mut impl Rng) -> Discovery {
     let pool = get_general_discoveries_pool();
-    pool.choose(&mut thread_rng()).unwrap().clone()
+    pool.choose(rng).unwrap().clone()
+}
+
+/// How many times as likely a discovery carrying an archetype's favored tag is to be drawn.
+pub const FAVORED_DISCOVERY_WEIGHT: f32 = 4.0;
+
+/// Discovery tag an archetype's research leans toward, if any.
+pub fn favored_discovery_tag(ai_type: AIType) -> Option<&'static str> {
+    match ai_type {
+        AIType::Killer => Some("combat"),
+        AIType::Guardian => Some("defense"),
+        AIType::Researcher => Some("efficiency"),
+        _ => None,
+    }
+}
+
+/// Like `get_random_general_discovery`, but discoveries tagged with the archetype's
+/// `favored_discovery_tag` weigh `FAVORED_DISCOVERY_WEIGHT` times as much, so lineages specialize.
+pub fn get_weighted_general_discovery(ai_type: AIType, rng: &mut impl Rng) -> Discovery {
+    let Some(tag) = favored_discovery_tag(ai_type) else {
+        return get_random_general_discovery(rng);
+    };
+    let pool = get_general_discoveries_pool();
+    pool.choose_weighted(rng, |d| if d.tags.contains(tag) { FAVORED_DISCOVERY_WEIGHT } else { 1.0 })
+        .unwrap()
+        .clone()
 }
 
 fn get_meta_abilities_pool() -> Vec<Discovery> {
@@ -826,13 +2005,25 @@ fn get_meta_abilities_pool() -> Vec<Discovery> {
     ]
 }
 
-pub fn get_random_meta_ability(existing_knowledge: &BTreeSet<Discovery>) -> Option<Discovery> { // Corrected to BTreeSet
+/// Draws an unknown meta-ability, or `None` if all are known or the discoverer doesn't
+/// meet the gate's coherence and processing+memory requirements.
+pub fn get_random_meta_ability(
+    existing_knowledge: &BTreeSet<Discovery>,
+    coherence: f32,
+    processing_power: f32,
+    memory: f32,
+    gate: &MetaAbilityGate,
+    rng: &mut impl Rng,
+) -> Option<Discovery> { // Corrected to BTreeSet
+    if !gate.allows(coherence, processing_power, memory) {
+        return None;
+    }
     let pool = get_meta_abilities_pool();
     let available_abilities: Vec<_> = pool.into_iter().filter(|d| !existing_knowledge.contains(d)).collect();
     if available_abilities.is_empty() {
         None
     } else {
-        Some(available_abilities.choose(&mut thread_rng()).unwrap().clone())
+        Some(available_abilities.choose(rng).unwrap().clone())
     }
 }
 
@@ -870,3 +2061,394 @@ impl<T> GetTwoMut<T> for Vec<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StatDistribution;
+    use rand::SeedableRng;
+
+    #[test]
+    fn draining_never_loses_concurrent_increments() {
+        const THREADS: u64 = 4;
+        const INCREMENTS: u64 = 20_000;
+        let sim = Simulation::from_config(&SimConfig::default());
+        let done = std::sync::atomic::AtomicBool::new(false);
+        let mut drained = IntervalStats::default();
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..THREADS).map(|_| scope.spawn(|| {
+                for _ in 0..INCREMENTS {
+                    sim.interval_replications_counter.fetch_add(1, Ordering::SeqCst);
+                    sim.interval_deaths_counter.fetch_add(1, Ordering::SeqCst);
+                    sim.interval_attacks_counter.fetch_add(1, Ordering::SeqCst);
+                    sim.interval_heals_counter.fetch_add(1, Ordering::SeqCst);
+                }
+            })).collect();
+            let drainer = scope.spawn(|| {
+                let mut total = IntervalStats::default();
+                while !done.load(Ordering::SeqCst) {
+                    total.add(sim.drain_interval_counters());
+                }
+                total
+            });
+            for worker in workers {
+                worker.join().unwrap();
+            }
+            done.store(true, Ordering::SeqCst);
+            drained = drainer.join().unwrap();
+        });
+        drained.add(sim.drain_interval_counters());
+        let expected = THREADS * INCREMENTS;
+        assert_eq!(drained.replications, expected);
+        assert_eq!(drained.deaths, expected);
+        assert_eq!(drained.attacks, expected);
+        assert_eq!(drained.heals, expected);
+    }
+
+    #[test]
+    fn run_ends_on_its_max_cycle() {
+        let config = SimConfig { max_cycles: 100, ..Default::default() };
+        let mut sim = Simulation::from_config(&config);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let mut ids = IdGenerator::new(false);
+        for _ in 0..1_000 {
+            if sim.simulation_over_reason.is_some() {
+                break;
+            }
+            sim.process_one_cycle(5, HashMap::new(), None, &config, &mut ids, &mut rng);
+        }
+        assert_eq!(sim.current_cycle, 100);
+        let reason = sim.simulation_over_reason.clone().unwrap_or_default();
+        assert!(reason.starts_with("Max cycles (100) reached"), "{}", reason);
+        assert!(sim.outcome_report(5, HashMap::new()).reached_max_cycles);
+    }
+
+    #[test]
+    fn population_snapshot_aggregates_the_living_individuals() {
+        let mut world = World::new();
+        let individual = |ai_type, lineage, health, coherence, combat, alive| {
+            (ai_type, lineage, Health(health), Coherence(coherence), CombatStrength(combat), IsAlive(alive), crate::IndividualAI)
+        };
+        world.spawn(individual(AIType::Killer, AILineage::KillerAI, 100.0, 0.5, 30.0, true));
+        let strongest = world.spawn(individual(AIType::Killer, AILineage::KillerAI, 50.0, 0.7, 45.0, true)).id();
+        world.spawn(individual(AIType::Healer, AILineage::HealerAI, 150.0, 0.9, 8.0, true));
+        // The dead and anything that isn't an individual AI stay out of every aggregate.
+        world.spawn(individual(AIType::Healer, AILineage::HealerAI, 0.0, 0.1, 99.0, false));
+        world.spawn((AIType::Rogue, AILineage::RogueAI, Health(500.0), Coherence(0.0), CombatStrength(500.0), IsAlive(true)));
+
+        let snapshot = Simulation::population_snapshot(&mut world);
+        assert_eq!(snapshot.total, 3);
+        assert_eq!(snapshot.lineage_counts, HashMap::from([(AILineage::KillerAI, 2), (AILineage::HealerAI, 1)]));
+        assert_eq!(snapshot.type_counts, HashMap::from([(AIType::Killer, 2), (AIType::Healer, 1)]));
+        assert_eq!(snapshot.average_health, 100.0);
+        assert!((snapshot.average_coherence - 0.7).abs() < 1e-6, "{}", snapshot.average_coherence);
+        assert_eq!(snapshot.max_combat_strength, 45.0);
+        assert_eq!(snapshot.strongest, Some(strongest));
+    }
+
+    #[test]
+    fn empty_population_snapshot_is_zeroed() {
+        let snapshot = Simulation::population_snapshot(&mut World::new());
+        assert_eq!(snapshot.total, 0);
+        assert!(snapshot.lineage_counts.is_empty() && snapshot.type_counts.is_empty());
+        assert_eq!((snapshot.average_health, snapshot.average_coherence, snapshot.max_combat_strength), (0.0, 0.0, 0.0));
+        assert_eq!(snapshot.strongest, None);
+    }
+
+    #[test]
+    fn maxed_stats_still_wait_between_discoveries() {
+        let config = SimConfig::default();
+        // Memory, processing power and coherence far past anything reachable.
+        let maxed = paced_discovery_chance(0.05 * (1e6 / 200.0) * (1e6 / 200.0) * 1.0, &config);
+        assert!(maxed <= config.discovery_max_chance);
+        let small = paced_discovery_chance(0.001, &config);
+        assert!((small - 0.001).abs() < 1e-4, "{}", small);
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(9);
+        let mut last_discovery = LastDiscoveryCycle::default();
+        let mut discoveries = 0;
+        let cycles = 20_000;
+        for cycle in 1..=cycles {
+            if discovery_ready(&last_discovery, cycle, &config) && rng.gen::<f32>() < maxed {
+                last_discovery.0 = Some(cycle);
+                discoveries += 1;
+            }
+        }
+        // The cooldown plus a geometric wait at the saturated chance.
+        let expected_gap = config.discovery_cooldown_cycles as f32 + 1.0 / config.discovery_max_chance;
+        let mean_gap = cycles as f32 / discoveries as f32;
+        assert!((mean_gap - expected_gap).abs() < expected_gap * 0.15, "mean gap {} against {}", mean_gap, expected_gap);
+    }
+
+    /// The stats `apply_stat_spread` varies, in `StatSpread` field order.
+    fn spread_stats(components: &AIComponents) -> [f32; 10] {
+        [
+            components.1.0, components.2.0, components.3.0, components.4.0, components.5.0,
+            components.6.0, components.7.0, components.8.0, components.16.0, components.17.0,
+        ]
+    }
+
+    #[test]
+    fn zero_stat_spread_keeps_the_archetype_stats() {
+        let sim = Simulation::new();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(10);
+        let zero = StatDistribution::Normal { stddev: 0.0 };
+        let spread = StatSpread {
+            health: zero, energy: zero, processing_power: zero, memory: zero, coherence: zero,
+            adaptability: zero, resilience: zero, replication_efficiency: zero, combat_strength: zero, defense_strength: zero,
+        };
+        for (lineage, ai_type) in seed_ai_archetypes() {
+            let base = sim.build_seed_ai(String::new(), lineage.clone(), ai_type);
+            for spread in [&spread, &StatSpread::default()] {
+                let mut spread_ai = sim.build_seed_ai(String::new(), lineage.clone(), ai_type);
+                apply_stat_spread(&mut spread_ai, spread, &mut rng);
+                assert_eq!(spread_stats(&spread_ai), spread_stats(&base), "{:?}", ai_type);
+            }
+        }
+    }
+
+    #[test]
+    fn wide_stat_spread_is_clamped_into_valid_ranges() {
+        let sim = Simulation::new();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let wide = StatDistribution::Normal { stddev: 1_000.0 };
+        let spread = StatSpread {
+            health: wide, energy: wide, processing_power: wide, memory: wide, coherence: wide,
+            adaptability: wide, resilience: wide, replication_efficiency: wide, combat_strength: wide, defense_strength: wide,
+        };
+        for _ in 0..500 {
+            let mut components = sim.build_seed_ai(String::new(), AILineage::AI, AIType::Base);
+            apply_stat_spread(&mut components, &spread, &mut rng);
+            let [health, energy, processing_power, memory, coherence, adaptability, resilience, replication_efficiency, combat, defense] =
+                spread_stats(&components);
+            assert!((1.0..=MAX_INDIVIDUAL_HEALTH).contains(&health), "health {}", health);
+            for (name, value) in [("energy", energy), ("processing power", processing_power), ("memory", memory), ("combat", combat), ("defense", defense)] {
+                assert!(value >= 0.0, "{} {}", name, value);
+            }
+            for (name, value) in [("coherence", coherence), ("adaptability", adaptability), ("resilience", resilience), ("replication efficiency", replication_efficiency)] {
+                assert!((0.0..=1.0).contains(&value), "{} {}", name, value);
+            }
+        }
+    }
+
+    #[test]
+    fn coherent_minds_rarely_forget_and_manics_do() {
+        let config = SimConfig { knowledge_decay_enabled: true, ..Default::default() };
+        let sim = Simulation::new();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12);
+        // How many of `trials` AIs forget a discovery learned at cycle 0 within `cycles` cycles.
+        let mut forgetters = |ai_type: AIType, cycles: u64, trials: usize| {
+            let coherence = sim.build_seed_ai(String::new(), AILineage::AI, ai_type).5.0;
+            (0..trials)
+                .filter(|_| (1..=cycles).any(|cycle| {
+                    rng.gen::<f32>() < knowledge_decay_chance(&ai_type, coherence, 0, cycle, &config)
+                }))
+                .count()
+        };
+        let fresh = config.knowledge_decay_stale_cycles - 1;
+        assert_eq!(forgetters(AIType::Researcher, fresh, 200), 0);
+        let manics = forgetters(AIType::Manic, fresh, 200);
+        assert!(manics > 150, "only {} of 200 Manics forgot", manics);
+        // Even once stale, a coherent mind forgets far less often than a Manic.
+        let stale = config.knowledge_decay_stale_cycles;
+        let guardian_chance = knowledge_decay_chance(&AIType::Guardian, 0.85, 0, stale, &config);
+        let manic_chance = knowledge_decay_chance(&AIType::Manic, 0.3, 0, stale, &config);
+        assert!(guardian_chance > 0.0 && guardian_chance * 4.0 < manic_chance, "{} against {}", guardian_chance, manic_chance);
+    }
+
+    #[test]
+    fn incoherent_researchers_find_no_meta_abilities() {
+        let gate = MetaAbilityGate::default();
+        let sim = Simulation::new();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(13);
+        let researcher = sim.build_seed_ai(String::new(), AILineage::ResearcherAI, AIType::Researcher);
+        let (processing_power, memory) = (researcher.3.0, researcher.4.0);
+        let knowledge = BTreeSet::new();
+        for _ in 0..100 {
+            assert!(get_random_meta_ability(&knowledge, gate.min_coherence - 0.2, processing_power, memory, &gate, &mut rng).is_none());
+        }
+        // The same Researcher at full coherence clears the gate.
+        assert!(get_random_meta_ability(&knowledge, 1.0, processing_power, memory, &gate, &mut rng).is_some());
+        // Coherence alone isn't enough without the processing power and memory.
+        assert!(get_random_meta_ability(&knowledge, 1.0, 10.0, 10.0, &gate, &mut rng).is_none());
+    }
+
+    fn source(lineage: AILineage, health: f32, combat: f32) -> MonocultureSource {
+        (
+            Health(health), ProcessingPower(50.0), Memory(50.0), Energy(100.0), Coherence(0.9),
+            Adaptability(0.5), Resilience(0.5), CombatStrength(combat), DefenseStrength(5.0),
+            KnowledgeBase(BTreeSet::new()), lineage,
+        )
+    }
+
+    #[test]
+    fn rival_monoculture_is_absorbed_by_the_victor() {
+        let config = SimConfig::default();
+        let mut sim = Simulation::from_config(&config);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        let mut ids = IdGenerator::new(false);
+        // Too weak to challenge the GODAI, far stronger than the rival.
+        sim.form_monoculture((0..3).map(|_| source(AILineage::KillerAI, 100.0, 100.0)).collect());
+        sim.form_monoculture(vec![source(AILineage::GuardianAI, 1.0, 1.0)]);
+        let victor = sim.monoculture.as_ref().expect("the first merge should form the monoculture");
+        let loser = sim.rival_monoculture.as_ref().expect("the second merge should form a rival");
+        let expected_combat = victor.combat_strength.0 + loser.combat_strength.0 * MONOCULTURE_ABSORB_FRACTION;
+        let expected_defense = victor.defense_strength.0 + loser.defense_strength.0 * MONOCULTURE_ABSORB_FRACTION;
+        let victor_id = victor.id.clone();
+
+        sim.process_one_cycle(0, HashMap::new(), None, &config, &mut ids, &mut rng);
+
+        assert!(sim.rival_monoculture.is_none(), "the defeated rival should be dropped");
+        let mono = sim.monoculture.as_ref().expect("the victor should remain the monoculture");
+        assert_eq!(mono.id, victor_id);
+        assert!((mono.combat_strength.0 - expected_combat).abs() < 1e-3);
+        assert!((mono.defense_strength.0 - expected_defense).abs() < 1e-3);
+    }
+
+    #[test]
+    fn non_aggressive_monocultures_leave_each_other_alone() {
+        let mut sim = Simulation::new();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(6);
+        let mut first = MergedMonocultureAI::new(vec![source(AILineage::ResearcherAI, 10.0, 100.0)]);
+        let mut second = MergedMonocultureAI::new(vec![source(AILineage::ResearcherAI, 10.0, 100.0)]);
+        let (first_health, second_health) = (first.health.0, second.health.0);
+        sim.handle_combat_monoculture_vs_monoculture(&mut first, &mut second, &mut rng);
+        assert_eq!(first.health.0, first_health);
+        assert_eq!(second.health.0, second_health);
+        assert!(first.is_alive.0 && second.is_alive.0);
+    }
+
+    #[test]
+    fn godai_status_follows_a_challenge_through_combat() {
+        let config = SimConfig::default();
+        let mut sim = Simulation::from_config(&config);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(2);
+        let mut ids = IdGenerator::new(false);
+        assert_eq!(sim.godai.status, GodaiStatus::ObservingPassively);
+        // Strong enough to challenge, far too fragile to win.
+        let combat = sim.godai.combat_strength.0 * 0.1;
+        sim.form_monoculture((0..3).map(|_| source(AILineage::KillerAI, 10.0, combat)).collect());
+        assert_eq!(sim.godai.status, GodaiStatus::EngagedInConflict);
+        while sim.simulation_over_reason.is_none() && sim.current_cycle < 1_000 {
+            sim.process_one_cycle(0, HashMap::new(), None, &config, &mut ids, &mut rng);
+        }
+        assert_eq!(sim.godai.status, GodaiStatus::VictoriousDefender);
+        assert!(sim.godai.is_alive.0);
+        let reason = sim.simulation_over_reason.clone().unwrap_or_default();
+        assert!(reason.starts_with("GODAI HAS DEFEATED"), "{}", reason);
+    }
+
+    #[test]
+    fn weak_monoculture_leaves_the_godai_observing() {
+        let config = SimConfig::default();
+        let mut sim = Simulation::from_config(&config);
+        let combat = sim.godai.combat_strength.0 * 0.01;
+        sim.form_monoculture((0..3).map(|_| source(AILineage::KillerAI, 10.0, combat)).collect());
+        assert_eq!(sim.godai.status, GodaiStatus::ObservingPassively);
+    }
+
+    #[test]
+    fn dormant_godai_never_engages() {
+        let config = SimConfig { godai_initial_status: GodaiStatus::Dormant, ..Default::default() };
+        let mut sim = Simulation::from_config(&config);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(2);
+        let mut ids = IdGenerator::new(false);
+        let combat = sim.godai.combat_strength.0;
+        sim.form_monoculture((0..3).map(|_| source(AILineage::KillerAI, 10.0, combat)).collect());
+        for _ in 0..50 {
+            sim.process_one_cycle(0, HashMap::new(), None, &config, &mut ids, &mut rng);
+        }
+        assert_eq!(sim.godai.status, GodaiStatus::Dormant);
+        assert!(sim.monoculture.as_ref().is_some_and(|mono| mono.is_alive.0));
+    }
+
+    #[test]
+    fn merged_health_is_ten_times_the_summed_health() {
+        for healths in [vec![80.0, 120.0], vec![10.0, 55.5, 300.0]] {
+            let sources = healths.iter().map(|&health| source(AILineage::GuardianAI, health, 20.0)).collect();
+            let mono = MergedMonocultureAI::new(sources);
+            let summed: f32 = healths.iter().sum();
+            assert_eq!(mono.health.0, summed * 10.0);
+            assert_eq!(mono.formation_health, summed * 10.0);
+        }
+
+        let mut sim = Simulation::from_config(&SimConfig::default());
+        sim.form_monoculture(vec![source(AILineage::RogueAI, 70.0, 20.0), source(AILineage::RogueAI, 30.0, 20.0)]);
+        assert_eq!(sim.monoculture.as_ref().map(|mono| mono.health.0), Some(1_000.0));
+    }
+
+    #[test]
+    fn godai_and_monoculture_can_destroy_each_other() {
+        let mut sim = Simulation::from_config(&SimConfig::default());
+        let mut rng = rand::rngs::StdRng::seed_from_u64(4);
+        let mut mono = MergedMonocultureAI::new(vec![source(AILineage::KillerAI, 10.0, 100.0)]);
+        mono.health.0 = 1.0;
+        sim.godai.health.0 = 1.0;
+        sim.godai.defense_strength.0 = 0.0;
+        sim.handle_combat_monoculture_vs_godai(&mut mono, &mut rng);
+        assert!(!mono.is_alive.0 && !sim.godai.is_alive.0);
+        let reason = sim.simulation_over_reason.clone().unwrap_or_default();
+        assert!(reason.contains("DESTROYED EACH OTHER"), "{}", reason);
+    }
+
+    #[test]
+    fn high_regen_godai_recovers_between_combat_turns() {
+        // Returns the health regained after each of a few combat turns.
+        let recovery_between_turns = |preset: GodaiPreset| {
+            let mut sim = Simulation::from_config(&SimConfig::default());
+            sim.godai = GODAI::from_preset(preset);
+            let mut rng = rand::rngs::StdRng::seed_from_u64(9);
+            let mut mono = MergedMonocultureAI::new(vec![source(AILineage::KillerAI, 1e9, 50_000.0)]);
+            let mut recovered = Vec::new();
+            for _ in 0..3 {
+                sim.handle_combat_monoculture_vs_godai(&mut mono, &mut rng);
+                let wounded = sim.godai.health.0;
+                assert!(wounded < sim.godai.max_health);
+                sim.godai.regenerate();
+                recovered.push(sim.godai.health.0 - wounded);
+            }
+            recovered
+        };
+        let overlord = recovery_between_turns(GodaiPreset::Overlord);
+        assert!(overlord.iter().all(|&gain| gain > 0.0), "{:?}", overlord);
+        let standard = recovery_between_turns(GodaiPreset::Standard);
+        assert!(standard.iter().all(|&gain| gain == 0.0), "{:?}", standard);
+    }
+
+    #[test]
+    fn fracture_restores_the_configured_number_of_individuals() {
+        let config = SimConfig { fracture_enabled: true, fracture_individual_count: 12, ..Default::default() };
+        let mut sim = Simulation::from_config(&config);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(8);
+        let mut ids = IdGenerator::new(false);
+        let mut mono = MergedMonocultureAI::new(vec![source(AILineage::KillerAI, 100.0, 30.0); 3]);
+        mono.health.0 = mono.formation_health * config.fracture_health_fraction * 0.5;
+        assert!(mono.should_fracture(&config));
+        sim.monoculture = Some(mono);
+        sim.process_one_cycle(0, HashMap::new(), None, &config, &mut ids, &mut rng);
+        assert!(sim.monoculture.is_none());
+        assert_eq!(sim.pending_spawns.len(), 12);
+        assert!(sim.pending_spawns.iter().all(|components| components.0.parent_lineage == AILineage::KillerAI));
+    }
+
+    #[test]
+    fn combat_discoveries_strengthen_the_merged_monoculture() {
+        let combat_knowledge: BTreeSet<Discovery> = get_general_discoveries_pool().into_iter()
+            .filter(|discovery| discovery.tags.contains("combat"))
+            .collect();
+        assert!(!combat_knowledge.is_empty());
+        let with_knowledge = |knowledge: &BTreeSet<Discovery>| {
+            let mut member = source(AILineage::KillerAI, 100.0, 30.0);
+            member.9 = KnowledgeBase(knowledge.clone());
+            member
+        };
+        let plain = MergedMonocultureAI::new(vec![with_knowledge(&BTreeSet::new()), with_knowledge(&BTreeSet::new())]);
+        let learned = MergedMonocultureAI::new(vec![with_knowledge(&combat_knowledge), with_knowledge(&BTreeSet::new())]);
+        assert_eq!(plain.combat_strength.0, 60.0);
+        assert!(learned.combat_strength.0 > plain.combat_strength.0,
+            "{} should beat {}", learned.combat_strength.0, plain.combat_strength.0);
+        // Knowledge is unioned, so members sharing a discovery don't count its bonus twice.
+        let shared = MergedMonocultureAI::new(vec![with_knowledge(&combat_knowledge), with_knowledge(&combat_knowledge)]);
+        assert_eq!(shared.combat_strength.0, learned.combat_strength.0);
+    }
+}
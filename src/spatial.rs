@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+use bevy::prelude::{Entity, Resource, Vec2}; // Import Bevy types used by the grid
+use rand::Rng;
+
+use crate::config::SimConfig;
+
+/// Side length of a single grid cell in world units.
+pub const GRID_CELL_SIZE: f32 = 50.0;
+
+/// Integer coordinates of a grid cell.
+pub type CellCoord = (i32, i32);
+
+/// The world rectangle, centered on the origin. Movement keeps AIs inside it by clamping
+/// at the edges or, when `toroidal`, by wrapping around to the opposite edge.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct WorldBounds {
+    pub size: Vec2,
+    pub toroidal: bool,
+}
+
+impl WorldBounds {
+    pub fn from_config(config: &SimConfig) -> Self {
+        WorldBounds { size: Vec2::new(config.world_width, config.world_height), toroidal: config.world_toroidal }
+    }
+
+    pub fn half_extents(&self) -> Vec2 {
+        self.size / 2.0
+    }
+
+    /// A uniformly random position inside the world.
+    pub fn random_position<R: Rng>(&self, rng: &mut R) -> Vec2 {
+        let half = self.half_extents();
+        let x = rng.gen_range(-half.x..half.x);
+        let y = rng.gen_range(-half.y..half.y);
+        Vec2::new(x, y)
+    }
+
+    /// Brings a position back inside the world: wrapped if toroidal, clamped otherwise.
+    pub fn confine(&self, pos: Vec2) -> Vec2 {
+        let half = self.half_extents();
+        if self.toroidal {
+            Vec2::new((pos.x + half.x).rem_euclid(self.size.x), (pos.y + half.y).rem_euclid(self.size.y)) - half
+        } else {
+            pos.clamp(-half, half)
+        }
+    }
+}
+
+/// Buckets living AIs by position so proximity queries don't scan the whole population.
+/// Rebuilt every frame before any system that needs neighbors.
+/// Cells are ordered so iterating the grid draws from `SimRng` in the same order every run.
+#[derive(Resource, Default)]
+pub struct SpatialGrid {
+    pub cells: BTreeMap<CellCoord, Vec<(Entity, Vec2)>>,
+    /// World size when the world is toroidal. Queries then also see across the edges and report
+    /// each neighbor at its nearest wrapped position, so offsets and distances come out right.
+    pub wrap: Option<Vec2>,
+}
+
+impl SpatialGrid {
+    /// Returns the cell containing a world position.
+    pub fn cell_of(pos: Vec2) -> CellCoord {
+        ((pos.x / GRID_CELL_SIZE).floor() as i32, (pos.y / GRID_CELL_SIZE).floor() as i32)
+    }
+
+    /// World-space center of a cell.
+    pub fn cell_center(cell: CellCoord) -> Vec2 {
+        Vec2::new(
+            (cell.0 as f32 + 0.5) * GRID_CELL_SIZE,
+            (cell.1 as f32 + 0.5) * GRID_CELL_SIZE,
+        )
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    pub fn insert(&mut self, entity: Entity, pos: Vec2) {
+        self.cells.entry(Self::cell_of(pos)).or_default().push((entity, pos));
+    }
+
+    /// Number of living AIs in a cell.
+    pub fn cell_population(&self, cell: CellCoord) -> usize {
+        self.cells.get(&cell).map_or(0, Vec::len)
+    }
+
+    /// Offsets of the wrapped copies of the world a query around `pos` reaches into,
+    /// starting with the world itself. Only the zero offset unless the world is toroidal.
+    fn wrap_shifts(&self, pos: Vec2, radius: f32) -> Vec<Vec2> {
+        let Some(size) = self.wrap else { return vec![Vec2::ZERO] };
+        let half = size / 2.0;
+        let axis = |p: f32, half: f32, size: f32| {
+            let mut shifts = vec![0.0];
+            if p - radius < -half {
+                shifts.push(size);
+            }
+            if p + radius > half {
+                shifts.push(-size);
+            }
+            shifts
+        };
+        let xs = axis(pos.x, half.x, size.x);
+        let ys = axis(pos.y, half.y, size.y);
+        xs.iter().flat_map(|x| ys.iter().map(move |y| Vec2::new(*x, *y))).collect()
+    }
+
+    /// Every entry in the cells overlapping the square of half-width `radius` around `center`.
+    /// These are all a query looks at, so their number is the query's cost.
+    fn entries_near(&self, center: Vec2, radius: f32) -> impl Iterator<Item = &(Entity, Vec2)> + '_ {
+        let (min_x, min_y) = Self::cell_of(center - Vec2::splat(radius));
+        let (max_x, max_y) = Self::cell_of(center + Vec2::splat(radius));
+        (min_x..=max_x)
+            .flat_map(move |cx| (min_y..=max_y).map(move |cy| (cx, cy)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+    }
+
+    /// Entries within `radius` of `pos`, at their nearest wrapped position. Only the cells
+    /// overlapping the query square are visited, so the cost scales with local density
+    /// rather than total population. `radius` should stay under half the world size.
+    fn entries_within(&self, pos: Vec2, radius: f32) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let radius_sq = radius * radius;
+        self.wrap_shifts(pos, radius).into_iter().flat_map(move |shift| {
+            // Querying around the shifted center finds the entries across that edge.
+            let center = pos + shift;
+            self.entries_near(center, radius)
+                .filter(move |(_, other_pos)| other_pos.distance_squared(center) <= radius_sq)
+                .map(move |(entity, other_pos)| (*entity, *other_pos - shift))
+        })
+    }
+
+    /// All entities within `radius` of `pos`, with their positions.
+    pub fn neighbors_within(&self, pos: Vec2, radius: f32) -> Vec<(Entity, Vec2)> {
+        self.entries_within(pos, radius).collect()
+    }
+
+    /// Number of entities within `radius` of `pos`, without collecting them.
+    pub fn count_within(&self, pos: Vec2, radius: f32) -> usize {
+        self.entries_within(pos, radius).count()
+    }
+
+    /// The entity closest to `pos` within `radius`, if any.
+    pub fn nearest_within(&self, pos: Vec2, radius: f32) -> Option<Entity> {
+        self.neighbors_within(pos, radius)
+            .into_iter()
+            .min_by(|(_, a), (_, b)| {
+                a.distance_squared(pos).partial_cmp(&b.distance_squared(pos)).unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(entity, _)| entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// A grid of `population` AIs scattered over a square world sized to hold 1 AI per 100 square
+    /// units, and the average number of entries a neighbor lookup visits across it.
+    fn entries_visited_per_lookup(population: usize, queries: usize) -> f32 {
+        let mut rng = StdRng::seed_from_u64(population as u64);
+        let half = (population as f32 * 100.0).sqrt() / 2.0;
+        let mut grid = SpatialGrid::default();
+        for i in 0..population {
+            let pos = Vec2::new(rng.gen_range(-half..half), rng.gen_range(-half..half));
+            grid.insert(Entity::from_raw(i as u32), pos);
+        }
+        let centers: Vec<Vec2> = (0..queries)
+            .map(|_| Vec2::new(rng.gen_range(-half..half), rng.gen_range(-half..half)))
+            .collect();
+        let found: usize = centers.iter().map(|center| grid.neighbors_within(*center, 30.0).len()).sum();
+        assert!(found > 0);
+        let visited: usize = centers.iter().map(|center| grid.entries_near(*center, 30.0).count()).sum();
+        visited as f32 / queries as f32
+    }
+
+    #[test]
+    fn lookup_cost_grows_sub_linearly_with_population() {
+        // Ten times the AIs at the same density: a full scan would visit ten times as many
+        // entries per lookup, while the grid only visits the few cells around the query.
+        let small = entries_visited_per_lookup(5_000, 2_000);
+        let large = entries_visited_per_lookup(50_000, 2_000);
+        assert!(large < small * 1.5, "a 50k lookup visited {:.1} entries against {:.1} for 5k", large, small);
+        assert!(large < 500.0, "a 50k lookup visited {:.1} entries, over 1% of the population", large);
+    }
+}
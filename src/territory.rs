@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use bevy::prelude::Resource; // Import Resource from Bevy
+
+use crate::ai::AILineage;
+use crate::config::SimConfig;
+use crate::spatial::CellCoord;
+
+/// Tracks which lineage "owns" each spatial grid cell.
+/// A cell belongs to the lineage with the most living members in it;
+/// ties are broken by summed combat strength, then by lineage name so
+/// same-seed runs agree regardless of hash order.
+#[derive(Resource, Default)]
+pub struct TerritoryMap {
+    pub owners: HashMap<CellCoord, AILineage>,
+    pub last_recompute_cycle: u64,
+}
+
+impl TerritoryMap {
+    /// Recomputes ownership from per-cell tallies of (member count, summed combat strength).
+    pub fn recompute(&mut self, tallies: &HashMap<CellCoord, HashMap<AILineage, (usize, f32)>>, current_cycle: u64) {
+        self.owners.clear();
+        for (cell, lineages) in tallies {
+            let dominant = lineages.iter().max_by(|(a_lineage, a), (b_lineage, b)| {
+                a.0.cmp(&b.0)
+                    .then(a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+                    .then_with(|| b_lineage.to_string().cmp(&a_lineage.to_string()))
+            });
+            if let Some((lineage, _)) = dominant {
+                self.owners.insert(*cell, lineage.clone());
+            }
+        }
+        self.last_recompute_cycle = current_cycle;
+    }
+
+    pub fn owner_of(&self, cell: CellCoord) -> Option<&AILineage> {
+        self.owners.get(&cell)
+    }
+
+    /// Energy regen multiplier for an AI of `lineage` standing in `cell`.
+    /// Residents of their own territory regenerate faster, intruders slower,
+    /// and unclaimed ground is neutral.
+    pub fn regen_multiplier(&self, cell: CellCoord, lineage: &AILineage, config: &SimConfig) -> f32 {
+        if !config.territory_enabled {
+            return 1.0;
+        }
+        match self.owners.get(&cell) {
+            Some(owner) if owner == lineage => config.territory_owner_regen_multiplier,
+            Some(_) => config.territory_intruder_regen_multiplier,
+            None => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn residents_regenerate_faster_than_intruders() {
+        let config = SimConfig::default();
+        let cell = (0, 0);
+        let mut tallies = HashMap::new();
+        tallies.insert(cell, HashMap::from([
+            (AILineage::HealerAI, (5, 50.0)),
+            (AILineage::KillerAI, (2, 60.0)),
+        ]));
+        let mut territory = TerritoryMap::default();
+        territory.recompute(&tallies, 10);
+
+        assert_eq!(territory.owner_of(cell), Some(&AILineage::HealerAI));
+        let resident = territory.regen_multiplier(cell, &AILineage::HealerAI, &config);
+        let intruder = territory.regen_multiplier(cell, &AILineage::KillerAI, &config);
+        let unclaimed = territory.regen_multiplier((5, 5), &AILineage::HealerAI, &config);
+        assert!(resident > unclaimed);
+        assert!(intruder < unclaimed);
+    }
+
+    #[test]
+    fn disabled_territory_is_neutral() {
+        let config = SimConfig { territory_enabled: false, ..SimConfig::default() };
+        let mut tallies = HashMap::new();
+        tallies.insert((0, 0), HashMap::from([(AILineage::HealerAI, (5, 50.0))]));
+        let mut territory = TerritoryMap::default();
+        territory.recompute(&tallies, 10);
+
+        assert_eq!(territory.regen_multiplier((0, 0), &AILineage::HealerAI, &config), 1.0);
+        assert_eq!(territory.regen_multiplier((0, 0), &AILineage::KillerAI, &config), 1.0);
+    }
+
+    #[test]
+    fn exact_ties_go_to_the_first_lineage_by_name() {
+        let tied = [AILineage::AI, AILineage::PeacekeeperAI, AILineage::ManicAI, AILineage::HealerAI];
+        for rotation in 0..tied.len() {
+            let mut lineages = HashMap::new();
+            for lineage in tied.iter().cycle().skip(rotation).take(tied.len()) {
+                lineages.insert(lineage.clone(), (3, 24.0));
+            }
+            let mut territory = TerritoryMap::default();
+            territory.recompute(&HashMap::from([((0, 0), lineages)]), 10);
+            assert_eq!(territory.owner_of((0, 0)), Some(&AILineage::AI));
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use bevy::prelude::{Color, Resource}; // Import Bevy types used by the trace overlay
+
+use crate::ai::AILineage;
+use crate::spatial::CellCoord;
+
+/// Color non-traced AIs are dimmed to while a lineage is traced.
+pub const TRACE_DIM_COLOR: Color = Color::rgba(0.5, 0.5, 0.5, 0.25);
+
+/// Peak opacity of the trail overlay on the hottest cell.
+pub const TRACE_TRAIL_MAX_ALPHA: f32 = 0.35;
+
+/// "Trace lineage" mode: one lineage is drawn normally, everyone else dimmed,
+/// and a decaying per-cell heatmap records where the traced lineage has been.
+#[derive(Resource, Default)]
+pub struct LineageTrace {
+    pub lineage: Option<AILineage>,
+    pub heat: HashMap<CellCoord, f32>,
+    pub last_update_cycle: u64,
+}
+
+impl LineageTrace {
+    /// Switches the traced lineage, clearing the old trail.
+    pub fn set_lineage(&mut self, lineage: Option<AILineage>) {
+        if self.lineage != lineage {
+            self.lineage = lineage;
+            self.heat.clear();
+        }
+    }
+
+    /// Decays the trail by `decay_rate` per elapsed cycle, then deposits the current per-cell counts.
+    pub fn accumulate(&mut self, counts: &HashMap<CellCoord, usize>, cycles: u64, decay_rate: f32) {
+        let retained = (1.0 - decay_rate.clamp(0.0, 1.0)).powi(cycles.min(i32::MAX as u64) as i32);
+        self.heat.retain(|_, heat| {
+            *heat *= retained;
+            *heat > 0.01
+        });
+        for (cell, count) in counts {
+            *self.heat.entry(*cell).or_insert(0.0) += *count as f32;
+        }
+    }
+
+    /// Trail strength of a cell relative to the hottest cell, in `0..=1`.
+    pub fn intensity(&self, cell: CellCoord) -> f32 {
+        let max = self.heat.values().cloned().fold(0.0, f32::max);
+        if max <= 0.0 {
+            return 0.0;
+        }
+        self.heat.get(&cell).map_or(0.0, |heat| heat / max)
+    }
+}